@@ -0,0 +1,82 @@
+//! Benchmarks of event parsing. Run with
+//! `cargo bench --features testing --bench parse`.
+//!
+//! Only public API is used, so to get a baseline, copy this file to a
+//! checkout of the commit to compare against, add the `[[bench]]` section
+//! to its `Cargo.toml` and run it there.
+
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bspc_rs::events::{Event, Subscription};
+use bspc_rs::testing::{FakeBspwm, EVENTS};
+
+const NODE_GEOMETRY: &str =
+    "node_geometry 0x00200002 0x00200007 0x00400002 951x1048+6+26";
+
+fn bench(name: &str, iterations: usize, mut f: impl FnMut() -> usize) {
+    // Warm up
+    f();
+
+    let start = Instant::now();
+    let mut lines = 0;
+
+    for _ in 0..iterations {
+        lines += f();
+    }
+
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<24} {:>12.0} lines/s {:>8.0} ns/line",
+        name,
+        lines as f64 / elapsed.as_secs_f64(),
+        elapsed.as_nanos() as f64 / lines as f64,
+    );
+}
+
+fn parse_corpus() -> usize {
+    for line in EVENTS.lines() {
+        black_box(black_box(line).parse::<Event>().unwrap());
+    }
+
+    EVENTS.lines().count()
+}
+
+fn parse_node_geometry() -> usize {
+    black_box(black_box(NODE_GEOMETRY).parse::<Event>().unwrap());
+    1
+}
+
+fn subscriber_node_geometry(count: usize) -> usize {
+    let bspwm = FakeBspwm::start().unwrap();
+    let mut subscriber = bspwm
+        .client()
+        .subscribe(false, None, &[Subscription::NodeGeometry])
+        .unwrap();
+
+    bspwm.wait_for_subscribers(1, Duration::from_secs(5));
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..count {
+                bspwm.emit(NODE_GEOMETRY);
+            }
+        });
+
+        for event in subscriber.events().take(count) {
+            black_box(event.unwrap());
+        }
+    });
+
+    count
+}
+
+fn main() {
+    bench("parse_corpus", 20_000, parse_corpus);
+    bench("parse_node_geometry", 1_000_000, parse_node_geometry);
+    bench("subscriber_node_geometry", 5, || {
+        subscriber_node_geometry(100_000)
+    });
+}
@@ -1,9 +1,15 @@
 // This script creates borders around the windows, when there are more, then
 // one window on the current desktop
 
-use bspc_rs::events::{self, Event, NodeEvent, Subscription};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
+
+use bspc_rs::errors::ReplyError;
+use bspc_rs::events::{EventLoop, HandlerError};
 use bspc_rs::selectors::NodeSelector;
 use bspc_rs::settings;
+use bspc_rs::tree;
 use bspc_rs::Id;
 
 // Apps names, which you don't want to have border for
@@ -21,31 +27,68 @@ const WINDOWS_FILE: &str = "/tmp/bordered_windows";
 const BORDER_WIDTH: i32 = 2;
 
 fn main() {
-    let subscriptions = [Subscription::NodeAdd, Subscription::NodeRemove];
-    let mut subscriber =
-        events::subscribe(false, None, &subscriptions).unwrap();
+    let bordered = RefCell::new(HashSet::new());
+
+    EventLoop::new()
+        .on_node_add(|info| {
+            update_borders(&mut bordered.borrow_mut(), info.desktop_id)
+        })
+        .on_node_remove(|info| {
+            bordered.borrow_mut().remove(&info.node_id);
+            update_borders(&mut bordered.borrow_mut(), info.desktop_id)
+        })
+        .on_node_transfer(|info| {
+            let mut bordered = bordered.borrow_mut();
+            update_borders(&mut bordered, info.src_desktop_id)?;
+            update_borders(&mut bordered, info.dst_desktop_id)
+        })
+        .on_error(|err| eprintln!("{err}"))
+        .run()
+        .unwrap();
+}
 
-    for event in subscriber.events() {
-        match event.unwrap() {
-            Event::NodeEvent(event) => match event {
-                NodeEvent::NodeAdd(node_info) => {}
+/// Sets borders of windows on the desktop, depending on how many visible
+/// windows it has, and dumps bordered windows to `WINDOWS_FILE`.
+fn update_borders(
+    bordered: &mut HashSet<Id>,
+    desktop_id: Id,
+) -> Result<(), HandlerError> {
+    let Some(desktop) = tree::from_id_to_desktop(desktop_id)? else {
+        return Ok(());
+    };
 
-                NodeEvent::NodeRemove(node_info) => {}
+    let windows: Vec<_> = desktop.windows().filter(|x| !x.hidden).collect();
 
-                _ => unreachable!(),
-            },
+    for node in &windows {
+        let class = match &node.client {
+            Some(client) => client.class_name.as_str(),
+            None => continue,
+        };
 
-            _ => unreachable!(),
+        if IGNORE_APPS.contains(&class) {
+            set_border(node.id, 0)?;
+            bordered.remove(&node.id);
+        } else if SET_BORDER.contains(&class) && windows.len() > 1 {
+            set_border(node.id, BORDER_WIDTH)?;
+            bordered.insert(node.id);
+        } else if SET_BORDER.contains(&class) {
+            set_border(node.id, 0)?;
+            bordered.remove(&node.id);
         }
     }
+
+    let lines: Vec<_> =
+        bordered.iter().map(|id| format!("0x{id:08X}")).collect();
+    fs::write(WINDOWS_FILE, lines.join("\n"))?;
+
+    Ok(())
 }
 
-fn create_border(id: Id) {
+fn set_border(id: Id, width: i32) -> Result<(), ReplyError> {
     settings::set_border_width(
         None,
         None,
-        Some(NodeSelector(&id.to_string())),
-        BORDER_WIDTH,
+        Some(NodeSelector(&format!("0x{id:08X}"))),
+        width,
     )
-    .unwrap();
 }
@@ -0,0 +1,45 @@
+#!/bin/sh
+# Rewrites replies in src/testing/queries.txt with the ones of running bspwm.
+# It loads src/testing/state.json first, so selectors are evaluated against
+# the same state, that tests use.
+#
+# Usage: scripts/capture-queries.sh [FIXTURE]
+
+set -eu
+
+root=$(dirname "$0")/..
+fixture=${1:-$root/src/testing/queries.txt}
+tmp=$(mktemp)
+tab=$(printf '\t')
+
+bspc wm -l "$root/src/testing/state.json"
+
+while IFS= read -r line; do
+    case $line in
+        '' | '#'*)
+            printf '%s\n' "$line"
+            continue
+            ;;
+    esac
+
+    domain=$(printf '%s' "$line" | cut -f 1)
+    selector=$(printf '%s' "$line" | cut -f 2)
+
+    case $domain in
+        -N) flag=-n ;;
+        -D) flag=-d ;;
+        -M) flag=-m ;;
+        *) echo "Unknown domain in line: $line" >&2; exit 1 ;;
+    esac
+
+    # Query fails, when nothing matches, which is written as no ids
+    if reply=$(bspc query "$domain" "$flag" "$selector" 2>/dev/null); then
+        ids=$(printf '%s\n' "$reply" | paste -sd ' ' -)
+    else
+        ids=
+    fi
+
+    printf '%s%s%s%s%s\n' "$domain" "$tab" "$selector" "$tab" "$ids"
+done < "$fixture" > "$tmp"
+
+mv "$tmp" "$fixture"
@@ -0,0 +1,139 @@
+use std::fmt;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::{Instant, SystemTime};
+
+use futures_core::Stream;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader, Lines};
+
+use super::{AsyncTransport, BspcClient};
+use crate::errors::ReplyError;
+use crate::events::{
+    subscribe_request, Event, EventContext, ReceiveTime, Subscription,
+    Timestamped, Timestamps,
+};
+
+/// Stream of events, that were subscribed to with
+/// [`BspcClient::subscribe`]. It ends, when bspwm closes the connection.
+pub struct Subscriber {
+    lines: Lines<BufReader<Box<dyn AsyncRead + Send + Unpin>>>,
+    received_at: Option<(Instant, SystemTime)>,
+    context: EventContext,
+    strict: bool,
+}
+
+impl Subscriber {
+    /// Sets strict mode, in which unrecognised events are returned as errors
+    /// instead of [`Event::Unknown`]. It is disabled by default.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Returns `true`, if strict mode is enabled.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Returns stream, that stamps events with the time they were received
+    /// and their sequence number, see [`Timestamps`].
+    pub fn timestamped(self) -> Timestamps<Self> {
+        Timestamps::new(self)
+    }
+}
+
+impl fmt::Debug for Subscriber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Subscriber").finish_non_exhaustive()
+    }
+}
+
+impl Stream for Subscriber {
+    type Item = Result<Event, ReplyError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let line = match ready!(Pin::new(&mut self.lines).poll_next_line(cx)) {
+            Ok(Some(line)) => {
+                // Event is stamped before parsing, see `Timestamps`
+                self.received_at = Some((Instant::now(), SystemTime::now()));
+                line
+            }
+            Ok(None) => return Poll::Ready(None),
+            Err(e) => return Poll::Ready(Some(Err(From::from(e)))),
+        };
+
+        let strict = self.strict;
+        let event = self.context.parse(&line, strict);
+        Poll::Ready(Some(event.map_err(From::from)))
+    }
+}
+
+impl ReceiveTime for Subscriber {
+    fn received_at(&self) -> Option<(Instant, SystemTime)> {
+        self.received_at
+    }
+}
+
+impl<S, T> Stream for Timestamps<S>
+where
+    S: Stream<Item = Result<T, ReplyError>> + ReceiveTime + Unpin,
+{
+    type Item = Result<Timestamped<T>, ReplyError>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let timestamps = self.get_mut();
+        let event = ready!(Pin::new(timestamps.get_mut()).poll_next(cx));
+
+        Poll::Ready(event.map(|event| event.map(|x| timestamps.stamp(x))))
+    }
+}
+
+impl<T: AsyncTransport> BspcClient<T> {
+    /// Subscribes to the given events.
+    ///
+    /// FIFO mode isn't supported, so `fifo_flag` set to `true` results in
+    /// [`ReplyError::InvalidRequest`].
+    ///
+    /// If `count` is `Some(x)`, then it stops returning subscription events
+    /// after having received `x` events.
+    pub async fn subscribe(
+        &self,
+        fifo_flag: bool,
+        count: Option<u32>,
+        subscriptions: &[Subscription],
+    ) -> Result<Subscriber, ReplyError> {
+        if fifo_flag {
+            return Err(ReplyError::InvalidRequest(
+                "FIFO mode is not supported by async subscriber".to_string(),
+            ));
+        }
+
+        let mut context = EventContext::default();
+
+        if EventContext::needs_status_prefix(subscriptions) {
+            context.status_prefix = Some(self.get_status_prefix().await?);
+        }
+
+        if EventContext::needs_names(subscriptions) {
+            context.add_names(&self.get_current_state().await?);
+        }
+
+        let mut conn = self.connect().await?;
+        let subscribe_message =
+            subscribe_request(fifo_flag, count, subscriptions);
+
+        conn.write_all(subscribe_message.as_bytes()).await?;
+
+        Ok(Subscriber {
+            lines: BufReader::new(Box::new(conn) as Box<_>).lines(),
+            received_at: None,
+            context,
+            strict: false,
+        })
+    }
+}
@@ -0,0 +1,263 @@
+//! This module provides async API on top of [`tokio`], which mirrors the
+//! blocking one. It is available with `tokio` feature.
+//!
+//! All requests are methods of [`BspcClient`] and events are delivered by
+//! [`Subscriber`], which implements [`Stream`](futures_core::Stream).
+//! Replies and events are parsed by the same parsers, as in blocking API.
+//!
+//! ## Example
+//!
+//! ```rust, no_run
+//! use bspc_rs::asynchronous::BspcClient;
+//! use bspc_rs::events::{Event, NodeEvent, Subscription};
+//! use futures_util::StreamExt;
+//!
+//! # async fn run() {
+//! let client = BspcClient::new();
+//! let mut subscriber = client
+//!     .subscribe(false, None, &[Subscription::NodeFocus])
+//!     .await
+//!     .unwrap();
+//!
+//! while let Some(event) = subscriber.next().await {
+//!     if let Event::NodeEvent(NodeEvent::NodeFocus(info)) = event.unwrap() {
+//!         let state = client.get_current_state().await.unwrap();
+//!         println!("{} of {}", info.node_id, state.clients_count);
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::future::Future;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+use crate::errors::ReplyError;
+use crate::socket::{self, UnixSocketTransport};
+
+mod events;
+mod query;
+mod settings;
+mod state;
+
+pub use events::Subscriber;
+
+/// Opens connections to bspwm asynchronously. It's async counterpart of
+/// [`Transport`](crate::socket::Transport).
+pub trait AsyncTransport {
+    /// Connection, that is returned by [`AsyncTransport::connect`]. Events
+    /// are read from it after subscribing.
+    type Connection: AsyncRead + AsyncWrite + Unpin + Send + 'static;
+
+    /// Opens a new connection to bspwm.
+    fn connect(
+        &self,
+    ) -> impl Future<Output = Result<Self::Connection, ReplyError>> + Send;
+}
+
+impl AsyncTransport for UnixSocketTransport {
+    type Connection = UnixStream;
+
+    async fn connect(&self) -> Result<UnixStream, ReplyError> {
+        let socket_path = self.locator().locate()?;
+        let stream = UnixStream::connect(socket_path).await?;
+
+        Ok(stream)
+    }
+}
+
+/// Async client for communicating with bspwm. Like its blocking counterpart
+/// [`crate::client::BspcClient`], it opens connections with the transport it
+/// holds.
+#[derive(Debug, Clone, Default)]
+pub struct BspcClient<T: AsyncTransport = UnixSocketTransport> {
+    transport: T,
+}
+
+impl BspcClient {
+    /// Creates client, that locates bspwm socket the same way as `bspc`
+    /// does.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: AsyncTransport> BspcClient<T> {
+    /// Creates client, that uses given transport for connecting to bspwm.
+    pub fn with_transport(transport: T) -> Self {
+        Self { transport }
+    }
+
+    /// Returns transport, used by this client.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    async fn connect(&self) -> Result<T::Connection, ReplyError> {
+        self.transport.connect().await
+    }
+
+    async fn request(&self, message: &str) -> Result<Vec<String>, ReplyError> {
+        let mut conn = self.connect().await?;
+        conn.write_all(message.as_bytes()).await?;
+
+        let mut reply = Vec::new();
+        conn.read_to_end(&mut reply).await?;
+
+        socket::process_reply(reply)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use futures_util::StreamExt;
+    use tokio::io::DuplexStream;
+
+    use super::*;
+    use crate::events::{Event, NodeEvent, Subscription};
+    use crate::selectors::{DesktopSelector, NodeSelector};
+    use crate::testing::{FakeBspwm, Reply, STATE_DUMP};
+
+    #[tokio::test]
+    async fn query_nodes() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.on_request(
+            &["query", "--nodes", "--node", ".window"],
+            Reply::lines(["0x00400002", "0x00800002"]),
+        );
+
+        let nodes = bspwm
+            .async_client()
+            .query_nodes(None, None, None, Some(NodeSelector(".window")))
+            .await
+            .unwrap();
+
+        assert_eq!(nodes, vec![0x00400002, 0x00800002]);
+    }
+
+    #[tokio::test]
+    async fn settings() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let client = bspwm.async_client();
+        bspwm.set_config("split_ratio", "0.500000");
+
+        assert_eq!(client.get_split_ratio().await.unwrap(), 0.5);
+
+        client.set_border_width(None, None, None, 3).await.unwrap();
+        assert_eq!(bspwm.config("border_width"), Some("3".to_string()));
+
+        assert!(matches!(
+            client.get_single_monocle().await,
+            Err(ReplyError::RequestFailed(_))
+        ));
+    }
+
+    /// Transport, that records requests and doesn't reply anything.
+    #[derive(Debug, Clone, Default)]
+    struct FakeTransport {
+        sent: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl AsyncTransport for FakeTransport {
+        type Connection = DuplexStream;
+
+        async fn connect(&self) -> Result<DuplexStream, ReplyError> {
+            let (conn, mut server) = tokio::io::duplex(1024);
+            let sent = self.sent.clone();
+
+            tokio::spawn(async move {
+                let mut buf = vec![0; 1024];
+                let len = server.read(&mut buf).await.unwrap();
+                let message = String::from_utf8_lossy(&buf[..len]);
+
+                sent.lock().unwrap().push(message.into_owned());
+            });
+
+            Ok(conn)
+        }
+    }
+
+    #[tokio::test]
+    async fn settings_with_fake_transport() {
+        let client = BspcClient::with_transport(FakeTransport::default());
+
+        client.set_split_ratio(0.5).await.unwrap();
+        client
+            .set_window_gap(None, Some(DesktopSelector("^2")), 12)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *client.transport().sent.lock().unwrap(),
+            vec![
+                "config\x00split_ratio\x000.5\x00",
+                "config\x00--desktop\x00^2\x00window_gap\x0012\x00",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn current_state() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.set_state(STATE_DUMP);
+
+        let state = bspwm.async_client().get_current_state().await.unwrap();
+
+        assert_eq!(state.clients_count, 5);
+    }
+
+    #[tokio::test]
+    async fn subscribe() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .async_client()
+            .subscribe(false, None, &[Subscription::Node])
+            .await
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.emit("desktop_focus 0x00200002 0x00200007");
+        bspwm.emit("node_remove 0x00200002 0x00200007 0x00400002");
+        bspwm.close_subscribers();
+
+        let events: Vec<_> = subscriber.by_ref().collect().await;
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[0],
+            Ok(Event::NodeEvent(NodeEvent::NodeFocus(_)))
+        ));
+        assert!(matches!(
+            events[1],
+            Ok(Event::NodeEvent(NodeEvent::NodeRemove(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn subscribe_timestamped() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let subscriber = bspwm
+            .async_client()
+            .subscribe(false, None, &[Subscription::Node])
+            .await
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.emit("node_remove 0x00200002 0x00200007 0x00400002");
+        bspwm.close_subscribers();
+
+        let events: Vec<_> = subscriber.timestamped().collect().await;
+        let events: Vec<_> = events.into_iter().map(Result::unwrap).collect();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].seq, events[1].seq), (0, 1));
+        assert_eq!(events[1].name(), "node_remove");
+        assert!(events[0].instant <= events[1].instant);
+    }
+}
@@ -0,0 +1,94 @@
+use super::{AsyncTransport, BspcClient};
+use crate::errors::ReplyError;
+use crate::query::{
+    extract, parse_ids, parse_tree, query_request, tree_request,
+};
+use crate::selectors::{DesktopSelector, MonitorSelector, NodeSelector};
+use crate::tree::Tree;
+use crate::Id;
+
+impl<T: AsyncTransport> BspcClient<T> {
+    /// Returns ids of the nodes, that match certain criteria
+    pub async fn query_nodes(
+        &self,
+        selector: Option<NodeSelector<'_>>,
+        monitor_selector: Option<MonitorSelector<'_>>,
+        desktop_selector: Option<DesktopSelector<'_>>,
+        node_selector: Option<NodeSelector<'_>>,
+    ) -> Result<Vec<Id>, ReplyError> {
+        let request = query_request(
+            "nodes",
+            false,
+            extract(&selector)?,
+            extract(&monitor_selector)?,
+            extract(&desktop_selector)?,
+            extract(&node_selector)?,
+        )?;
+
+        parse_ids(&self.request(&request).await?)
+    }
+
+    /// Returns ids of the desktops, that match certain criteria
+    pub async fn query_desktops(
+        &self,
+        names_flag: bool,
+        selector: Option<DesktopSelector<'_>>,
+        monitor_selector: Option<MonitorSelector<'_>>,
+        desktop_selector: Option<DesktopSelector<'_>>,
+        node_selector: Option<NodeSelector<'_>>,
+    ) -> Result<Vec<Id>, ReplyError> {
+        let request = query_request(
+            "desktops",
+            names_flag,
+            extract(&selector)?,
+            extract(&monitor_selector)?,
+            extract(&desktop_selector)?,
+            extract(&node_selector)?,
+        )?;
+
+        parse_ids(&self.request(&request).await?)
+    }
+
+    /// Returns ids of monitors, that match certain criteria
+    pub async fn query_monitors(
+        &self,
+        names_flag: bool,
+        selector: Option<MonitorSelector<'_>>,
+        monitor_selector: Option<MonitorSelector<'_>>,
+        desktop_selector: Option<DesktopSelector<'_>>,
+        node_selector: Option<NodeSelector<'_>>,
+    ) -> Result<Vec<Id>, ReplyError> {
+        let request = query_request(
+            "monitors",
+            names_flag,
+            extract(&selector)?,
+            extract(&monitor_selector)?,
+            extract(&desktop_selector)?,
+            extract(&node_selector)?,
+        )?;
+
+        parse_ids(&self.request(&request).await?)
+    }
+
+    /// Returnes tree representation of the matching item
+    ///
+    /// Note: when more then one of the arguments are not `None`, then the
+    /// matching will give the result in this priority: Node, Desktop,
+    /// Monitor.
+    pub async fn query_tree(
+        &self,
+        monitor_selector: Option<MonitorSelector<'_>>,
+        desktop_selector: Option<DesktopSelector<'_>>,
+        node_selector: Option<NodeSelector<'_>>,
+    ) -> Result<Tree, ReplyError> {
+        let monitor_selector = extract(&monitor_selector)?;
+        let desktop_selector = extract(&desktop_selector)?;
+        let node_selector = extract(&node_selector)?;
+
+        let request =
+            tree_request(monitor_selector, desktop_selector, node_selector);
+        let reply = self.request(&request).await?;
+
+        parse_tree(&reply, monitor_selector, desktop_selector, node_selector)
+    }
+}
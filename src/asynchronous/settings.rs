@@ -0,0 +1,98 @@
+//! Async versions of the settings getters and setters. See
+//! [`crate::settings`] for their blocking counterparts.
+
+use std::string::ToString;
+
+use super::{AsyncTransport, BspcClient};
+use crate::errors::ReplyError;
+use crate::selectors::{self, DesktopSelector, MonitorSelector, NodeSelector};
+use crate::settings::{
+    for_each_selected_setting, for_each_setting, parse_setting,
+    process_get_reply, process_set_reply, ClickToFocus, PointerAction,
+    PointerModifier, Polarity, Scheme, Tightness,
+};
+
+impl<T: AsyncTransport> BspcClient<T> {
+    async fn get_config_property(
+        &self,
+        property: &str,
+    ) -> Result<String, ReplyError> {
+        let reply =
+            self.request(&format!("config\x00{}\x00", property)).await?;
+
+        Ok(process_get_reply(reply))
+    }
+
+    async fn set_config_property(
+        &self,
+        property: &str,
+        value: &str,
+    ) -> Result<(), ReplyError> {
+        let reply = self
+            .request(&format!("config\x00{}\x00{}\x00", property, value))
+            .await;
+
+        process_set_reply(reply)
+    }
+}
+
+macro_rules! impl_settings {
+    ($($name:ident: $type:ty => $getter:ident, $setter:ident;)*) => {
+        impl<T: AsyncTransport> BspcClient<T> {
+            $(
+                pub async fn $getter(&self) -> Result<$type, ReplyError> {
+                    let value =
+                        self.get_config_property(stringify!($name)).await?;
+
+                    parse_setting(value)
+                }
+
+                pub async fn $setter(
+                    &self,
+                    value: $type,
+                ) -> Result<(), ReplyError> {
+                    self.set_config_property(
+                        stringify!($name),
+                        &value.to_string(),
+                    )
+                    .await
+                }
+            )*
+        }
+    };
+}
+
+macro_rules! impl_selected_settings {
+    ($(
+        $(#[$attr:meta])*
+        $setter:ident($($node_selector:ident)?) => $name:ident;
+    )*) => {
+        impl<T: AsyncTransport> BspcClient<T> {
+            $(
+                $(#[$attr])*
+                pub async fn $setter(
+                    &self,
+                    monitor_selector: Option<MonitorSelector<'_>>,
+                    desktop_selector: Option<DesktopSelector<'_>>,
+                    $($node_selector: Option<NodeSelector<'_>>,)?
+                    $name: i32,
+                ) -> Result<(), ReplyError> {
+                    let selectors = selectors::collect(
+                        monitor_selector,
+                        desktop_selector,
+                        None $(.or($node_selector))?,
+                    );
+
+                    self.set_config_property(
+                        &format!("{}{}", selectors, stringify!($name)),
+                        &$name.to_string(),
+                    )
+                    .await
+                }
+            )*
+        }
+    };
+}
+
+for_each_setting!(impl_settings);
+for_each_selected_setting!(impl_selected_settings);
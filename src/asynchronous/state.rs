@@ -0,0 +1,23 @@
+use super::{AsyncTransport, BspcClient};
+use crate::errors::ReplyError;
+use crate::events::ReportInfo;
+use crate::state::State;
+
+impl<T: AsyncTransport> BspcClient<T> {
+    /// Returns a dump of the current bspwm state <br>
+    /// Contains all monitors with their desktops (and window respectively)
+    pub async fn get_current_state(&self) -> Result<State, ReplyError> {
+        let reply = self.request("wm\x00-d\x00").await?;
+        let state: State = serde_json::from_str(&reply[0])?;
+        Ok(state)
+    }
+
+    /// Returns current status report, the same one, that is sent to
+    /// subscribers of `report` events.
+    pub async fn get_status(&self) -> Result<ReportInfo, ReplyError> {
+        let status_prefix = self.get_status_prefix().await?;
+        let reply = self.request("wm\x00--get-status\x00").await?;
+        let report = ReportInfo::parse_with_prefix(&reply[0], &status_prefix)?;
+        Ok(report)
+    }
+}
@@ -0,0 +1,171 @@
+//! This module contains [`BspcClient`], which is the main entry point for
+//! communicating with bspwm.
+//!
+//! Client holds the [`Transport`], that is used for opening connections to
+//! bspwm, so you can point it to a non-default socket or replace it
+//! completely. All queries, settings, state dumps and subscriptions are
+//! available as methods on it. Free functions in other modules are just
+//! wrappers around the default client.
+//!
+//! ## Example
+//!
+//! ```rust, no_run
+//! use bspc_rs::client::BspcClient;
+//! use bspc_rs::socket::UnixSocketTransport;
+//!
+//! let transport = UnixSocketTransport::with_path("/tmp/bspwm_0_0-socket");
+//! let client = BspcClient::with_transport(transport);
+//!
+//! println!("{}", client.get_split_ratio().unwrap());
+//! ```
+
+use crate::errors::ReplyError;
+use crate::socket::{Transport, UnixSocketTransport};
+
+#[derive(Debug, Clone, Default)]
+pub struct BspcClient<T: Transport = UnixSocketTransport> {
+    transport: T,
+}
+
+impl BspcClient {
+    /// Creates client, that locates bspwm socket the same way as `bspc`
+    /// does.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Transport> BspcClient<T> {
+    /// Creates client, that uses given transport for connecting to bspwm.
+    pub fn with_transport(transport: T) -> Self {
+        Self { transport }
+    }
+
+    /// Returns transport, used by this client.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub(crate) fn connect(&self) -> Result<T::Connection, ReplyError> {
+        self.transport.connect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Read};
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::selectors::{DesktopSelector, MonitorSelector, NodeSelector};
+    use crate::socket::BspcCommunication;
+
+    #[derive(Clone, Default)]
+    struct FakeTransport {
+        sent: Arc<Mutex<Vec<String>>>,
+        reply: Vec<String>,
+    }
+
+    struct FakeConnection {
+        transport: FakeTransport,
+    }
+
+    impl Transport for FakeTransport {
+        type Connection = FakeConnection;
+
+        fn connect(&self) -> Result<FakeConnection, ReplyError> {
+            Ok(FakeConnection {
+                transport: self.clone(),
+            })
+        }
+    }
+
+    impl BspcCommunication for FakeConnection {
+        fn send_message(&mut self, message: &str) -> io::Result<()> {
+            self.transport
+                .sent
+                .lock()
+                .unwrap()
+                .push(message.to_string());
+            Ok(())
+        }
+
+        fn receive_message(&mut self) -> Result<Vec<String>, ReplyError> {
+            // Like the socket, empty reply means bspwm didn't reply anything
+            if self.transport.reply.is_empty() {
+                return Err(ReplyError::NoReply);
+            }
+
+            Ok(self.transport.reply.clone())
+        }
+    }
+
+    impl Read for FakeConnection {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn query_with_fake_transport() {
+        let transport = FakeTransport {
+            reply: vec!["0x00400002".to_string(), "0x00600002".to_string()],
+            ..Default::default()
+        };
+        let client = BspcClient::with_transport(transport);
+
+        let ids = client
+            .query_nodes(None, None, None, Some(NodeSelector(".window")))
+            .unwrap();
+
+        assert_eq!(ids, vec![0x00400002, 0x00600002]);
+        assert_eq!(
+            *client.transport().sent.lock().unwrap(),
+            vec!["query\x00--nodes\x00--node\x00.window\x00"]
+        );
+    }
+
+    #[test]
+    fn get_setting_with_fake_transport() {
+        let transport = FakeTransport {
+            reply: vec!["0.520000".to_string()],
+            ..Default::default()
+        };
+        let client = BspcClient::with_transport(transport);
+
+        assert_eq!(client.get_split_ratio().unwrap(), 0.52);
+        assert_eq!(
+            *client.transport().sent.lock().unwrap(),
+            vec!["config\x00split_ratio\x00"]
+        );
+    }
+
+    #[test]
+    fn set_settings_with_fake_transport() {
+        let client = BspcClient::with_transport(FakeTransport::default());
+        let monitor = || Some(MonitorSelector("HDMI1"));
+        let desktop = || Some(DesktopSelector("^2"));
+
+        client
+            .set_border_width(None, None, Some(NodeSelector("0x00400002")), 2)
+            .unwrap();
+        client.set_window_gap(None, desktop(), 12).unwrap();
+        client.top_padding(monitor(), None, 20).unwrap();
+        client.right_padding(monitor(), desktop(), 0).unwrap();
+        client.bottom_padding(None, None, -4).unwrap();
+        client.left_padding(None, desktop(), 8).unwrap();
+
+        assert_eq!(
+            *client.transport().sent.lock().unwrap(),
+            vec![
+                "config\x00--node\x000x00400002\x00border_width\x002\x00",
+                "config\x00--desktop\x00^2\x00window_gap\x0012\x00",
+                "config\x00--monitor\x00HDMI1\x00top_padding\x0020\x00",
+                "config\x00--monitor\x00HDMI1\x00--desktop\x00^2\x00\
+                 right_padding\x000\x00",
+                "config\x00bottom_padding\x00-4\x00",
+                "config\x00--desktop\x00^2\x00left_padding\x008\x00",
+            ]
+        );
+    }
+}
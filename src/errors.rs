@@ -30,10 +30,44 @@ impl fmt::Display for QueryError {
     }
 }
 
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SocketError {
+    DisplayNotSet,
+    InvalidDisplay(String),
+    SearchFailed(io::Error),
+}
+
+impl Error for SocketError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SocketError::SearchFailed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SocketError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SocketError::DisplayNotSet => {
+                write!(f, "Neither $BSPWM_SOCKET nor $DISPLAY variable is set")
+            }
+            SocketError::InvalidDisplay(display) => {
+                write!(f, "Can't parse $DISPLAY variable: '{}'", display)
+            }
+            SocketError::SearchFailed(err) => {
+                write!(f, "Failed to search for bspwm sockets: {}", err)
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum ReplyError {
     ConnectionError(io::Error),
+    SocketError(SocketError),
     ParseError(ParseError),
     QueryError(QueryError),
     InvalidRequest(String),
@@ -42,12 +76,23 @@ pub enum ReplyError {
     InvalidSelector(String),
 }
 
-impl Error for ReplyError {}
+impl Error for ReplyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplyError::ConnectionError(err) => Some(err),
+            ReplyError::SocketError(err) => Some(err),
+            ReplyError::ParseError(err) => Some(err),
+            ReplyError::QueryError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for ReplyError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ReplyError::ConnectionError(err) => err.fmt(f),
+            ReplyError::SocketError(err) => err.fmt(f),
             ReplyError::ParseError(err) => err.fmt(f),
             ReplyError::InvalidRequest(err) => write!(f, "{}", err),
             ReplyError::QueryError(err) => err.fmt(f),
@@ -66,6 +111,12 @@ impl From<io::Error> for ReplyError {
     }
 }
 
+impl From<SocketError> for ReplyError {
+    fn from(error: SocketError) -> ReplyError {
+        ReplyError::SocketError(error)
+    }
+}
+
 impl<T: Into<ParseError>> From<T> for ReplyError {
     fn from(error: T) -> ReplyError {
         ReplyError::ParseError(Into::into(error))
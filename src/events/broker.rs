@@ -0,0 +1,619 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::mpsc::{RecvError, RecvTimeoutError, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::{Event, ShutdownHandle, Subscriber, Subscription};
+use crate::client::BspcClient;
+use crate::errors::ReplyError;
+use crate::socket::{Transport, UnixSocketTransport};
+
+/// What [`EventBroker`] does, when receiver is full, because consumer
+/// doesn't keep up with events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Drops the oldest event in the receiver to make room for the new one.
+    DropOldest,
+
+    /// Waits, until consumer makes room. This delays events for all
+    /// consumers.
+    Block,
+
+    /// Disconnects receiver. Consumer receives events, that are already in
+    /// the receiver, and then [`RecvError`].
+    Disconnect,
+}
+
+#[derive(Debug)]
+struct ChannelState {
+    queue: VecDeque<Event>,
+    capacity: usize,
+    backpressure: Backpressure,
+    is_disconnected: bool,
+    is_receiver_dropped: bool,
+    dropped: u64,
+}
+
+#[derive(Debug)]
+struct Channel {
+    state: Mutex<ChannelState>,
+    available: Condvar,
+    space: Condvar,
+}
+
+impl Channel {
+    fn lock(&self) -> MutexGuard<'_, ChannelState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Sends event to the receiver according to its backpressure policy.
+    /// Returns `false`, if receiver is gone or broker is shut down, while
+    /// waiting for room.
+    fn send(&self, event: Event, shutdown: &ShutdownHandle) -> bool {
+        let mut state = self.lock();
+
+        if state.is_receiver_dropped || state.is_disconnected {
+            return false;
+        }
+
+        if state.queue.len() >= state.capacity {
+            match state.backpressure {
+                Backpressure::DropOldest => {
+                    state.queue.pop_front();
+                    state.dropped += 1;
+                }
+
+                Backpressure::Block => {
+                    while state.queue.len() >= state.capacity
+                        && !state.is_receiver_dropped
+                        && !shutdown.is_shutdown()
+                    {
+                        // Wakes up periodically to notice shutdown
+                        state = self
+                            .space
+                            .wait_timeout(state, Duration::from_millis(50))
+                            .unwrap()
+                            .0;
+                    }
+
+                    if state.is_receiver_dropped || shutdown.is_shutdown() {
+                        return false;
+                    }
+                }
+
+                Backpressure::Disconnect => {
+                    state.is_disconnected = true;
+                    self.available.notify_all();
+                    return false;
+                }
+            }
+        }
+
+        state.queue.push_back(event);
+        self.available.notify_one();
+
+        true
+    }
+
+    fn disconnect(&self) {
+        self.lock().is_disconnected = true;
+        self.available.notify_all();
+    }
+}
+
+/// Receiver of events from [`EventBroker`]. It has the same interface as
+/// [`std::sync::mpsc::Receiver`] and is disconnected, when the broker stops.
+pub struct EventReceiver {
+    channel: Arc<Channel>,
+}
+
+impl fmt::Debug for EventReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventReceiver").finish_non_exhaustive()
+    }
+}
+
+impl EventReceiver {
+    /// Blocks, until event is received. Returns error, if receiver was
+    /// disconnected and there are no more events.
+    pub fn recv(&self) -> Result<Event, RecvError> {
+        let mut state = self.channel.lock();
+
+        loop {
+            if let Some(event) = state.queue.pop_front() {
+                self.channel.space.notify_one();
+                return Ok(event);
+            }
+
+            if state.is_disconnected {
+                return Err(RecvError);
+            }
+
+            state = self.channel.available.wait(state).unwrap();
+        }
+    }
+
+    /// Returns event, if there is one, without blocking.
+    pub fn try_recv(&self) -> Result<Event, TryRecvError> {
+        let mut state = self.channel.lock();
+
+        match state.queue.pop_front() {
+            Some(event) => {
+                self.channel.space.notify_one();
+                Ok(event)
+            }
+            None if state.is_disconnected => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Blocks, until event is received or `timeout` passes.
+    pub fn recv_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<Event, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.channel.lock();
+
+        loop {
+            if let Some(event) = state.queue.pop_front() {
+                self.channel.space.notify_one();
+                return Ok(event);
+            }
+
+            if state.is_disconnected {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+
+            state = self
+                .channel
+                .available
+                .wait_timeout(state, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Returns iterator, that blocks waiting for events, until receiver is
+    /// disconnected.
+    pub fn iter(&self) -> impl Iterator<Item = Event> + '_ {
+        std::iter::from_fn(|| self.recv().ok())
+    }
+
+    /// Returns number of events, that were dropped because of
+    /// [`Backpressure::DropOldest`].
+    pub fn dropped(&self) -> u64 {
+        self.channel.lock().dropped
+    }
+
+    /// Returns `true`, if receiver was disconnected, either because broker
+    /// stopped or because of [`Backpressure::Disconnect`].
+    pub fn is_disconnected(&self) -> bool {
+        self.channel.lock().is_disconnected
+    }
+}
+
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        self.channel.lock().is_receiver_dropped = true;
+        self.channel.space.notify_all();
+    }
+}
+
+struct Consumer {
+    subscriptions: Vec<Subscription>,
+    channel: Arc<Channel>,
+}
+
+impl Consumer {
+    fn is_subscribed_to(&self, event: &Event) -> bool {
+        self.subscriptions.iter().any(|sub| sub.matches(event))
+    }
+}
+
+/// Broker, that shares a single bspwm subscription between several
+/// consumers, so that bspwm sends every event once and all consumers see
+/// events in the same order.
+///
+/// Every consumer gets [`EventReceiver`] with [`EventBroker::receiver`],
+/// which receives only events, it is subscribed to. After all receivers are
+/// created, broker is started with [`EventBroker::start`] and subscribes to
+/// the union of all subscriptions.
+pub struct EventBroker<T: Transport = UnixSocketTransport> {
+    client: BspcClient<T>,
+    consumers: Vec<Consumer>,
+}
+
+impl<T: Transport> fmt::Debug for EventBroker<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventBroker")
+            .field("consumers", &self.consumers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for EventBroker {
+    fn default() -> Self {
+        Self::new(BspcClient::new())
+    }
+}
+
+impl<T: Transport> EventBroker<T> {
+    /// Creates broker, that subscribes using the given client.
+    pub fn new(client: BspcClient<T>) -> Self {
+        Self {
+            client,
+            consumers: Vec::new(),
+        }
+    }
+
+    /// Creates receiver for the given events, that holds at most `capacity`
+    /// events. If subscriptions are empty, it receives reports, just like
+    /// bspwm does.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `capacity` is `0`.
+    pub fn receiver(
+        &mut self,
+        subscriptions: &[Subscription],
+        capacity: usize,
+        backpressure: Backpressure,
+    ) -> EventReceiver {
+        assert!(capacity > 0, "Capacity of receiver must be positive");
+
+        let mut subscriptions = subscriptions.to_vec();
+
+        if subscriptions.is_empty() {
+            subscriptions.push(Subscription::Report);
+        }
+
+        let channel = Arc::new(Channel {
+            state: Mutex::new(ChannelState {
+                queue: VecDeque::new(),
+                capacity,
+                backpressure,
+                is_disconnected: false,
+                is_receiver_dropped: false,
+                dropped: 0,
+            }),
+            available: Condvar::new(),
+            space: Condvar::new(),
+        });
+
+        self.consumers.push(Consumer {
+            subscriptions,
+            channel: Arc::clone(&channel),
+        });
+
+        EventReceiver { channel }
+    }
+
+    /// Returns union of subscriptions of all receivers.
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        let mut union = Vec::new();
+
+        for consumer in &self.consumers {
+            for subscription in &consumer.subscriptions {
+                if !union.contains(subscription) {
+                    union.push(*subscription);
+                }
+            }
+        }
+
+        if union.contains(&Subscription::All) {
+            return vec![Subscription::All];
+        }
+
+        union
+    }
+
+    /// Subscribes to events of all receivers and starts delivering them in
+    /// the background thread.
+    pub fn start(self) -> Result<BrokerHandle, ReplyError> {
+        let subscriber =
+            self.client.subscribe(false, None, &self.subscriptions())?;
+        let shutdown = subscriber.shutdown_handle();
+
+        let thread = {
+            let shutdown = shutdown.clone();
+            let consumers = self.consumers;
+
+            thread::spawn(move || run(subscriber, consumers, &shutdown))
+        };
+
+        Ok(BrokerHandle { shutdown, thread })
+    }
+}
+
+fn run(
+    mut subscriber: Subscriber,
+    mut consumers: Vec<Consumer>,
+    shutdown: &ShutdownHandle,
+) -> Result<(), ReplyError> {
+    let mut result = Ok(());
+
+    for event in subscriber.events() {
+        let event = match event {
+            Ok(event) => event,
+
+            // Malformed events are skipped, but connection errors end it
+            Err(ReplyError::ParseError(_)) => continue,
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        };
+
+        consumers.retain(|consumer| {
+            if !consumer.is_subscribed_to(&event) {
+                return !consumer.channel.lock().is_receiver_dropped;
+            }
+
+            if consumer.channel.send(event.clone(), shutdown) {
+                return true;
+            }
+
+            consumer.channel.disconnect();
+            false
+        });
+
+        if consumers.is_empty() {
+            break;
+        }
+    }
+
+    for consumer in &consumers {
+        consumer.channel.disconnect();
+    }
+
+    result
+}
+
+/// Handle of the running [`EventBroker`].
+#[derive(Debug)]
+pub struct BrokerHandle {
+    shutdown: ShutdownHandle,
+    thread: JoinHandle<Result<(), ReplyError>>,
+}
+
+impl BrokerHandle {
+    /// Returns handle, that stops the broker, disconnecting all receivers.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Stops the broker and waits for it to finish.
+    pub fn stop(self) -> Result<(), ReplyError> {
+        self.shutdown.shutdown()?;
+        self.join()
+    }
+
+    /// Waits for the broker to finish, which happens, when bspwm closes
+    /// connection, all receivers are dropped or broker is shut down.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the same payload, if broker thread panicked.
+    pub fn join(self) -> Result<(), ReplyError> {
+        self.thread
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::FakeBspwm;
+
+    const NODE_FOCUS: &str = "node_focus 0x00200002 0x00200007 0x00400002";
+    const DESKTOP_FOCUS: &str = "desktop_focus 0x00200002 0x00200007";
+    const REPORT: &str = "WMeDP1:O1:LT:TT:G";
+
+    fn wait(bspwm: &FakeBspwm) {
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn union_of_subscriptions() {
+        let mut broker = EventBroker::default();
+        let _a = broker.receiver(
+            &[Subscription::Node, Subscription::Report],
+            1,
+            Backpressure::Block,
+        );
+        let _b = broker.receiver(
+            &[Subscription::Report, Subscription::DesktopFocus],
+            1,
+            Backpressure::Block,
+        );
+
+        assert_eq!(
+            broker.subscriptions(),
+            vec![
+                Subscription::Node,
+                Subscription::Report,
+                Subscription::DesktopFocus
+            ]
+        );
+
+        let _c = broker.receiver(&[Subscription::All], 1, Backpressure::Block);
+        assert_eq!(broker.subscriptions(), vec![Subscription::All]);
+    }
+
+    #[test]
+    fn fan_out() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut broker = EventBroker::new(bspwm.client());
+
+        let nodes =
+            broker.receiver(&[Subscription::Node], 16, Backpressure::Block);
+        let all = broker.receiver(
+            &[Subscription::NodeFocus, Subscription::DesktopFocus],
+            16,
+            Backpressure::Block,
+        );
+        let reports = broker.receiver(&[], 16, Backpressure::Block);
+
+        let handle = broker.start().unwrap();
+        wait(&bspwm);
+
+        assert_eq!(
+            bspwm.requests(),
+            vec![
+                vec!["config", "status_prefix"],
+                vec![
+                    "subscribe",
+                    "node",
+                    "node_focus",
+                    "desktop_focus",
+                    "report"
+                ]
+            ]
+        );
+
+        bspwm.emit(NODE_FOCUS);
+        bspwm.emit(DESKTOP_FOCUS);
+        bspwm.emit(REPORT);
+        bspwm.close_subscribers();
+
+        assert!(handle.join().is_ok());
+
+        let names = |receiver: &EventReceiver| {
+            receiver
+                .iter()
+                .map(|x| x.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(&nodes), vec!["node_focus"]);
+        assert_eq!(names(&all), vec!["node_focus", "desktop_focus"]);
+        assert_eq!(names(&reports), vec!["report"]);
+        assert_eq!(nodes.recv(), Err(RecvError));
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut broker = EventBroker::new(bspwm.client());
+        let receiver =
+            broker.receiver(&[Subscription::All], 2, Backpressure::DropOldest);
+
+        let handle = broker.start().unwrap();
+        wait(&bspwm);
+
+        bspwm.emit(NODE_FOCUS);
+        bspwm.emit(DESKTOP_FOCUS);
+        bspwm.emit(REPORT);
+        bspwm.close_subscribers();
+        handle.join().unwrap();
+
+        let names: Vec<_> =
+            receiver.iter().map(|x| x.name().to_string()).collect();
+
+        assert_eq!(names, vec!["desktop_focus", "report"]);
+        assert_eq!(receiver.dropped(), 1);
+    }
+
+    #[test]
+    fn disconnect() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut broker = EventBroker::new(bspwm.client());
+        let slow =
+            broker.receiver(&[Subscription::All], 1, Backpressure::Disconnect);
+        let fast =
+            broker.receiver(&[Subscription::All], 8, Backpressure::Block);
+
+        let handle = broker.start().unwrap();
+        wait(&bspwm);
+
+        bspwm.emit(NODE_FOCUS);
+        bspwm.emit(DESKTOP_FOCUS);
+
+        assert!(fast.recv().is_ok());
+        assert!(fast.recv().is_ok());
+
+        assert!(slow.is_disconnected());
+        assert!(slow.recv().is_ok());
+        assert_eq!(slow.try_recv(), Err(TryRecvError::Disconnected));
+
+        handle.stop().unwrap();
+        assert_eq!(fast.recv(), Err(RecvError));
+    }
+
+    #[test]
+    fn block() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut broker = EventBroker::new(bspwm.client());
+        let receiver =
+            broker.receiver(&[Subscription::All], 1, Backpressure::Block);
+
+        let handle = broker.start().unwrap();
+        wait(&bspwm);
+
+        bspwm.emit(NODE_FOCUS);
+        bspwm.emit(DESKTOP_FOCUS);
+        bspwm.emit(REPORT);
+
+        for name in ["node_focus", "desktop_focus", "report"] {
+            let event = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert_eq!(event.name(), name);
+        }
+
+        assert_eq!(receiver.dropped(), 0);
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Timeout)
+        );
+
+        handle.stop().unwrap();
+    }
+
+    #[test]
+    fn stop_while_blocked() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut broker = EventBroker::new(bspwm.client());
+        let receiver =
+            broker.receiver(&[Subscription::All], 1, Backpressure::Block);
+
+        let handle = broker.start().unwrap();
+        wait(&bspwm);
+
+        bspwm.emit(NODE_FOCUS);
+        bspwm.emit(DESKTOP_FOCUS);
+
+        // Gives broker time to fill the receiver and block on the second event
+        thread::sleep(Duration::from_millis(200));
+        handle.stop().unwrap();
+
+        let events: Vec<_> = receiver.iter().collect();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name(), "node_focus");
+    }
+
+    #[test]
+    fn stop_when_receivers_dropped() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut broker = EventBroker::new(bspwm.client());
+        let receiver =
+            broker.receiver(&[Subscription::All], 1, Backpressure::Block);
+
+        let handle = broker.start().unwrap();
+        wait(&bspwm);
+
+        drop(receiver);
+        bspwm.emit(NODE_FOCUS);
+
+        assert!(handle.join().is_ok());
+    }
+}
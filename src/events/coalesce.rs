@@ -0,0 +1,435 @@
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{Event, ShutdownHandle, Subscriber, Subscription};
+use crate::errors::ReplyError;
+use crate::Id;
+
+/// Part of the event, that coalesced events share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoalesceBy {
+    /// Events of the same type are coalesced.
+    Event,
+
+    /// Events of the same type about the same monitors are coalesced, see
+    /// [`Event::monitor_ids`].
+    Monitor,
+
+    /// Events of the same type about the same desktops are coalesced, see
+    /// [`Event::desktop_ids`].
+    Desktop,
+
+    /// Events of the same type about the same nodes are coalesced, see
+    /// [`Event::node_ids`].
+    Node,
+}
+
+/// Time, that event is held back for.
+#[derive(Debug, Clone, Copy)]
+enum Window {
+    /// Window starts with the first event and isn't extended by later ones.
+    Fixed(Duration),
+
+    /// Window starts again with every later event.
+    Debounce(Duration),
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    subscription: Subscription,
+    by: CoalesceBy,
+    window: Window,
+}
+
+/// Rules of coalescing events for [`Coalesced`].
+///
+/// When event matches a rule, it's held back for the rule's time window and
+/// replaced by the later events with the same key, so that only the last one
+/// is returned, once the window passes. Events, that don't match any rule,
+/// are returned immediately.
+///
+/// Rules, added with [`Coalescing::rule`], have a fixed window, that starts
+/// with the first event, so that noisy events are returned at most once per
+/// window. Rules, added with [`Coalescing::debounce`], start the window again
+/// with every later event, so that the last event is returned only after
+/// events stop coming for the whole window.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use bspc_rs::events::{self, CoalesceBy, Coalescing, Subscription};
+///
+/// let window = Duration::from_millis(50);
+/// let coalescing = Coalescing::new()
+///     .rule(Subscription::NodeGeometry, CoalesceBy::Node, window)
+///     .rule(Subscription::NodeFocus, CoalesceBy::Monitor, window);
+///
+/// let subscriber = events::subscribe(false, None, &[Subscription::Node])
+///     .unwrap();
+///
+/// for event in subscriber.coalesce(coalescing) {
+///     println!("{}", event.unwrap());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Coalescing {
+    rules: Vec<Rule>,
+}
+
+impl Coalescing {
+    /// Creates coalescing without rules, which returns all events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds rule, that coalesces events matching `subscription` by the given
+    /// key within fixed `window`, that starts with the first event. Rules are
+    /// checked in the order, they were added.
+    pub fn rule(
+        self,
+        subscription: Subscription,
+        by: CoalesceBy,
+        window: Duration,
+    ) -> Self {
+        self.push(subscription, by, Window::Fixed(window))
+    }
+
+    /// Adds rule, that coalesces events matching `subscription` by the given
+    /// key, until there are no such events for `window`. Events, that keep
+    /// coming more often, are held back, until the stream of events ends.
+    pub fn debounce(
+        self,
+        subscription: Subscription,
+        by: CoalesceBy,
+        window: Duration,
+    ) -> Self {
+        self.push(subscription, by, Window::Debounce(window))
+    }
+
+    fn push(
+        mut self,
+        subscription: Subscription,
+        by: CoalesceBy,
+        window: Window,
+    ) -> Self {
+        self.rules.push(Rule {
+            subscription,
+            by,
+            window,
+        });
+
+        self
+    }
+
+    /// Returns key and window of the event, if it should be coalesced.
+    fn key(&self, event: &Event) -> Option<(Key, Window)> {
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| rule.subscription.matches(event))?;
+
+        let ids = match rule.by {
+            CoalesceBy::Event => Vec::new(),
+            CoalesceBy::Monitor => event.monitor_ids(),
+            CoalesceBy::Desktop => event.desktop_ids(),
+            CoalesceBy::Node => event.node_ids(),
+        };
+
+        Some(((event.name().to_string(), ids), rule.window))
+    }
+}
+
+type Key = (String, Vec<Id>);
+
+struct Pending {
+    key: Key,
+    deadline: Instant,
+    event: Event,
+}
+
+/// Iterator, that coalesces events according to [`Coalescing`]. It is
+/// created with [`Subscriber::coalesce`] or [`Coalesced::new`].
+///
+/// Events are read in the background thread, so that held back events are
+/// returned in time, even if there are no new events. Events, that are not
+/// coalesced, keep their relative order. When stream of events ends, held
+/// back events are returned without waiting.
+pub struct Coalesced {
+    receiver: Receiver<Result<Event, ReplyError>>,
+    coalescing: Coalescing,
+    pending: Vec<Pending>,
+    shutdown: Option<ShutdownHandle>,
+    is_closed: bool,
+}
+
+impl fmt::Debug for Coalesced {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Coalesced")
+            .field("coalescing", &self.coalescing)
+            .field("pending", &self.pending.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Coalesced {
+    /// Coalesces events of the given iterator, e.g.
+    /// [`ReconnectingSubscriber`](super::ReconnectingSubscriber).
+    pub fn new<I>(events: I, coalescing: Coalescing) -> Self
+    where
+        I: Iterator<Item = Result<Event, ReplyError>> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for event in events {
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            receiver,
+            coalescing,
+            pending: Vec::new(),
+            shutdown: None,
+            is_closed: false,
+        }
+    }
+
+    /// Sets handle, that stops reading events, when iterator is dropped.
+    fn with_shutdown(mut self, shutdown: ShutdownHandle) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Returns number of events, that are held back.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Removes pending event with the earliest deadline, if it has passed or
+    /// `force` is set.
+    fn take_pending(&mut self, force: bool) -> Option<Event> {
+        let now = Instant::now();
+        let (index, pending) = self
+            .pending
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, pending)| pending.deadline)?;
+
+        if !force && pending.deadline > now {
+            return None;
+        }
+
+        Some(self.pending.remove(index).event)
+    }
+
+    fn hold(&mut self, event: Event, key: Key, window: Window) {
+        let now = Instant::now();
+
+        match self.pending.iter_mut().find(|pending| pending.key == key) {
+            Some(pending) => {
+                pending.event = event;
+
+                if let Window::Debounce(window) = window {
+                    pending.deadline = now + window;
+                }
+            }
+
+            None => {
+                let (Window::Fixed(window) | Window::Debounce(window)) = window;
+
+                self.pending.push(Pending {
+                    key,
+                    deadline: now + window,
+                    event,
+                });
+            }
+        }
+    }
+}
+
+impl Iterator for Coalesced {
+    type Item = Result<Event, ReplyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.take_pending(self.is_closed) {
+                return Some(Ok(event));
+            }
+
+            if self.is_closed {
+                return None;
+            }
+
+            let deadline = self.pending.iter().map(|x| x.deadline).min();
+
+            let received = match deadline {
+                Some(deadline) => self.receiver.recv_timeout(
+                    deadline.saturating_duration_since(Instant::now()),
+                ),
+                None => self
+                    .receiver
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            match received {
+                Ok(Ok(event)) => match self.coalescing.key(&event) {
+                    Some((key, window)) => self.hold(event, key, window),
+                    None => return Some(Ok(event)),
+                },
+
+                Ok(Err(err)) => return Some(Err(err)),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => self.is_closed = true,
+            }
+        }
+    }
+}
+
+impl Drop for Coalesced {
+    fn drop(&mut self) {
+        if let Some(shutdown) = &self.shutdown {
+            let _ = shutdown.shutdown();
+        }
+    }
+}
+
+impl Subscriber {
+    /// Returns iterator over events, that coalesces them according to the
+    /// given rules. Subscriber is shut down, when iterator is dropped.
+    pub fn coalesce(mut self, coalescing: Coalescing) -> Coalesced {
+        let shutdown = self.shutdown_handle();
+        let events = std::iter::from_fn(move || self.events().next());
+
+        Coalesced::new(events, coalescing).with_shutdown(shutdown)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::FakeBspwm;
+
+    fn geometry(node: &str, width: u32) -> String {
+        format!("node_geometry 0x00200002 0x00200007 {node} {width}x100+0+0")
+    }
+
+    fn subscriber(bspwm: &FakeBspwm) -> Subscriber {
+        let subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::All])
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        subscriber
+    }
+
+    #[test]
+    fn coalesce_by_node() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let coalescing = Coalescing::new().rule(
+            Subscription::NodeGeometry,
+            CoalesceBy::Node,
+            Duration::from_secs(60),
+        );
+        let events = subscriber(&bspwm).coalesce(coalescing);
+
+        bspwm.emit(&geometry("0x00400002", 1));
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.emit(&geometry("0x00400003", 2));
+        bspwm.emit(&geometry("0x00400002", 3));
+        bspwm.emit("desktop_focus 0x00200002 0x00200007");
+        bspwm.emit(&geometry("0x00400002", 4));
+        bspwm.close_subscribers();
+
+        let lines: Vec<_> = events.map(|x| x.unwrap().to_string()).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "node_focus 0x00200002 0x00200007 0x00400002".to_string(),
+                "desktop_focus 0x00200002 0x00200007".to_string(),
+                geometry("0x00400002", 4),
+                geometry("0x00400003", 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn coalesce_within_window() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let window = Duration::from_millis(50);
+        let coalescing = Coalescing::new().rule(
+            Subscription::Node,
+            CoalesceBy::Event,
+            window,
+        );
+        let mut events = subscriber(&bspwm).coalesce(coalescing);
+
+        let start = Instant::now();
+        bspwm.emit(&geometry("0x00400002", 1));
+        bspwm.emit(&geometry("0x00400003", 2));
+
+        let event = events.next().unwrap().unwrap();
+
+        assert!(start.elapsed() >= window);
+        assert_eq!(event.to_string(), geometry("0x00400003", 2));
+        assert_eq!(events.pending(), 0);
+
+        // Later events start a new window
+        bspwm.emit(&geometry("0x00400002", 3));
+        let event = events.next().unwrap().unwrap();
+        assert_eq!(event.to_string(), geometry("0x00400002", 3));
+    }
+
+    #[test]
+    fn debounce_until_quiet() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let window = Duration::from_millis(100);
+        let coalescing = Coalescing::new().debounce(
+            Subscription::NodeGeometry,
+            CoalesceBy::Event,
+            window,
+        );
+        let mut events = subscriber(&bspwm).coalesce(coalescing);
+
+        let last = thread::scope(|scope| {
+            let emitter = scope.spawn(|| {
+                for width in 1..=3 {
+                    bspwm.emit(&geometry("0x00400002", width));
+                    thread::sleep(window / 2);
+                }
+
+                let last = Instant::now();
+                bspwm.emit(&geometry("0x00400002", 4));
+                last
+            });
+
+            // Window of the first event passes, but it's started again
+            let event = events.next().unwrap().unwrap();
+            assert_eq!(event.to_string(), geometry("0x00400002", 4));
+
+            emitter.join().unwrap()
+        });
+
+        assert!(last.elapsed() >= window);
+        assert_eq!(events.pending(), 0);
+    }
+
+    #[test]
+    fn shutdown_on_drop() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let subscriber = subscriber(&bspwm);
+        let handle = subscriber.shutdown_handle();
+        let events = subscriber.coalesce(Coalescing::new());
+
+        assert!(!handle.is_shutdown());
+        drop(events);
+        assert!(handle.is_shutdown());
+    }
+}
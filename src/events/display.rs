@@ -0,0 +1,848 @@
+use super::*;
+
+/// Formats id as bspwm does, e.g. `0x00200002`.
+struct Hex(Id);
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{:08X}", self.0)
+    }
+}
+
+impl fmt::Display for MonitorAddInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "monitor_add {} {} {}",
+            Hex(self.monitor_id),
+            self.monitor_name,
+            self.monitor_geometry
+        )
+    }
+}
+
+impl fmt::Display for MonitorRenameInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "monitor_rename {} {} {}",
+            Hex(self.monitor_id),
+            self.old_name,
+            self.new_name
+        )
+    }
+}
+
+impl fmt::Display for MonitorRemoveInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "monitor_remove {}", Hex(self.monitor_id))
+    }
+}
+
+impl fmt::Display for MonitorSwapInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "monitor_swap {} {}",
+            Hex(self.src_monitor_id),
+            Hex(self.dst_monitor_id)
+        )
+    }
+}
+
+impl fmt::Display for MonitorFocusInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "monitor_focus {}", Hex(self.monitor_id))
+    }
+}
+
+impl fmt::Display for MonitorGeometryInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "monitor_geometry {} {}",
+            Hex(self.monitor_id),
+            self.monitor_geometry
+        )
+    }
+}
+
+impl fmt::Display for DesktopAddInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "desktop_add {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            self.desktop_name
+        )
+    }
+}
+
+impl fmt::Display for DesktopRenameInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "desktop_rename {} {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            self.old_name,
+            self.new_name
+        )
+    }
+}
+
+impl fmt::Display for DesktopRemoveInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "desktop_remove {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id)
+        )
+    }
+}
+
+impl fmt::Display for DesktopSwapInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "desktop_swap {} {} {} {}",
+            Hex(self.src_monitor_id),
+            Hex(self.src_desktop_id),
+            Hex(self.dst_monitor_id),
+            Hex(self.dst_desktop_id)
+        )
+    }
+}
+
+impl fmt::Display for DesktopTransferInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "desktop_transfer {} {} {}",
+            Hex(self.src_monitor_id),
+            Hex(self.src_desktop_id),
+            Hex(self.dst_monitor_id)
+        )
+    }
+}
+
+impl fmt::Display for DesktopFocusInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "desktop_focus {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id)
+        )
+    }
+}
+
+impl fmt::Display for DesktopActivateInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "desktop_activate {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id)
+        )
+    }
+}
+
+impl fmt::Display for DesktopLayoutInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "desktop_layout {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            self.layout
+        )
+    }
+}
+
+impl fmt::Display for NodeAddInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_add {} {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.ip_id),
+            Hex(self.node_id)
+        )
+    }
+}
+
+impl fmt::Display for NodeRemoveInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_remove {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id)
+        )
+    }
+}
+
+impl fmt::Display for NodeSwapInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_swap {} {} {} {} {} {}",
+            Hex(self.src_monitor_id),
+            Hex(self.src_desktop_id),
+            Hex(self.src_node_id),
+            Hex(self.dst_monitor_id),
+            Hex(self.dst_desktop_id),
+            Hex(self.dst_node_id)
+        )
+    }
+}
+
+impl fmt::Display for NodeTransferInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_transfer {} {} {} {} {} {}",
+            Hex(self.src_monitor_id),
+            Hex(self.src_desktop_id),
+            Hex(self.src_node_id),
+            Hex(self.dst_monitor_id),
+            Hex(self.dst_desktop_id),
+            Hex(self.dst_node_id)
+        )
+    }
+}
+
+impl fmt::Display for NodeFocusInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_focus {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id)
+        )
+    }
+}
+
+impl fmt::Display for NodeActivateInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_activate {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id)
+        )
+    }
+}
+
+impl fmt::Display for NodePreselInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_presel {} {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id),
+            self.presel
+        )
+    }
+}
+
+impl fmt::Display for NodeStackInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_stack {} {} {}",
+            Hex(self.node_id_1),
+            self.stack,
+            Hex(self.node_id_2)
+        )
+    }
+}
+
+impl fmt::Display for NodeGeometryInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_geometry {} {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id),
+            self.node_geometry
+        )
+    }
+}
+
+impl fmt::Display for NodeStateInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_state {} {} {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id),
+            self.state,
+            self.switch
+        )
+    }
+}
+
+impl fmt::Display for NodeFlagInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_flag {} {} {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id),
+            self.flag,
+            self.switch
+        )
+    }
+}
+
+impl fmt::Display for NodeLayerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "node_layer {} {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id),
+            self.layer
+        )
+    }
+}
+
+impl fmt::Display for PointerActionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "pointer_action {} {} {} {} {}",
+            Hex(self.monitor_id),
+            Hex(self.desktop_id),
+            Hex(self.node_id),
+            self.action,
+            self.action_state
+        )
+    }
+}
+
+impl fmt::Display for ReportInfo {
+    /// Formats report with the default `W` prefix.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "W")?;
+
+        for (i, monitor) in self.monitors.iter().enumerate() {
+            if i > 0 {
+                write!(f, ":")?;
+            }
+
+            let kind = if monitor.focused { 'M' } else { 'm' };
+            write!(f, "{}{}", kind, monitor.name)?;
+
+            for desktop in &monitor.desktops {
+                let kind = match desktop.state {
+                    ReportDesktopState::Free => 'f',
+                    ReportDesktopState::Occupied => 'o',
+                    ReportDesktopState::Urgent => 'u',
+                };
+
+                let kind = if desktop.focused {
+                    kind.to_ascii_uppercase()
+                } else {
+                    kind
+                };
+
+                write!(f, ":{}{}", kind, desktop.name)?;
+            }
+
+            match monitor.layout {
+                Some(Layout::Tiled) => write!(f, ":LT")?,
+                Some(Layout::Monocle) => write!(f, ":LM")?,
+                None => {}
+            }
+
+            if let Some(node) = &monitor.focused_node {
+                let state = match node.state {
+                    Some(State::Tiled) => 'T',
+                    Some(State::PseudoTiled) => 'P',
+                    Some(State::Floating) => 'F',
+                    Some(State::Fullscreen) => '=',
+                    None => '@',
+                };
+
+                write!(f, ":T{}:G", state)?;
+
+                let flags = [
+                    (node.sticky, 'S'),
+                    (node.private, 'P'),
+                    (node.locked, 'L'),
+                    (node.marked, 'M'),
+                ];
+
+                for (_, flag) in flags.iter().filter(|(is_set, _)| *is_set) {
+                    write!(f, "{}", flag)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for MonitorEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MonitorEvent::MonitorAdd(info) => info.fmt(f),
+            MonitorEvent::MonitorRename(info) => info.fmt(f),
+            MonitorEvent::MonitorRemove(info) => info.fmt(f),
+            MonitorEvent::MonitorSwap(info) => info.fmt(f),
+            MonitorEvent::MonitorFocus(info) => info.fmt(f),
+            MonitorEvent::MonitorGeometry(info) => info.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for DesktopEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DesktopEvent::DesktopAdd(info) => info.fmt(f),
+            DesktopEvent::DesktopRename(info) => info.fmt(f),
+            DesktopEvent::DesktopRemove(info) => info.fmt(f),
+            DesktopEvent::DesktopSwap(info) => info.fmt(f),
+            DesktopEvent::DesktopTransfer(info) => info.fmt(f),
+            DesktopEvent::DesktopFocus(info) => info.fmt(f),
+            DesktopEvent::DesktopActivate(info) => info.fmt(f),
+            DesktopEvent::DesktopLayout(info) => info.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for NodeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeEvent::NodeAdd(info) => info.fmt(f),
+            NodeEvent::NodeRemove(info) => info.fmt(f),
+            NodeEvent::NodeSwap(info) => info.fmt(f),
+            NodeEvent::NodeTransfer(info) => info.fmt(f),
+            NodeEvent::NodeFocus(info) => info.fmt(f),
+            NodeEvent::NodeActivate(info) => info.fmt(f),
+            NodeEvent::NodePresel(info) => info.fmt(f),
+            NodeEvent::NodeStack(info) => info.fmt(f),
+            NodeEvent::NodeGeometry(info) => info.fmt(f),
+            NodeEvent::NodeState(info) => info.fmt(f),
+            NodeEvent::NodeFlag(info) => info.fmt(f),
+            NodeEvent::NodeLayer(info) => info.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    /// Formats event as the line, that bspwm sends, without trailing newline.
+    /// [`Event::Reconnected`] isn't sent by bspwm and is formatted as
+    /// `reconnected`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Report(info) => info.fmt(f),
+            Event::MonitorEvent(event) => event.fmt(f),
+            Event::DesktopEvent(event) => event.fmt(f),
+            Event::NodeEvent(event) => event.fmt(f),
+            Event::PointerAction(info) => info.fmt(f),
+            Event::Unknown { raw, .. } => write!(f, "{}", raw),
+            Event::Reconnected => write!(f, "reconnected"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::testing::EVENTS;
+
+    #[test]
+    fn display_corpus() {
+        for line in EVENTS.lines() {
+            let event = Event::parse_strict(line).unwrap();
+            assert_eq!(event.to_string(), line);
+        }
+    }
+
+    fn id() -> impl Strategy<Value = Id> {
+        any::<Id>()
+    }
+
+    /// Name without spaces, so that names of renames are unambiguous.
+    fn word() -> impl Strategy<Value = String> {
+        "[a-zA-Z0-9_.-]{1,12}"
+    }
+
+    /// Name, that can contain spaces, but not `:` of reports.
+    fn name() -> impl Strategy<Value = String> {
+        "[a-zA-Z0-9_.-]{1,8}( [a-zA-Z0-9_.-]{1,8}){0,2}"
+    }
+
+    fn rectangle() -> impl Strategy<Value = Rectangle> {
+        (any::<i16>(), any::<i16>(), 0..10000, 0..10000).prop_map(
+            |(x, y, width, height)| Rectangle {
+                x: x.into(),
+                y: y.into(),
+                width,
+                height,
+            },
+        )
+    }
+
+    fn presel() -> impl Strategy<Value = Presel> {
+        // Ratio is printed with 6 decimal places, just like bspwm does
+        let ratio = (0..=1_000_000_u32).prop_map(|x| {
+            Presel::Ratio(format!("{:.6}", x as f64 / 1e6).parse().unwrap())
+        });
+
+        prop_oneof![
+            prop_oneof![
+                Just(Dir::South),
+                Just(Dir::North),
+                Just(Dir::West),
+                Just(Dir::East)
+            ]
+            .prop_map(Presel::Dir),
+            ratio,
+            Just(Presel::Cancel),
+        ]
+    }
+
+    fn switch() -> impl Strategy<Value = Switch> {
+        prop_oneof![Just(Switch::On), Just(Switch::Off)]
+    }
+
+    fn layout() -> impl Strategy<Value = Layout> {
+        prop_oneof![Just(Layout::Tiled), Just(Layout::Monocle)]
+    }
+
+    fn state() -> impl Strategy<Value = State> {
+        prop_oneof![
+            Just(State::Tiled),
+            Just(State::PseudoTiled),
+            Just(State::Floating),
+            Just(State::Fullscreen),
+        ]
+    }
+
+    fn monitor_event() -> impl Strategy<Value = MonitorEvent> {
+        prop_oneof![
+            (id(), name(), rectangle()).prop_map(|(id, name, geometry)| {
+                MonitorEvent::MonitorAdd(MonitorAddInfo {
+                    monitor_id: id,
+                    monitor_name: name,
+                    monitor_geometry: geometry,
+                })
+            }),
+            (id(), word(), word()).prop_map(|(id, old_name, new_name)| {
+                MonitorEvent::MonitorRename(MonitorRenameInfo {
+                    monitor_id: id,
+                    old_name,
+                    new_name,
+                })
+            }),
+            id().prop_map(|id| {
+                MonitorEvent::MonitorRemove(MonitorRemoveInfo {
+                    monitor_id: id,
+                })
+            }),
+            (id(), id()).prop_map(|(src, dst)| {
+                MonitorEvent::MonitorSwap(MonitorSwapInfo {
+                    src_monitor_id: src,
+                    dst_monitor_id: dst,
+                })
+            }),
+            id().prop_map(|id| {
+                MonitorEvent::MonitorFocus(MonitorFocusInfo { monitor_id: id })
+            }),
+            (id(), rectangle()).prop_map(|(id, geometry)| {
+                MonitorEvent::MonitorGeometry(MonitorGeometryInfo {
+                    monitor_id: id,
+                    monitor_geometry: geometry,
+                })
+            }),
+        ]
+    }
+
+    fn desktop_event() -> impl Strategy<Value = DesktopEvent> {
+        prop_oneof![
+            (id(), id(), name()).prop_map(|(m, d, name)| {
+                DesktopEvent::DesktopAdd(DesktopAddInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    desktop_name: name,
+                })
+            }),
+            (id(), id(), word(), word()).prop_map(|(m, d, old, new)| {
+                DesktopEvent::DesktopRename(DesktopRenameInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    old_name: old,
+                    new_name: new,
+                })
+            }),
+            (id(), id()).prop_map(|(m, d)| {
+                DesktopEvent::DesktopRemove(DesktopRemoveInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                })
+            }),
+            (id(), id(), id(), id()).prop_map(|(sm, sd, dm, dd)| {
+                DesktopEvent::DesktopSwap(DesktopSwapInfo {
+                    src_monitor_id: sm,
+                    src_desktop_id: sd,
+                    dst_monitor_id: dm,
+                    dst_desktop_id: dd,
+                })
+            }),
+            (id(), id(), id()).prop_map(|(sm, sd, dm)| {
+                DesktopEvent::DesktopTransfer(DesktopTransferInfo {
+                    src_monitor_id: sm,
+                    src_desktop_id: sd,
+                    dst_monitor_id: dm,
+                })
+            }),
+            (id(), id()).prop_map(|(m, d)| {
+                DesktopEvent::DesktopFocus(DesktopFocusInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                })
+            }),
+            (id(), id()).prop_map(|(m, d)| {
+                DesktopEvent::DesktopActivate(DesktopActivateInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                })
+            }),
+            (id(), id(), layout()).prop_map(|(m, d, layout)| {
+                DesktopEvent::DesktopLayout(DesktopLayoutInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    layout,
+                })
+            }),
+        ]
+    }
+
+    fn node_event() -> impl Strategy<Value = NodeEvent> {
+        let flag = prop_oneof![
+            Just(Flag::Hidden),
+            Just(Flag::Sticky),
+            Just(Flag::Private),
+            Just(Flag::Locked),
+            Just(Flag::Marked),
+            Just(Flag::Urgent),
+        ];
+        let layer = prop_oneof![
+            Just(Layer::Below),
+            Just(Layer::Normal),
+            Just(Layer::Above)
+        ];
+        let stack = prop_oneof![Just(Stack::Below), Just(Stack::Above)];
+
+        prop_oneof![
+            (id(), id(), id(), id()).prop_map(|(m, d, ip, n)| {
+                NodeEvent::NodeAdd(NodeAddInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    ip_id: ip,
+                    node_id: n,
+                })
+            }),
+            (id(), id(), id()).prop_map(|(m, d, n)| {
+                NodeEvent::NodeRemove(NodeRemoveInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    node_id: n,
+                })
+            }),
+            [id(), id(), id(), id(), id(), id()].prop_map(|ids| {
+                NodeEvent::NodeSwap(NodeSwapInfo {
+                    src_monitor_id: ids[0],
+                    src_desktop_id: ids[1],
+                    src_node_id: ids[2],
+                    dst_monitor_id: ids[3],
+                    dst_desktop_id: ids[4],
+                    dst_node_id: ids[5],
+                })
+            }),
+            [id(), id(), id(), id(), id(), id()].prop_map(|ids| {
+                NodeEvent::NodeTransfer(NodeTransferInfo {
+                    src_monitor_id: ids[0],
+                    src_desktop_id: ids[1],
+                    src_node_id: ids[2],
+                    dst_monitor_id: ids[3],
+                    dst_desktop_id: ids[4],
+                    dst_node_id: ids[5],
+                })
+            }),
+            (id(), id(), id()).prop_map(|(m, d, n)| {
+                NodeEvent::NodeFocus(NodeFocusInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    node_id: n,
+                })
+            }),
+            (id(), id(), id()).prop_map(|(m, d, n)| {
+                NodeEvent::NodeActivate(NodeActivateInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    node_id: n,
+                })
+            }),
+            (id(), id(), id(), presel()).prop_map(|(m, d, n, presel)| {
+                NodeEvent::NodePresel(NodePreselInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    node_id: n,
+                    presel,
+                })
+            }),
+            (id(), stack, id()).prop_map(|(n1, stack, n2)| {
+                NodeEvent::NodeStack(NodeStackInfo {
+                    node_id_1: n1,
+                    stack,
+                    node_id_2: n2,
+                })
+            }),
+            (id(), id(), id(), rectangle()).prop_map(|(m, d, n, geometry)| {
+                NodeEvent::NodeGeometry(NodeGeometryInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    node_id: n,
+                    node_geometry: geometry,
+                })
+            }),
+            (id(), id(), id(), state(), switch()).prop_map(
+                |(m, d, n, state, switch)| {
+                    NodeEvent::NodeState(NodeStateInfo {
+                        monitor_id: m,
+                        desktop_id: d,
+                        node_id: n,
+                        state,
+                        switch,
+                    })
+                }
+            ),
+            (id(), id(), id(), flag, switch()).prop_map(
+                |(m, d, n, flag, switch)| {
+                    NodeEvent::NodeFlag(NodeFlagInfo {
+                        monitor_id: m,
+                        desktop_id: d,
+                        node_id: n,
+                        flag,
+                        switch,
+                    })
+                }
+            ),
+            (id(), id(), id(), layer).prop_map(|(m, d, n, layer)| {
+                NodeEvent::NodeLayer(NodeLayerInfo {
+                    monitor_id: m,
+                    desktop_id: d,
+                    node_id: n,
+                    layer,
+                })
+            }),
+        ]
+    }
+
+    fn pointer_action() -> impl Strategy<Value = PointerActionInfo> {
+        let action = prop_oneof![
+            Just(Action::Move),
+            Just(Action::ResizeCorner),
+            Just(Action::ResizeSide),
+        ];
+        let action_state =
+            prop_oneof![Just(ActionState::Begin), Just(ActionState::End)];
+
+        (id(), id(), id(), action, action_state).prop_map(
+            |(m, d, n, action, action_state)| PointerActionInfo {
+                monitor_id: m,
+                desktop_id: d,
+                node_id: n,
+                action,
+                action_state,
+            },
+        )
+    }
+
+    fn report() -> impl Strategy<Value = ReportInfo> {
+        let desktop_state = prop_oneof![
+            Just(ReportDesktopState::Free),
+            Just(ReportDesktopState::Occupied),
+            Just(ReportDesktopState::Urgent),
+        ];
+        let desktop = (name(), desktop_state, any::<bool>()).prop_map(
+            |(name, state, focused)| ReportDesktopInfo {
+                name,
+                state,
+                focused,
+            },
+        );
+        let node = (proptest::option::of(state()), any::<[bool; 4]>())
+            .prop_map(|(state, flags)| ReportNodeInfo {
+                state,
+                sticky: flags[0],
+                private: flags[1],
+                locked: flags[2],
+                marked: flags[3],
+            });
+        let monitor = (
+            name(),
+            any::<bool>(),
+            prop::collection::vec(desktop, 1..4),
+            proptest::option::of(layout()),
+            proptest::option::of(node),
+        )
+            .prop_map(
+                |(name, focused, desktops, layout, focused_node)| {
+                    ReportMonitorInfo {
+                        name,
+                        focused,
+                        desktops,
+                        layout,
+                        focused_node,
+                    }
+                },
+            );
+
+        prop::collection::vec(monitor, 1..3)
+            .prop_map(|monitors| ReportInfo { monitors })
+    }
+
+    fn event() -> impl Strategy<Value = Event> {
+        prop_oneof![
+            report().prop_map(Event::Report),
+            monitor_event().prop_map(Event::MonitorEvent),
+            desktop_event().prop_map(Event::DesktopEvent),
+            node_event().prop_map(Event::NodeEvent),
+            pointer_action().prop_map(Event::PointerAction),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trip(event in event()) {
+            let line = event.to_string();
+            prop_assert_eq!(Event::parse_strict(&line).unwrap(), event);
+        }
+
+        #[test]
+        fn json_round_trip(event in event()) {
+            let json = serde_json::to_string(&event).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+        }
+    }
+}
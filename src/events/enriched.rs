@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::*;
+use crate::selectors::NodeSelector;
+use crate::socket::UnixSocketTransport;
+use crate::tree::{Client, Node, Tree};
+
+/// Events, that [`EnrichedSubscriber`] subscribes to. Only window lifecycle
+/// events are returned, the others keep cached names and clients up to date.
+const SUBSCRIPTIONS: &[Subscription] = &[
+    Subscription::NodeAdd,
+    Subscription::NodeRemove,
+    Subscription::NodeFocus,
+    Subscription::NodeState,
+    Subscription::NodeFlag,
+    Subscription::NodeLayer,
+    Subscription::MonitorAdd,
+    Subscription::MonitorRename,
+    Subscription::MonitorRemove,
+    Subscription::DesktopAdd,
+    Subscription::DesktopRename,
+    Subscription::DesktopRemove,
+];
+
+/// Window lifecycle event, i.e. `node_add`, `node_remove`, `node_focus`,
+/// `node_state` or `node_flag`, together with metadata of the window and
+/// names of its desktop and monitor.
+#[derive(Debug, Clone)]
+pub struct EnrichedEvent {
+    pub event: NodeEvent,
+
+    /// Client of the window. It is `None` for nodes without windows (e.g.
+    /// receptacles) and windows, that were closed before they were queried.
+    /// For `node_remove` it's the last known client of the window.
+    pub client: Option<Client>,
+
+    pub desktop_name: Option<String>,
+    pub monitor_name: Option<String>,
+
+    /// Id of the node, that the event is about.
+    pub node_id: Id,
+}
+
+/// Subscriber, that attaches client of the window and names of its desktop
+/// and monitor to window lifecycle events. It is returned by
+/// [`BspcClient::subscribe_enriched`].
+///
+/// Names and clients of the existing windows are read from the state dump,
+/// when subscribing, and are kept up to date by the events, so that
+/// `node_remove` still reports, which application was closed. Clients of the
+/// new windows are queried, when they are added, state, layer and urgency of
+/// the known ones are updated from the events without querying bspwm.
+///
+/// ```no_run
+/// use bspc_rs::events::{self, NodeEvent};
+///
+/// for event in events::subscribe_enriched().unwrap() {
+///     let event = event.unwrap();
+///
+///     if let (NodeEvent::NodeRemove(_), Some(client)) =
+///         (&event.event, &event.client)
+///     {
+///         println!("{} was closed", client.class_name);
+///     }
+/// }
+/// ```
+pub struct EnrichedSubscriber<T: Transport = UnixSocketTransport> {
+    client: BspcClient<T>,
+    subscriber: Subscriber,
+    clients: HashMap<Id, Client>,
+    desktop_names: HashMap<Id, String>,
+    monitor_names: HashMap<Id, String>,
+}
+
+impl<T: Transport> fmt::Debug for EnrichedSubscriber<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EnrichedSubscriber")
+            .field("clients", &self.clients.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Transport> EnrichedSubscriber<T> {
+    /// Returns handle, that can shut down this subscriber from another
+    /// thread.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.subscriber.shutdown_handle()
+    }
+
+    /// Returns cached client of the window, if it is known.
+    pub fn cached_client(&self, node_id: Id) -> Option<&Client> {
+        self.clients.get(&node_id)
+    }
+
+    fn load_state(&mut self) -> Result<(), ReplyError> {
+        let state = self.client.get_current_state()?;
+
+        for monitor in state.monitors {
+            self.monitor_names.insert(monitor.id, monitor.name);
+
+            for desktop in monitor.desktops {
+                for node in desktop.windows() {
+                    if let Some(client) = &node.client {
+                        self.clients.insert(node.id, client.clone());
+                    }
+                }
+
+                self.desktop_names.insert(desktop.id, desktop.name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queries client of the node and caches it. Client stays the same, if
+    /// node doesn't exist anymore.
+    fn refresh(&mut self, node_id: Id) -> Result<(), ReplyError> {
+        let selector = format!("0x{:08X}", node_id);
+        let tree =
+            self.client
+                .query_tree(None, None, Some(NodeSelector(&selector)));
+
+        match tree {
+            Ok(Tree::Node(Node {
+                client: Some(client),
+                ..
+            })) => {
+                self.clients.insert(node_id, client);
+            }
+
+            Ok(_) | Err(ReplyError::RequestFailed(_)) => {}
+            Err(err) => return Err(err),
+        }
+
+        Ok(())
+    }
+
+    /// Updates cached client from the payload of the event, querying it
+    /// only, if it isn't cached yet.
+    fn update_client(
+        &mut self,
+        node_id: Id,
+        update: impl FnOnce(&mut Client),
+    ) -> Result<(), ReplyError> {
+        match self.clients.get_mut(&node_id) {
+            Some(client) => update(client),
+            None => self.refresh(node_id)?,
+        }
+
+        Ok(())
+    }
+
+    fn update_names(&mut self, event: &Event) {
+        match event {
+            Event::MonitorEvent(MonitorEvent::MonitorAdd(info)) => {
+                self.monitor_names
+                    .insert(info.monitor_id, info.monitor_name.clone());
+            }
+
+            Event::MonitorEvent(MonitorEvent::MonitorRename(info)) => {
+                let new_name = match self.monitor_names.get(&info.monitor_id) {
+                    Some(old_name) => MonitorRenameInfo::parse_with_old_name(
+                        &info.to_string(),
+                        old_name,
+                    )
+                    .map_or(info.new_name.clone(), |info| info.new_name),
+                    None => info.new_name.clone(),
+                };
+
+                self.monitor_names.insert(info.monitor_id, new_name);
+            }
+
+            Event::MonitorEvent(MonitorEvent::MonitorRemove(info)) => {
+                self.monitor_names.remove(&info.monitor_id);
+            }
+
+            Event::DesktopEvent(DesktopEvent::DesktopAdd(info)) => {
+                self.desktop_names
+                    .insert(info.desktop_id, info.desktop_name.clone());
+            }
+
+            Event::DesktopEvent(DesktopEvent::DesktopRename(info)) => {
+                let new_name = match self.desktop_names.get(&info.desktop_id) {
+                    Some(old_name) => DesktopRenameInfo::parse_with_old_name(
+                        &info.to_string(),
+                        old_name,
+                    )
+                    .map_or(info.new_name.clone(), |info| info.new_name),
+                    None => info.new_name.clone(),
+                };
+
+                self.desktop_names.insert(info.desktop_id, new_name);
+            }
+
+            Event::DesktopEvent(DesktopEvent::DesktopRemove(info)) => {
+                self.desktop_names.remove(&info.desktop_id);
+            }
+
+            _ => {}
+        }
+    }
+
+    /// Updates cache with the node event and returns it enriched, if it's a
+    /// lifecycle event.
+    fn enrich(
+        &mut self,
+        event: NodeEvent,
+    ) -> Result<Option<EnrichedEvent>, ReplyError> {
+        let (monitor_id, desktop_id, node_id) = match &event {
+            NodeEvent::NodeAdd(info) => {
+                self.refresh(info.node_id)?;
+                (info.monitor_id, info.desktop_id, info.node_id)
+            }
+
+            // Old state is switched off right before the new one is on
+            NodeEvent::NodeState(info) => {
+                if info.switch == Switch::On {
+                    self.update_client(info.node_id, |client| {
+                        client.last_state = std::mem::replace(
+                            &mut client.state,
+                            info.state.clone(),
+                        );
+                    })?;
+                }
+
+                (info.monitor_id, info.desktop_id, info.node_id)
+            }
+
+            // Other flags belong to the node, not to its client
+            NodeEvent::NodeFlag(info) => {
+                if info.flag == Flag::Urgent {
+                    self.update_client(info.node_id, |client| {
+                        client.urgent = info.switch == Switch::On;
+                    })?;
+                }
+
+                (info.monitor_id, info.desktop_id, info.node_id)
+            }
+
+            NodeEvent::NodeFocus(info) => {
+                if !self.clients.contains_key(&info.node_id) {
+                    self.refresh(info.node_id)?;
+                }
+
+                (info.monitor_id, info.desktop_id, info.node_id)
+            }
+
+            NodeEvent::NodeRemove(info) => {
+                (info.monitor_id, info.desktop_id, info.node_id)
+            }
+
+            NodeEvent::NodeLayer(info) => {
+                self.update_client(info.node_id, |client| {
+                    client.last_layer = std::mem::replace(
+                        &mut client.layer,
+                        info.layer.clone(),
+                    );
+                })?;
+
+                return Ok(None);
+            }
+
+            _ => return Ok(None),
+        };
+
+        let client = match &event {
+            NodeEvent::NodeRemove(_) => self.clients.remove(&node_id),
+            _ => self.clients.get(&node_id).cloned(),
+        };
+
+        Ok(Some(EnrichedEvent {
+            event,
+            client,
+            desktop_name: self.desktop_names.get(&desktop_id).cloned(),
+            monitor_name: self.monitor_names.get(&monitor_id).cloned(),
+            node_id,
+        }))
+    }
+}
+
+impl<T: Transport> Iterator for EnrichedSubscriber<T> {
+    type Item = Result<EnrichedEvent, ReplyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let event = match self.subscriber.events().next()? {
+                Ok(event) => event,
+                Err(err) => return Some(Err(err)),
+            };
+
+            match event {
+                Event::NodeEvent(event) => match self.enrich(event) {
+                    Ok(Some(event)) => return Some(Ok(event)),
+                    Ok(None) => {}
+                    Err(err) => return Some(Err(err)),
+                },
+
+                event => self.update_names(&event),
+            }
+        }
+    }
+}
+
+impl<T: Transport + Clone> BspcClient<T> {
+    /// Subscribes to window lifecycle events, that carry client of the
+    /// window and names of its desktop and monitor, see
+    /// [`EnrichedSubscriber`].
+    pub fn subscribe_enriched(
+        &self,
+    ) -> Result<EnrichedSubscriber<T>, ReplyError> {
+        // State is read after subscribing, so that no changes are missed
+        let subscriber = self.subscribe(false, None, SUBSCRIPTIONS)?;
+
+        let mut enriched = EnrichedSubscriber {
+            client: self.clone(),
+            subscriber,
+            clients: HashMap::new(),
+            desktop_names: HashMap::new(),
+            monitor_names: HashMap::new(),
+        };
+
+        enriched.load_state()?;
+        Ok(enriched)
+    }
+}
+
+/// Subscribes to window lifecycle events, that carry client of the window
+/// and names of its desktop and monitor, see [`EnrichedSubscriber`].
+pub fn subscribe_enriched() -> Result<EnrichedSubscriber, ReplyError> {
+    BspcClient::new().subscribe_enriched()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::{FakeBspwm, Reply, STATE_DUMP};
+
+    const NEW_NODE: &str = r#"{"id":4194320,"splitType":"vertical","splitRatio":0.5,"vacant":false,"hidden":false,"sticky":false,"private":false,"locked":false,"marked":false,"presel":null,"rectangle":{"x":0,"y":0,"width":100,"height":100},"constraints":{"min_width":32,"min_height":32},"firstChild":null,"secondChild":null,"client":{"className":"firefox","instanceName":"Navigator","borderWidth":1,"state":"tiled","lastState":"tiled","layer":"normal","lastLayer":"normal","urgent":false,"shown":true,"tiledRectangle":{"x":0,"y":0,"width":100,"height":100},"floatingRectangle":{"x":0,"y":0,"width":100,"height":100}}}"#;
+
+    fn start() -> (FakeBspwm, EnrichedSubscriber) {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.set_state(STATE_DUMP);
+
+        let subscriber = bspwm.client().subscribe_enriched().unwrap();
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        (bspwm, subscriber)
+    }
+
+    fn summary(event: EnrichedEvent) -> (String, Option<String>, String) {
+        (
+            event.event.name().to_string(),
+            event.client.map(|client| client.class_name),
+            event.desktop_name.unwrap_or_default(),
+        )
+    }
+
+    #[test]
+    fn enrich_lifecycle_events() {
+        let (bspwm, subscriber) = start();
+        bspwm.on_request(
+            &["query", "--tree", "--node", "0x00400010"],
+            Reply::lines([NEW_NODE]),
+        );
+
+        bspwm.emit("node_add 0x00200002 0x00200007 0x00400002 0x00400010");
+        bspwm.emit("node_geometry 0x00200002 0x00200007 0x00400010 1x1+0+0");
+        bspwm.emit("desktop_rename 0x00200002 0x00200007 1 web");
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.emit("node_remove 0x00200002 0x00200007 0x00400010");
+        bspwm.close_subscribers();
+
+        let events: Vec<_> = subscriber.map(|x| summary(x.unwrap())).collect();
+        let summary = |name: &str, class: &str, desktop: &str| {
+            (
+                name.to_string(),
+                Some(class.to_string()),
+                desktop.to_string(),
+            )
+        };
+
+        assert_eq!(
+            events,
+            vec![
+                summary("node_add", "firefox", "1"),
+                summary("node_focus", "Alacritty", "web"),
+                summary("node_remove", "firefox", "web"),
+            ]
+        );
+    }
+
+    #[test]
+    fn update_client_from_events() {
+        let (bspwm, mut subscriber) = start();
+        let requests = bspwm.requests().len();
+
+        bspwm.emit("node_state 0x00200002 0x00200007 0x00400002 tiled off");
+        bspwm.emit("node_state 0x00200002 0x00200007 0x00400002 floating on");
+        bspwm.emit("node_layer 0x00200002 0x00200007 0x00400002 above");
+        bspwm.emit("node_flag 0x00200002 0x00200007 0x00400002 urgent on");
+        bspwm.close_subscribers();
+
+        let events: Vec<_> = subscriber.by_ref().map(|x| x.unwrap()).collect();
+        let client = events[2].client.as_ref().unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[2].node_id, 0x00400002);
+        assert_eq!(client.state, State::Floating);
+        assert_eq!(client.last_state, State::Tiled);
+        assert_eq!(client.layer, Layer::Above);
+        assert_eq!(client.last_layer, Layer::Normal);
+        assert!(client.urgent);
+
+        // Cached clients are updated without querying bspwm
+        assert_eq!(bspwm.requests().len(), requests);
+    }
+
+    #[test]
+    fn remove_forgets_client() {
+        let (bspwm, mut subscriber) = start();
+
+        assert!(subscriber.cached_client(0x00400002).is_some());
+
+        bspwm.emit("node_remove 0x00200002 0x00200007 0x00400002");
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.close_subscribers();
+
+        let removed = subscriber.next().unwrap().unwrap();
+        assert_eq!(removed.client.unwrap().class_name, "Alacritty");
+        assert_eq!(removed.monitor_name.as_deref(), Some("eDP1"));
+
+        // Node is queried again, but bspwm doesn't know it anymore
+        let focused = subscriber.next().unwrap().unwrap();
+        assert!(focused.client.is_none());
+        assert!(subscriber.cached_client(0x00400002).is_none());
+    }
+}
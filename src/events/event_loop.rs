@@ -0,0 +1,357 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::ControlFlow;
+
+use super::*;
+use crate::socket::UnixSocketTransport;
+
+/// Error, that is returned by handler of [`EventLoop`].
+pub type HandlerError = Box<dyn Error + Send + Sync>;
+
+/// Value, that can be returned by handler of [`EventLoop`]: `()`,
+/// [`ControlFlow`] or [`Result`] of them.
+pub trait HandlerOutput {
+    fn into_control_flow(self) -> Result<ControlFlow<()>, HandlerError>;
+}
+
+impl HandlerOutput for () {
+    fn into_control_flow(self) -> Result<ControlFlow<()>, HandlerError> {
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+impl HandlerOutput for ControlFlow<()> {
+    fn into_control_flow(self) -> Result<ControlFlow<()>, HandlerError> {
+        Ok(self)
+    }
+}
+
+impl<E: Into<HandlerError>> HandlerOutput for Result<(), E> {
+    fn into_control_flow(self) -> Result<ControlFlow<()>, HandlerError> {
+        self.map(|_| ControlFlow::Continue(())).map_err(Into::into)
+    }
+}
+
+impl<E: Into<HandlerError>> HandlerOutput for Result<ControlFlow<()>, E> {
+    fn into_control_flow(self) -> Result<ControlFlow<()>, HandlerError> {
+        self.map_err(Into::into)
+    }
+}
+
+type HandlerFn<'a> = Box<
+    dyn FnMut(&Event) -> Option<Result<ControlFlow<()>, HandlerError>> + 'a,
+>;
+
+type ErrorHook<'a> = Box<dyn FnMut(HandlerError) + 'a>;
+
+struct Handler<'a> {
+    subscription: Subscription,
+    handle: HandlerFn<'a>,
+}
+
+/// Event loop, that dispatches events to the registered handlers.
+///
+/// It subscribes only to events, that have handlers, and calls handlers in
+/// the order, they were registered. Loop stops, when handler returns
+/// [`ControlFlow::Break`] or bspwm closes connection. Errors of handlers and
+/// events, that failed to parse, are passed to the error hook, set with
+/// [`EventLoop::on_error`], and are ignored otherwise.
+///
+/// ```no_run
+/// use bspc_rs::events::EventLoop;
+///
+/// EventLoop::new()
+///     .on_node_focus(|info| println!("{} is focused", info.node_id))
+///     .on_error(|err| eprintln!("{err}"))
+///     .run()
+///     .unwrap();
+/// ```
+pub struct EventLoop<'a, T: Transport = UnixSocketTransport> {
+    client: BspcClient<T>,
+    handlers: Vec<Handler<'a>>,
+    error_hook: Option<ErrorHook<'a>>,
+}
+
+impl<'a, T: Transport> fmt::Debug for EventLoop<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventLoop")
+            .field("subscriptions", &self.subscriptions())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> Default for EventLoop<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> EventLoop<'a> {
+    /// Creates event loop, that subscribes using the default client.
+    pub fn new() -> Self {
+        Self::with_client(BspcClient::new())
+    }
+}
+
+impl<'a, T: Transport> EventLoop<'a, T> {
+    /// Creates event loop, that subscribes using the given client.
+    pub fn with_client(client: BspcClient<T>) -> Self {
+        Self {
+            client,
+            handlers: Vec::new(),
+            error_hook: None,
+        }
+    }
+
+    /// Registers handler of events of type `I`.
+    pub fn on<I, R>(mut self, mut handler: impl FnMut(&I) -> R + 'a) -> Self
+    where
+        I: EventInfo,
+        R: HandlerOutput,
+    {
+        self.handlers.push(Handler {
+            subscription: I::SUBSCRIPTION,
+            handle: Box::new(move |event| {
+                I::from_event_ref(event)
+                    .map(|info| handler(info).into_control_flow())
+            }),
+        });
+
+        self
+    }
+
+    /// Sets hook, that receives errors of handlers and events, that failed
+    /// to parse.
+    pub fn on_error(mut self, hook: impl FnMut(HandlerError) + 'a) -> Self {
+        self.error_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Returns events, that event loop subscribes to.
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        let mut subscriptions = Vec::new();
+
+        for handler in &self.handlers {
+            if !subscriptions.contains(&handler.subscription) {
+                subscriptions.push(handler.subscription);
+            }
+        }
+
+        subscriptions
+    }
+
+    /// Runs event loop, until handler returns [`ControlFlow::Break`] or
+    /// connection is closed. It returns immediately, if there are no
+    /// handlers.
+    pub fn run(mut self) -> Result<(), ReplyError> {
+        if self.handlers.is_empty() {
+            return Ok(());
+        }
+
+        let mut subscriber =
+            self.client.subscribe(false, None, &self.subscriptions())?;
+
+        // Malformed events are reported, instead of being skipped as unknown
+        subscriber.set_strict(true);
+
+        for event in subscriber.events() {
+            let event = match event {
+                Ok(event) => event,
+                Err(ReplyError::ParseError(err)) => {
+                    report(&mut self.error_hook, Box::new(err));
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            if self.dispatch(&event).is_break() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn dispatch(&mut self, event: &Event) -> ControlFlow<()> {
+        for handler in &mut self.handlers {
+            match (handler.handle)(event) {
+                Some(Ok(ControlFlow::Break(()))) => {
+                    return ControlFlow::Break(())
+                }
+                Some(Err(err)) => report(&mut self.error_hook, err),
+                _ => {}
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+}
+
+fn report(error_hook: &mut Option<ErrorHook<'_>>, err: HandlerError) {
+    if let Some(hook) = error_hook {
+        hook(err);
+    }
+}
+
+macro_rules! impl_handlers {
+    ($($method:ident => $info:ident),* $(,)?) => {
+        impl<'a, T: Transport> EventLoop<'a, T> {
+            $(
+                #[doc = concat!("Registers handler of [`", stringify!($info), "`].")]
+                pub fn $method<R: HandlerOutput>(
+                    self,
+                    handler: impl FnMut(&$info) -> R + 'a,
+                ) -> Self {
+                    self.on(handler)
+                }
+            )*
+        }
+    };
+}
+
+impl_handlers! {
+    on_report => ReportInfo,
+    on_pointer_action => PointerActionInfo,
+    on_monitor_add => MonitorAddInfo,
+    on_monitor_rename => MonitorRenameInfo,
+    on_monitor_remove => MonitorRemoveInfo,
+    on_monitor_swap => MonitorSwapInfo,
+    on_monitor_focus => MonitorFocusInfo,
+    on_monitor_geometry => MonitorGeometryInfo,
+    on_desktop_add => DesktopAddInfo,
+    on_desktop_rename => DesktopRenameInfo,
+    on_desktop_remove => DesktopRemoveInfo,
+    on_desktop_swap => DesktopSwapInfo,
+    on_desktop_transfer => DesktopTransferInfo,
+    on_desktop_focus => DesktopFocusInfo,
+    on_desktop_activate => DesktopActivateInfo,
+    on_desktop_layout => DesktopLayoutInfo,
+    on_node_add => NodeAddInfo,
+    on_node_remove => NodeRemoveInfo,
+    on_node_swap => NodeSwapInfo,
+    on_node_transfer => NodeTransferInfo,
+    on_node_focus => NodeFocusInfo,
+    on_node_activate => NodeActivateInfo,
+    on_node_presel => NodePreselInfo,
+    on_node_stack => NodeStackInfo,
+    on_node_geometry => NodeGeometryInfo,
+    on_node_state => NodeStateInfo,
+    on_node_flag => NodeFlagInfo,
+    on_node_layer => NodeLayerInfo,
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::FakeBspwm;
+
+    const NODE_ADD: &str =
+        "node_add 0x00200002 0x00200007 0x00400002 0x00400003";
+    const NODE_FOCUS: &str = "node_focus 0x00200002 0x00200007 0x00400003";
+    const DESKTOP_FOCUS: &str = "desktop_focus 0x00200002 0x00200007";
+
+    fn emit_later(bspwm: &FakeBspwm, lines: &'static [&'static str]) {
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        for line in lines {
+            bspwm.emit(line);
+        }
+
+        bspwm.close_subscribers();
+    }
+
+    #[test]
+    fn dispatch_to_handlers() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let seen = RefCell::new(Vec::new());
+
+        let event_loop = EventLoop::with_client(bspwm.client())
+            .on_node_add(|info| seen.borrow_mut().push(info.node_id))
+            .on_node_focus(|info| seen.borrow_mut().push(info.node_id + 1))
+            .on_node_add(|_| seen.borrow_mut().push(0));
+
+        assert_eq!(
+            event_loop.subscriptions(),
+            vec![Subscription::NodeAdd, Subscription::NodeFocus]
+        );
+
+        thread::scope(|s| {
+            s.spawn(|| emit_later(&bspwm, &[NODE_ADD, NODE_FOCUS]));
+            event_loop.run().unwrap();
+        });
+
+        assert_eq!(
+            bspwm.requests(),
+            vec![vec!["subscribe", "node_add", "node_focus"]]
+        );
+        assert_eq!(seen.into_inner(), vec![0x00400003, 0, 0x00400004]);
+    }
+
+    #[test]
+    fn break_from_handler() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut count = 0;
+
+        thread::scope(|s| {
+            s.spawn(|| {
+                assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+                bspwm.emit(DESKTOP_FOCUS);
+                bspwm.emit(DESKTOP_FOCUS);
+            });
+
+            EventLoop::with_client(bspwm.client())
+                .on_desktop_focus(|_| {
+                    count += 1;
+                    ControlFlow::Break(())
+                })
+                .run()
+                .unwrap();
+        });
+
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn errors_go_to_hook() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let errors = RefCell::new(Vec::new());
+        let mut focused = 0;
+
+        thread::scope(|s| {
+            s.spawn(|| {
+                emit_later(
+                    &bspwm,
+                    &["node_focus 0x00200002 nonsense", NODE_FOCUS, NODE_FOCUS],
+                )
+            });
+
+            EventLoop::with_client(bspwm.client())
+                .on_node_focus(|_| -> Result<(), HandlerError> {
+                    focused += 1;
+                    Err("handler failed".into())
+                })
+                .on_error(|err| errors.borrow_mut().push(err.to_string()))
+                .run()
+                .unwrap();
+        });
+
+        let errors = errors.into_inner();
+
+        assert_eq!(focused, 2);
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("node_focus"), "{}", errors[0]);
+        assert_eq!(errors[1..], ["handler failed", "handler failed"]);
+    }
+
+    #[test]
+    fn no_handlers() {
+        let bspwm = FakeBspwm::start().unwrap();
+
+        EventLoop::with_client(bspwm.client()).run().unwrap();
+        assert!(bspwm.requests().is_empty());
+    }
+}
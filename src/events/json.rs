@@ -0,0 +1,223 @@
+use super::*;
+
+/// Flat representation of [`Event`] in serde, which is tagged by the name of
+/// the event, just like [`MonitorEvent`], [`DesktopEvent`] and
+/// [`NodeEvent`] are.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(super) enum EventRepr {
+    Report(ReportInfo),
+    MonitorAdd(MonitorAddInfo),
+    MonitorRename(MonitorRenameInfo),
+    MonitorRemove(MonitorRemoveInfo),
+    MonitorSwap(MonitorSwapInfo),
+    MonitorFocus(MonitorFocusInfo),
+    MonitorGeometry(MonitorGeometryInfo),
+    DesktopAdd(DesktopAddInfo),
+    DesktopRename(DesktopRenameInfo),
+    DesktopRemove(DesktopRemoveInfo),
+    DesktopSwap(DesktopSwapInfo),
+    DesktopTransfer(DesktopTransferInfo),
+    DesktopFocus(DesktopFocusInfo),
+    DesktopActivate(DesktopActivateInfo),
+    DesktopLayout(DesktopLayoutInfo),
+    NodeAdd(NodeAddInfo),
+    NodeRemove(NodeRemoveInfo),
+    NodeSwap(NodeSwapInfo),
+    NodeTransfer(NodeTransferInfo),
+    NodeFocus(NodeFocusInfo),
+    NodeActivate(NodeActivateInfo),
+    NodePresel(NodePreselInfo),
+    NodeStack(NodeStackInfo),
+    NodeGeometry(NodeGeometryInfo),
+    NodeState(NodeStateInfo),
+    NodeFlag(NodeFlagInfo),
+    NodeLayer(NodeLayerInfo),
+    PointerAction(PointerActionInfo),
+    Unknown {
+        name: String,
+        args: Vec<String>,
+        raw: String,
+    },
+    Reconnected,
+}
+
+impl From<Event> for EventRepr {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::Report(info) => EventRepr::Report(info),
+
+            Event::MonitorEvent(event) => match event {
+                MonitorEvent::MonitorAdd(info) => EventRepr::MonitorAdd(info),
+                MonitorEvent::MonitorRename(info) => {
+                    EventRepr::MonitorRename(info)
+                }
+                MonitorEvent::MonitorRemove(info) => {
+                    EventRepr::MonitorRemove(info)
+                }
+                MonitorEvent::MonitorSwap(info) => EventRepr::MonitorSwap(info),
+                MonitorEvent::MonitorFocus(info) => {
+                    EventRepr::MonitorFocus(info)
+                }
+                MonitorEvent::MonitorGeometry(info) => {
+                    EventRepr::MonitorGeometry(info)
+                }
+            },
+
+            Event::DesktopEvent(event) => match event {
+                DesktopEvent::DesktopAdd(info) => EventRepr::DesktopAdd(info),
+                DesktopEvent::DesktopRename(info) => {
+                    EventRepr::DesktopRename(info)
+                }
+                DesktopEvent::DesktopRemove(info) => {
+                    EventRepr::DesktopRemove(info)
+                }
+                DesktopEvent::DesktopSwap(info) => EventRepr::DesktopSwap(info),
+                DesktopEvent::DesktopTransfer(info) => {
+                    EventRepr::DesktopTransfer(info)
+                }
+                DesktopEvent::DesktopFocus(info) => {
+                    EventRepr::DesktopFocus(info)
+                }
+                DesktopEvent::DesktopActivate(info) => {
+                    EventRepr::DesktopActivate(info)
+                }
+                DesktopEvent::DesktopLayout(info) => {
+                    EventRepr::DesktopLayout(info)
+                }
+            },
+
+            Event::NodeEvent(event) => match event {
+                NodeEvent::NodeAdd(info) => EventRepr::NodeAdd(info),
+                NodeEvent::NodeRemove(info) => EventRepr::NodeRemove(info),
+                NodeEvent::NodeSwap(info) => EventRepr::NodeSwap(info),
+                NodeEvent::NodeTransfer(info) => EventRepr::NodeTransfer(info),
+                NodeEvent::NodeFocus(info) => EventRepr::NodeFocus(info),
+                NodeEvent::NodeActivate(info) => EventRepr::NodeActivate(info),
+                NodeEvent::NodePresel(info) => EventRepr::NodePresel(info),
+                NodeEvent::NodeStack(info) => EventRepr::NodeStack(info),
+                NodeEvent::NodeGeometry(info) => EventRepr::NodeGeometry(info),
+                NodeEvent::NodeState(info) => EventRepr::NodeState(info),
+                NodeEvent::NodeFlag(info) => EventRepr::NodeFlag(info),
+                NodeEvent::NodeLayer(info) => EventRepr::NodeLayer(info),
+            },
+
+            Event::PointerAction(info) => EventRepr::PointerAction(info),
+            Event::Unknown { name, args, raw } => {
+                EventRepr::Unknown { name, args, raw }
+            }
+            Event::Reconnected => EventRepr::Reconnected,
+        }
+    }
+}
+
+impl From<EventRepr> for Event {
+    fn from(repr: EventRepr) -> Self {
+        use DesktopEvent::*;
+        use MonitorEvent::*;
+        use NodeEvent::*;
+
+        match repr {
+            EventRepr::Report(info) => Event::Report(info),
+
+            EventRepr::MonitorAdd(info) => MonitorAdd(info).into(),
+            EventRepr::MonitorRename(info) => MonitorRename(info).into(),
+            EventRepr::MonitorRemove(info) => MonitorRemove(info).into(),
+            EventRepr::MonitorSwap(info) => MonitorSwap(info).into(),
+            EventRepr::MonitorFocus(info) => MonitorFocus(info).into(),
+            EventRepr::MonitorGeometry(info) => MonitorGeometry(info).into(),
+
+            EventRepr::DesktopAdd(info) => DesktopAdd(info).into(),
+            EventRepr::DesktopRename(info) => DesktopRename(info).into(),
+            EventRepr::DesktopRemove(info) => DesktopRemove(info).into(),
+            EventRepr::DesktopSwap(info) => DesktopSwap(info).into(),
+            EventRepr::DesktopTransfer(info) => DesktopTransfer(info).into(),
+            EventRepr::DesktopFocus(info) => DesktopFocus(info).into(),
+            EventRepr::DesktopActivate(info) => DesktopActivate(info).into(),
+            EventRepr::DesktopLayout(info) => DesktopLayout(info).into(),
+
+            EventRepr::NodeAdd(info) => NodeAdd(info).into(),
+            EventRepr::NodeRemove(info) => NodeRemove(info).into(),
+            EventRepr::NodeSwap(info) => NodeSwap(info).into(),
+            EventRepr::NodeTransfer(info) => NodeTransfer(info).into(),
+            EventRepr::NodeFocus(info) => NodeFocus(info).into(),
+            EventRepr::NodeActivate(info) => NodeActivate(info).into(),
+            EventRepr::NodePresel(info) => NodePresel(info).into(),
+            EventRepr::NodeStack(info) => NodeStack(info).into(),
+            EventRepr::NodeGeometry(info) => NodeGeometry(info).into(),
+            EventRepr::NodeState(info) => NodeState(info).into(),
+            EventRepr::NodeFlag(info) => NodeFlag(info).into(),
+            EventRepr::NodeLayer(info) => NodeLayer(info).into(),
+
+            EventRepr::PointerAction(info) => Event::PointerAction(info),
+            EventRepr::Unknown { name, args, raw } => {
+                Event::Unknown { name, args, raw }
+            }
+            EventRepr::Reconnected => Event::Reconnected,
+        }
+    }
+}
+
+impl From<MonitorEvent> for Event {
+    fn from(event: MonitorEvent) -> Self {
+        Event::MonitorEvent(event)
+    }
+}
+
+impl From<DesktopEvent> for Event {
+    fn from(event: DesktopEvent) -> Self {
+        Event::DesktopEvent(event)
+    }
+}
+
+impl From<NodeEvent> for Event {
+    fn from(event: NodeEvent) -> Self {
+        Event::NodeEvent(event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::EVENTS;
+
+    #[test]
+    fn json_shape() {
+        let event: Event = "node_focus 0x00200002 0x00200007 0x00400002"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"node_focus","monitor_id":2097154,"desktop_id":2097159,"node_id":4194306}"#
+        );
+
+        let event: Event = "node_presel 0x00200002 0x00200007 0x00400002 \
+                            dir east"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap()["presel"],
+            serde_json::json!({ "dir": "east" })
+        );
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut events: Vec<Event> = EVENTS
+            .lines()
+            .map(|line| Event::parse_strict(line).unwrap())
+            .collect();
+
+        events.push("future_event 0x00200002 x".parse().unwrap());
+        events.push(Event::Reconnected);
+
+        for event in events {
+            let json = serde_json::to_string(&event).unwrap();
+            let parsed: Event = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(parsed, event, "{json}");
+        }
+    }
+}
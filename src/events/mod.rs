@@ -0,0 +1,1640 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::BufRead;
+use std::io::{self, BufReader, Read, Write};
+use std::iter::FusedIterator;
+use std::path::{Path, PathBuf};
+use std::string::ToString;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+use strum_macros::{Display, EnumIter, IntoStaticStr};
+
+use crate::client::BspcClient;
+use crate::errors::{ParseError, ReplyError};
+use crate::parser::parse_events::desktop_events::parse_desktop_rename;
+use crate::parser::parse_events::monitor_events::parse_monitor_rename;
+use crate::parser::parse_events::report::parse_report;
+use crate::parser::parse_events::{parse_event, parse_event_or_unknown};
+use crate::properties::*;
+use crate::socket::{BspcCommunication, ShutdownFn, Transport};
+use crate::Id;
+use json::EventRepr;
+
+mod broker;
+mod coalesce;
+mod display;
+mod enriched;
+mod event_loop;
+mod json;
+mod reconnect;
+mod timestamp;
+mod typed;
+
+pub use broker::{Backpressure, BrokerHandle, EventBroker, EventReceiver};
+pub use coalesce::{CoalesceBy, Coalesced, Coalescing};
+pub use enriched::{subscribe_enriched, EnrichedEvent, EnrichedSubscriber};
+pub use event_loop::{EventLoop, HandlerError, HandlerOutput};
+pub use reconnect::{subscribe_reconnecting, Backoff, ReconnectingSubscriber};
+pub use timestamp::{ReceiveTime, Timestamped, Timestamps};
+pub use typed::{subscribe_typed, EventInfo, TypedSubscriber};
+
+#[derive(
+    Display, EnumIter, IntoStaticStr, Debug, Clone, Copy, PartialEq, Eq, Hash,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum Subscription {
+    All,
+    Report,
+    Monitor,
+    Desktop,
+    Node,
+    MonitorAdd,
+    MonitorRename,
+    MonitorRemove,
+    MonitorSwap,
+    MonitorFocus,
+    MonitorGeometry,
+    DesktopAdd,
+    DesktopRename,
+    DesktopRemove,
+    DesktopSwap,
+    DesktopTransfer,
+    DesktopFocus,
+    DesktopActivate,
+    DesktopLayout,
+    NodeAdd,
+    NodeRemove,
+    NodeSwap,
+    NodeTransfer,
+    NodeFocus,
+    NodeActivate,
+    NodePresel,
+    NodeStack,
+    NodeGeometry,
+    NodeState,
+    NodeFlag,
+    NodeLayer,
+    PointerAction,
+}
+
+impl Subscription {
+    /// Returns `true`, if subscription includes the event. Synthetic
+    /// [`Event::Reconnected`] is included in every subscription.
+    pub fn matches(&self, event: &Event) -> bool {
+        if let Event::Reconnected = event {
+            return true;
+        }
+
+        self.includes(event.name())
+    }
+
+    /// Returns `true`, if subscription includes events with the given name.
+    pub(crate) fn includes(&self, name: &str) -> bool {
+        match self {
+            Subscription::All => true,
+            Subscription::Monitor => name.starts_with("monitor_"),
+            Subscription::Desktop => name.starts_with("desktop_"),
+            Subscription::Node => name.starts_with("node_"),
+            subscription => <&str>::from(subscription) == name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorAddInfo {
+    pub monitor_id: Id,
+    pub monitor_name: String,
+    pub monitor_geometry: Rectangle,
+}
+
+/// Names can contain spaces, which makes splitting them ambiguous. Parsing
+/// with [`str::parse`] fails with [`ParseError::AmbiguousNames`], unless both
+/// names are single words. Use [`MonitorRenameInfo::parse_with_old_name`], if
+/// old name is known. [`Subscriber`] keeps track of names itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorRenameInfo {
+    pub monitor_id: Id,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorRemoveInfo {
+    pub monitor_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorSwapInfo {
+    pub src_monitor_id: Id,
+    pub dst_monitor_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorFocusInfo {
+    pub monitor_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorGeometryInfo {
+    pub monitor_id: Id,
+    pub monitor_geometry: Rectangle,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum MonitorEvent {
+    MonitorAdd(MonitorAddInfo),
+    MonitorRename(MonitorRenameInfo),
+    MonitorRemove(MonitorRemoveInfo),
+    MonitorSwap(MonitorSwapInfo),
+    MonitorFocus(MonitorFocusInfo),
+    MonitorGeometry(MonitorGeometryInfo),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesktopAddInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub desktop_name: String,
+}
+
+/// Names can contain spaces, which makes splitting them ambiguous. Parsing
+/// with [`str::parse`] fails with [`ParseError::AmbiguousNames`], unless both
+/// names are single words. Use [`DesktopRenameInfo::parse_with_old_name`], if
+/// old name is known. [`Subscriber`] keeps track of names itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesktopRenameInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesktopRemoveInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesktopSwapInfo {
+    pub src_monitor_id: Id,
+    pub src_desktop_id: Id,
+    pub dst_monitor_id: Id,
+    pub dst_desktop_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesktopTransferInfo {
+    pub src_monitor_id: Id,
+    pub src_desktop_id: Id,
+    pub dst_monitor_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesktopFocusInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesktopActivateInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesktopLayoutInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub layout: Layout,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum DesktopEvent {
+    DesktopAdd(DesktopAddInfo),
+    DesktopRename(DesktopRenameInfo),
+    DesktopRemove(DesktopRemoveInfo),
+    DesktopSwap(DesktopSwapInfo),
+    DesktopTransfer(DesktopTransferInfo),
+    DesktopFocus(DesktopFocusInfo),
+    DesktopActivate(DesktopActivateInfo),
+    DesktopLayout(DesktopLayoutInfo),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeAddInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub ip_id: Id,
+    pub node_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeRemoveInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeSwapInfo {
+    pub src_monitor_id: Id,
+    pub src_desktop_id: Id,
+    pub src_node_id: Id,
+    pub dst_monitor_id: Id,
+    pub dst_desktop_id: Id,
+    pub dst_node_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeTransferInfo {
+    pub src_monitor_id: Id,
+    pub src_desktop_id: Id,
+    pub src_node_id: Id,
+    pub dst_monitor_id: Id,
+    pub dst_desktop_id: Id,
+    pub dst_node_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeFocusInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeActivateInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodePreselInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+    pub presel: Presel,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeStackInfo {
+    pub node_id_1: Id,
+    pub stack: Stack,
+    pub node_id_2: Id,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeGeometryInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+    pub node_geometry: Rectangle,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeStateInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+    pub state: State,
+    pub switch: Switch,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeFlagInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+    pub flag: Flag,
+    pub switch: Switch,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeLayerInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+    pub layer: Layer,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NodeEvent {
+    NodeAdd(NodeAddInfo),
+    NodeRemove(NodeRemoveInfo),
+    NodeSwap(NodeSwapInfo),
+    NodeTransfer(NodeTransferInfo),
+    NodeFocus(NodeFocusInfo),
+    NodeActivate(NodeActivateInfo),
+    NodePresel(NodePreselInfo),
+    NodeStack(NodeStackInfo),
+    NodeGeometry(NodeGeometryInfo),
+    NodeState(NodeStateInfo),
+    NodeFlag(NodeFlagInfo),
+    NodeLayer(NodeLayerInfo),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointerActionInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+    pub action: Action,
+    pub action_state: ActionState,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportDesktopState {
+    Free,
+    Occupied,
+    Urgent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportDesktopInfo {
+    pub name: String,
+    pub state: ReportDesktopState,
+    pub focused: bool,
+}
+
+/// Focused node of the focused desktop of the monitor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportNodeInfo {
+    /// State of the node. It is `None`, if node is not a window.
+    pub state: Option<State>,
+    pub sticky: bool,
+    pub private: bool,
+    pub locked: bool,
+    pub marked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportMonitorInfo {
+    pub name: String,
+    pub focused: bool,
+    pub desktops: Vec<ReportDesktopInfo>,
+    /// Layout of the focused desktop.
+    pub layout: Option<Layout>,
+    pub focused_node: Option<ReportNodeInfo>,
+}
+
+/// Status report of bspwm, as it is printed by `bspc subscribe report` or
+/// `bspc wm --get-status`. Monitors and desktops are in the same order as in
+/// bspwm.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportInfo {
+    pub monitors: Vec<ReportMonitorInfo>,
+}
+
+impl MonitorRenameInfo {
+    /// Parses `monitor_rename` event of monitor, that was named `old_name`.
+    pub fn parse_with_old_name(
+        input: &str,
+        old_name: &str,
+    ) -> Result<MonitorRenameInfo, ParseError> {
+        parse_monitor_rename(input, Some(old_name))
+    }
+}
+
+impl DesktopRenameInfo {
+    /// Parses `desktop_rename` event of desktop, that was named `old_name`.
+    pub fn parse_with_old_name(
+        input: &str,
+        old_name: &str,
+    ) -> Result<DesktopRenameInfo, ParseError> {
+        parse_desktop_rename(input, Some(old_name))
+    }
+}
+
+impl ReportInfo {
+    /// Parses report, that starts with the given `status_prefix`.
+    ///
+    /// Parsing with [`str::parse`] guesses prefix, assuming that it doesn't
+    /// contain `M` and `m` characters, which holds for the default `W`
+    /// prefix. Use this function, if your prefix is different. Reports of
+    /// [`Subscriber`] are always parsed with the configured prefix.
+    pub fn parse_with_prefix(
+        input: &str,
+        status_prefix: &str,
+    ) -> Result<ReportInfo, ParseError> {
+        parse_report(input, status_prefix)
+    }
+
+    /// Returns focused monitor.
+    pub fn focused_monitor(&self) -> Option<&ReportMonitorInfo> {
+        self.monitors.iter().find(|monitor| monitor.focused)
+    }
+}
+
+impl MonitorEvent {
+    /// Returns name of the event, as it is sent by bspwm.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MonitorEvent::MonitorAdd(_) => "monitor_add",
+            MonitorEvent::MonitorRename(_) => "monitor_rename",
+            MonitorEvent::MonitorRemove(_) => "monitor_remove",
+            MonitorEvent::MonitorSwap(_) => "monitor_swap",
+            MonitorEvent::MonitorFocus(_) => "monitor_focus",
+            MonitorEvent::MonitorGeometry(_) => "monitor_geometry",
+        }
+    }
+}
+
+impl DesktopEvent {
+    /// Returns name of the event, as it is sent by bspwm.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DesktopEvent::DesktopAdd(_) => "desktop_add",
+            DesktopEvent::DesktopRename(_) => "desktop_rename",
+            DesktopEvent::DesktopRemove(_) => "desktop_remove",
+            DesktopEvent::DesktopSwap(_) => "desktop_swap",
+            DesktopEvent::DesktopTransfer(_) => "desktop_transfer",
+            DesktopEvent::DesktopFocus(_) => "desktop_focus",
+            DesktopEvent::DesktopActivate(_) => "desktop_activate",
+            DesktopEvent::DesktopLayout(_) => "desktop_layout",
+        }
+    }
+}
+
+impl NodeEvent {
+    /// Returns name of the event, as it is sent by bspwm.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NodeEvent::NodeAdd(_) => "node_add",
+            NodeEvent::NodeRemove(_) => "node_remove",
+            NodeEvent::NodeSwap(_) => "node_swap",
+            NodeEvent::NodeTransfer(_) => "node_transfer",
+            NodeEvent::NodeFocus(_) => "node_focus",
+            NodeEvent::NodeActivate(_) => "node_activate",
+            NodeEvent::NodePresel(_) => "node_presel",
+            NodeEvent::NodeStack(_) => "node_stack",
+            NodeEvent::NodeGeometry(_) => "node_geometry",
+            NodeEvent::NodeState(_) => "node_state",
+            NodeEvent::NodeFlag(_) => "node_flag",
+            NodeEvent::NodeLayer(_) => "node_layer",
+        }
+    }
+}
+
+/// Event of bspwm.
+///
+/// It is formatted with [`Display`](fmt::Display) as the line, that bspwm
+/// sends, and is serialized as an object with name of the event in `event`
+/// field and fields of its info, e.g.
+/// `{"event":"node_focus","monitor_id":1,"desktop_id":2,"node_id":3}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "EventRepr", into = "EventRepr")]
+pub enum Event {
+    Report(ReportInfo),
+    MonitorEvent(MonitorEvent),
+    DesktopEvent(DesktopEvent),
+    NodeEvent(NodeEvent),
+    PointerAction(PointerActionInfo),
+
+    /// Event, that couldn't be recognised, e.g. event of newer bspwm version.
+    Unknown {
+        name: String,
+        args: Vec<String>,
+        raw: String,
+    },
+
+    /// Synthetic event, that is returned by [`ReconnectingSubscriber`] after
+    /// it reconnected to bspwm. Events could be missed in the meantime, so
+    /// state should be queried again, e.g. with
+    /// [`get_current_state`](crate::state::get_current_state).
+    Reconnected,
+}
+
+impl Event {
+    /// Parses event, returning error instead of [`Event::Unknown`], if event
+    /// can't be recognised.
+    pub fn parse_strict(input: &str) -> Result<Event, ParseError> {
+        parse_event(input, &EventContext::default())
+    }
+
+    /// Returns name of the event, as it is sent by bspwm, e.g. `node_focus`.
+    /// Name of [`Event::Reconnected`] is `reconnected`.
+    pub fn name(&self) -> &str {
+        match self {
+            Event::Report(_) => "report",
+            Event::MonitorEvent(event) => event.name(),
+            Event::DesktopEvent(event) => event.name(),
+            Event::NodeEvent(event) => event.name(),
+            Event::PointerAction(_) => "pointer_action",
+            Event::Unknown { name, .. } => name,
+            Event::Reconnected => "reconnected",
+        }
+    }
+
+    /// Returns ids of monitors, that the event is about. Swap and transfer
+    /// events return both source and destination monitors.
+    pub fn monitor_ids(&self) -> Vec<Id> {
+        match self {
+            Event::MonitorEvent(event) => match event {
+                MonitorEvent::MonitorAdd(info) => vec![info.monitor_id],
+                MonitorEvent::MonitorRename(info) => vec![info.monitor_id],
+                MonitorEvent::MonitorRemove(info) => vec![info.monitor_id],
+                MonitorEvent::MonitorSwap(info) => {
+                    vec![info.src_monitor_id, info.dst_monitor_id]
+                }
+                MonitorEvent::MonitorFocus(info) => vec![info.monitor_id],
+                MonitorEvent::MonitorGeometry(info) => vec![info.monitor_id],
+            },
+
+            Event::DesktopEvent(event) => match event {
+                DesktopEvent::DesktopAdd(info) => vec![info.monitor_id],
+                DesktopEvent::DesktopRename(info) => vec![info.monitor_id],
+                DesktopEvent::DesktopRemove(info) => vec![info.monitor_id],
+                DesktopEvent::DesktopSwap(info) => {
+                    vec![info.src_monitor_id, info.dst_monitor_id]
+                }
+                DesktopEvent::DesktopTransfer(info) => {
+                    vec![info.src_monitor_id, info.dst_monitor_id]
+                }
+                DesktopEvent::DesktopFocus(info) => vec![info.monitor_id],
+                DesktopEvent::DesktopActivate(info) => vec![info.monitor_id],
+                DesktopEvent::DesktopLayout(info) => vec![info.monitor_id],
+            },
+
+            Event::NodeEvent(event) => match event {
+                NodeEvent::NodeAdd(info) => vec![info.monitor_id],
+                NodeEvent::NodeRemove(info) => vec![info.monitor_id],
+                NodeEvent::NodeSwap(info) => {
+                    vec![info.src_monitor_id, info.dst_monitor_id]
+                }
+                NodeEvent::NodeTransfer(info) => {
+                    vec![info.src_monitor_id, info.dst_monitor_id]
+                }
+                NodeEvent::NodeFocus(info) => vec![info.monitor_id],
+                NodeEvent::NodeActivate(info) => vec![info.monitor_id],
+                NodeEvent::NodePresel(info) => vec![info.monitor_id],
+                NodeEvent::NodeStack(_) => vec![],
+                NodeEvent::NodeGeometry(info) => vec![info.monitor_id],
+                NodeEvent::NodeState(info) => vec![info.monitor_id],
+                NodeEvent::NodeFlag(info) => vec![info.monitor_id],
+                NodeEvent::NodeLayer(info) => vec![info.monitor_id],
+            },
+
+            Event::PointerAction(info) => vec![info.monitor_id],
+            _ => vec![],
+        }
+    }
+
+    /// Returns ids of desktops, that the event is about. Swap and transfer
+    /// events return both source and destination desktops.
+    pub fn desktop_ids(&self) -> Vec<Id> {
+        match self {
+            Event::DesktopEvent(event) => match event {
+                DesktopEvent::DesktopAdd(info) => vec![info.desktop_id],
+                DesktopEvent::DesktopRename(info) => vec![info.desktop_id],
+                DesktopEvent::DesktopRemove(info) => vec![info.desktop_id],
+                DesktopEvent::DesktopSwap(info) => {
+                    vec![info.src_desktop_id, info.dst_desktop_id]
+                }
+                DesktopEvent::DesktopTransfer(info) => {
+                    vec![info.src_desktop_id]
+                }
+                DesktopEvent::DesktopFocus(info) => vec![info.desktop_id],
+                DesktopEvent::DesktopActivate(info) => vec![info.desktop_id],
+                DesktopEvent::DesktopLayout(info) => vec![info.desktop_id],
+            },
+
+            Event::NodeEvent(event) => match event {
+                NodeEvent::NodeAdd(info) => vec![info.desktop_id],
+                NodeEvent::NodeRemove(info) => vec![info.desktop_id],
+                NodeEvent::NodeSwap(info) => {
+                    vec![info.src_desktop_id, info.dst_desktop_id]
+                }
+                NodeEvent::NodeTransfer(info) => {
+                    vec![info.src_desktop_id, info.dst_desktop_id]
+                }
+                NodeEvent::NodeFocus(info) => vec![info.desktop_id],
+                NodeEvent::NodeActivate(info) => vec![info.desktop_id],
+                NodeEvent::NodePresel(info) => vec![info.desktop_id],
+                NodeEvent::NodeStack(_) => vec![],
+                NodeEvent::NodeGeometry(info) => vec![info.desktop_id],
+                NodeEvent::NodeState(info) => vec![info.desktop_id],
+                NodeEvent::NodeFlag(info) => vec![info.desktop_id],
+                NodeEvent::NodeLayer(info) => vec![info.desktop_id],
+            },
+
+            Event::PointerAction(info) => vec![info.desktop_id],
+            _ => vec![],
+        }
+    }
+
+    /// Returns ids of nodes, that the event is about. Swap and transfer
+    /// events return both source and destination nodes, `node_stack` returns
+    /// both stacked nodes.
+    pub fn node_ids(&self) -> Vec<Id> {
+        match self {
+            Event::NodeEvent(event) => match event {
+                NodeEvent::NodeAdd(info) => vec![info.node_id],
+                NodeEvent::NodeRemove(info) => vec![info.node_id],
+                NodeEvent::NodeSwap(info) => {
+                    vec![info.src_node_id, info.dst_node_id]
+                }
+                NodeEvent::NodeTransfer(info) => {
+                    vec![info.src_node_id, info.dst_node_id]
+                }
+                NodeEvent::NodeFocus(info) => vec![info.node_id],
+                NodeEvent::NodeActivate(info) => vec![info.node_id],
+                NodeEvent::NodePresel(info) => vec![info.node_id],
+                NodeEvent::NodeStack(info) => {
+                    vec![info.node_id_1, info.node_id_2]
+                }
+                NodeEvent::NodeGeometry(info) => vec![info.node_id],
+                NodeEvent::NodeState(info) => vec![info.node_id],
+                NodeEvent::NodeFlag(info) => vec![info.node_id],
+                NodeEvent::NodeLayer(info) => vec![info.node_id],
+            },
+
+            Event::PointerAction(info) => vec![info.node_id],
+            _ => vec![],
+        }
+    }
+}
+
+/// What is known about bspwm, that sends events, so that they can be parsed
+/// unambiguously: status prefix of reports and names of monitors and
+/// desktops for renames. Names are taken from state dump at subscribe time
+/// and then updated from the events of subscription.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EventContext {
+    pub(crate) status_prefix: Option<String>,
+    pub(crate) monitor_names: HashMap<Id, String>,
+    pub(crate) desktop_names: HashMap<Id, String>,
+}
+
+impl EventContext {
+    /// Returns `true`, if status prefix is needed to parse events of the
+    /// subscriptions.
+    pub(crate) fn needs_status_prefix(subscriptions: &[Subscription]) -> bool {
+        subscriptions.iter().any(|x| x.includes("report"))
+    }
+
+    /// Returns `true`, if names of monitors and desktops are needed to parse
+    /// events of the subscriptions.
+    pub(crate) fn needs_names(subscriptions: &[Subscription]) -> bool {
+        subscriptions.iter().any(|x| {
+            x.includes("monitor_rename") || x.includes("desktop_rename")
+        })
+    }
+
+    /// Remembers names of all monitors and desktops in the state.
+    pub(crate) fn add_names(&mut self, state: &crate::state::State) {
+        for monitor in &state.monitors {
+            self.monitor_names.insert(monitor.id, monitor.name.clone());
+
+            for desktop in &monitor.desktops {
+                self.desktop_names.insert(desktop.id, desktop.name.clone());
+            }
+        }
+    }
+
+    /// Queries what is needed to parse events of the given subscriptions.
+    pub(crate) fn query<T: Transport>(
+        client: &BspcClient<T>,
+        subscriptions: &[Subscription],
+    ) -> Result<Self, ReplyError> {
+        let mut context = EventContext::default();
+
+        if Self::needs_status_prefix(subscriptions) {
+            context.status_prefix = Some(client.get_status_prefix()?);
+        }
+
+        if Self::needs_names(subscriptions) {
+            context.add_names(&client.get_current_state()?);
+        }
+
+        Ok(context)
+    }
+
+    /// Parses event in strict mode, if `strict` is `true`, and updates names
+    /// of monitors and desktops from it.
+    pub(crate) fn parse(
+        &mut self,
+        input: &str,
+        strict: bool,
+    ) -> Result<Event, ParseError> {
+        let event = if strict {
+            parse_event(input, self)?
+        } else {
+            parse_event_or_unknown(input, self)?
+        };
+
+        self.update(&event);
+        Ok(event)
+    }
+
+    fn update(&mut self, event: &Event) {
+        match event {
+            Event::MonitorEvent(event) => match event {
+                MonitorEvent::MonitorAdd(info) => {
+                    let name = info.monitor_name.clone();
+                    self.monitor_names.insert(info.monitor_id, name);
+                }
+                MonitorEvent::MonitorRename(info) => {
+                    let name = info.new_name.clone();
+                    self.monitor_names.insert(info.monitor_id, name);
+                }
+                MonitorEvent::MonitorRemove(info) => {
+                    self.monitor_names.remove(&info.monitor_id);
+                }
+                _ => {}
+            },
+
+            Event::DesktopEvent(event) => match event {
+                DesktopEvent::DesktopAdd(info) => {
+                    let name = info.desktop_name.clone();
+                    self.desktop_names.insert(info.desktop_id, name);
+                }
+                DesktopEvent::DesktopRename(info) => {
+                    let name = info.new_name.clone();
+                    self.desktop_names.insert(info.desktop_id, name);
+                }
+                DesktopEvent::DesktopRemove(info) => {
+                    self.desktop_names.remove(&info.desktop_id);
+                }
+                _ => {}
+            },
+
+            _ => {}
+        }
+    }
+}
+
+/// Handle, that shuts down [`Subscriber`] from another thread. It can be
+/// obtained with [`Subscriber::shutdown_handle`].
+#[derive(Clone)]
+pub struct ShutdownHandle {
+    inner: Arc<ShutdownState>,
+}
+
+struct ShutdownState {
+    is_shutdown: AtomicBool,
+    shutdown_fn: Option<ShutdownFn>,
+}
+
+impl fmt::Debug for ShutdownHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ShutdownHandle")
+            .field("is_shutdown", &self.is_shutdown())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShutdownHandle {
+    fn new(shutdown_fn: Option<ShutdownFn>) -> Self {
+        Self {
+            inner: Arc::new(ShutdownState {
+                is_shutdown: AtomicBool::new(false),
+                shutdown_fn,
+            }),
+        }
+    }
+
+    /// Shuts down the subscriber, so it stops returning events. If subscriber
+    /// is blocked waiting for event, it's unblocked, when connection supports
+    /// it (see [`BspcCommunication::shutdown_fn`]), otherwise it stops after
+    /// the next event.
+    pub fn shutdown(&self) -> io::Result<()> {
+        if self.inner.is_shutdown.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        match &self.inner.shutdown_fn {
+            Some(shutdown_fn) => shutdown_fn(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns `true`, if subscriber was shut down.
+    pub fn is_shutdown(&self) -> bool {
+        self.inner.is_shutdown.load(Ordering::SeqCst)
+    }
+}
+
+/// Subscriber to bspwm events, that is returned by [`BspcClient::subscribe`].
+///
+/// Events are read with [`Subscriber::events`], which ends, when bspwm closes
+/// connection (e.g. after `count` events or on exit) or subscriber is shut
+/// down. Connection is shut down, when subscriber is dropped.
+pub struct Subscriber {
+    stream_buf: BufReader<Box<dyn Read + Send>>,
+    line: String,
+    received_at: Option<(Instant, SystemTime)>,
+    context: EventContext,
+    strict: bool,
+    is_closed: bool,
+    shutdown: ShutdownHandle,
+    fifo_path: Option<PathBuf>,
+}
+
+impl fmt::Debug for Subscriber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Subscriber").finish_non_exhaustive()
+    }
+}
+
+impl Subscriber {
+    /// Sets strict mode, in which unrecognised events are returned as errors
+    /// instead of [`Event::Unknown`]. It is disabled by default.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Returns `true`, if strict mode is enabled.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Returns handle, that can shut down this subscriber from another
+    /// thread.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Returns `true`, if stream of events has ended, because it was closed
+    /// by bspwm, failed or was shut down.
+    pub fn is_closed(&self) -> bool {
+        self.is_closed || self.shutdown.is_shutdown()
+    }
+
+    /// Returns path to the FIFO, that events are read from, if subscriber was
+    /// created with `fifo_flag`. FIFO belongs to this subscriber and is
+    /// removed, when it's dropped, so it shouldn't be read by other
+    /// processes. Use [`BspcClient::subscribe_fifo`] to get FIFO for them.
+    pub fn fifo_path(&self) -> Option<&Path> {
+        self.fifo_path.as_deref()
+    }
+
+    /// Shuts down connection to bspwm.
+    pub fn close(self) -> Result<(), ReplyError> {
+        self.shutdown.shutdown()?;
+        Ok(())
+    }
+
+    pub fn events(&mut self) -> EventIterator<'_> {
+        EventIterator {
+            subscriber: self,
+            filters: Vec::new(),
+        }
+    }
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        // Handles can hold connection open, so it is shut down explicitly
+        let _ = self.shutdown.shutdown();
+
+        // FIFO is opened only by subscriber, so it's not used by anyone else
+        if let Some(fifo_path) = &self.fifo_path {
+            let _ = fs::remove_file(fifo_path);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Subscriber {
+    type Item = Result<Event, ReplyError>;
+    type IntoIter = EventIterator<'a>;
+
+    fn into_iter(self) -> EventIterator<'a> {
+        self.events()
+    }
+}
+
+/// Iterator over events of [`Subscriber`]. It returns `None`, when stream of
+/// events ends, and error, when reading fails, after which it ends too.
+///
+/// Events can be filtered by ids with [`EventIterator::monitor`],
+/// [`EventIterator::desktop`] and [`EventIterator::node`]. Errors are
+/// returned regardless of filters.
+#[derive(Debug)]
+pub struct EventIterator<'a> {
+    subscriber: &'a mut Subscriber,
+    filters: Vec<IdFilter>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum IdFilter {
+    Monitor(Id),
+    Desktop(Id),
+    Node(Id),
+}
+
+impl IdFilter {
+    fn matches(&self, event: &Event) -> bool {
+        match *self {
+            IdFilter::Monitor(id) => event.monitor_ids().contains(&id),
+            IdFilter::Desktop(id) => event.desktop_ids().contains(&id),
+            IdFilter::Node(id) => event.node_ids().contains(&id),
+        }
+    }
+}
+
+impl<'a> EventIterator<'a> {
+    /// Returns only events about the given monitor, see
+    /// [`Event::monitor_ids`].
+    pub fn monitor(mut self, monitor_id: Id) -> Self {
+        self.filters.push(IdFilter::Monitor(monitor_id));
+        self
+    }
+
+    /// Returns only events about the given desktop, see
+    /// [`Event::desktop_ids`].
+    pub fn desktop(mut self, desktop_id: Id) -> Self {
+        self.filters.push(IdFilter::Desktop(desktop_id));
+        self
+    }
+
+    /// Returns only events about the given node, see [`Event::node_ids`].
+    pub fn node(mut self, node_id: Id) -> Self {
+        self.filters.push(IdFilter::Node(node_id));
+        self
+    }
+
+    fn is_wanted(&self, event: &Event) -> bool {
+        self.filters.iter().all(|filter| filter.matches(event))
+    }
+
+    fn next_event(&mut self) -> Option<Result<Event, ReplyError>> {
+        let subscriber = &mut *self.subscriber;
+
+        if subscriber.is_closed() {
+            return None;
+        }
+
+        // Line buffer is reused, so it doesn't allocate for every event
+        subscriber.line.clear();
+        let result = subscriber.stream_buf.read_line(&mut subscriber.line);
+
+        // Event is stamped before parsing, see `Timestamps`
+        subscriber.received_at = Some((Instant::now(), SystemTime::now()));
+
+        match result {
+            Ok(0) => {
+                subscriber.is_closed = true;
+                None
+            }
+
+            // Line can be incomplete, if subscriber was shut down
+            Ok(_) if subscriber.shutdown.is_shutdown() => None,
+
+            Ok(_) => {
+                let event = subscriber
+                    .context
+                    .parse(&subscriber.line, subscriber.strict)
+                    .map_err(From::from);
+                Some(event)
+            }
+
+            Err(_) if subscriber.shutdown.is_shutdown() => None,
+
+            Err(e) => {
+                subscriber.is_closed = true;
+                Some(Err(From::from(e)))
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for EventIterator<'a> {
+    type Item = Result<Event, ReplyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_event()? {
+                Ok(event) if !self.is_wanted(&event) => continue,
+                result => return Some(result),
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for EventIterator<'a> {}
+
+/// Returns path to FIFO, that bspwm replied with.
+fn receive_fifo_path(
+    conn: &mut impl BspcCommunication,
+) -> Result<PathBuf, ReplyError> {
+    let reply = conn.receive_message()?;
+    Ok(PathBuf::from(reply.first().ok_or(ReplyError::NoReply)?))
+}
+
+/// Opens FIFO, that bspwm replied with, and reads events from it.
+fn open_fifo(
+    mut conn: impl BspcCommunication,
+    context: EventContext,
+) -> Result<Subscriber, ReplyError> {
+    let fifo_path = receive_fifo_path(&mut conn)?;
+    let fifo = File::open(&fifo_path)?;
+
+    // Reader of FIFO can't be shut down, so it is woken up with empty line.
+    // Subscriber is the only reader, so no one else gets it.
+    let shutdown_fn: ShutdownFn = {
+        let fifo_path = fifo_path.clone();
+
+        Box::new(move || {
+            OpenOptions::new()
+                .write(true)
+                .open(&fifo_path)?
+                .write_all(b"\n")
+        })
+    };
+
+    Ok(Subscriber {
+        stream_buf: BufReader::new(Box::new(fifo)),
+        line: String::new(),
+        received_at: None,
+        context,
+        strict: false,
+        is_closed: false,
+        shutdown: ShutdownHandle::new(Some(shutdown_fn)),
+        fifo_path: Some(fifo_path),
+    })
+}
+
+pub(crate) fn subscribe_request(
+    fifo_flag: bool,
+    count: Option<u32>,
+    subscriptions: &[Subscription],
+) -> String {
+    let all_subscriptions = subscriptions
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join("\x00");
+
+    let mut count_option = String::new();
+    let mut fifo_option = "";
+
+    if let Some(x) = count {
+        count_option = format!("--count\x00{}\x00", x);
+    }
+
+    if fifo_flag {
+        fifo_option = "--fifo\x00";
+    }
+
+    format!(
+        "subscribe\x00{}{}{}\x00",
+        fifo_option, count_option, all_subscriptions
+    )
+}
+
+impl<T: Transport> BspcClient<T> {
+    /// Subscribes to the given events.
+    ///
+    /// If `fifo_flag` is set to `true`, then events are read from a FIFO,
+    /// created by bspwm, instead of the socket. Its path is returned by
+    /// [`Subscriber::fifo_path`] and it's removed, when subscriber is dropped.
+    ///
+    /// If `count` is `Some(x)`, then it stops returning subscription events
+    /// after having received `x` events.
+    pub fn subscribe(
+        &self,
+        fifo_flag: bool,
+        count: Option<u32>,
+        subscriptions: &[Subscription],
+    ) -> Result<Subscriber, ReplyError> {
+        let context = EventContext::query(self, subscriptions)?;
+        let mut conn = self.connect()?;
+        let subscribe_message =
+            subscribe_request(fifo_flag, count, subscriptions);
+
+        conn.send_message(&subscribe_message)?;
+
+        if fifo_flag {
+            return open_fifo(conn, context);
+        }
+
+        let shutdown = ShutdownHandle::new(conn.shutdown_fn()?);
+
+        Ok(Subscriber {
+            stream_buf: BufReader::new(Box::new(conn)),
+            line: String::new(),
+            received_at: None,
+            context,
+            strict: false,
+            is_closed: false,
+            shutdown,
+            fifo_path: None,
+        })
+    }
+
+    /// Subscribes to the given events in FIFO mode and returns path to the
+    /// FIFO, created by bspwm, without opening it, so it can be read by
+    /// another process, e.g. passed to a child. FIFO belongs to the caller:
+    /// it isn't read or removed by this crate.
+    ///
+    /// If `count` is `Some(x)`, then bspwm stops writing subscription events
+    /// after having written `x` events.
+    pub fn subscribe_fifo(
+        &self,
+        count: Option<u32>,
+        subscriptions: &[Subscription],
+    ) -> Result<PathBuf, ReplyError> {
+        let mut conn = self.connect()?;
+
+        conn.send_message(&subscribe_request(true, count, subscriptions))?;
+        receive_fifo_path(&mut conn)
+    }
+}
+
+/// Subscribes to the given events.
+///
+/// If `fifo_flag` is set to `true`, then events are read from a FIFO, created
+/// by bspwm, instead of the socket. Its path is returned by
+/// [`Subscriber::fifo_path`] and it's removed, when subscriber is dropped.
+///
+/// If `count` is `Some(x)`, then it stops returning subscription events
+/// after having received `x` events.
+pub fn subscribe(
+    fifo_flag: bool,
+    count: Option<u32>,
+    subscriptions: &[Subscription],
+) -> Result<Subscriber, ReplyError> {
+    BspcClient::new().subscribe(fifo_flag, count, subscriptions)
+}
+
+/// Subscribes to the given events in FIFO mode and returns path to the FIFO
+/// without opening it, see [`BspcClient::subscribe_fifo`].
+pub fn subscribe_fifo(
+    count: Option<u32>,
+    subscriptions: &[Subscription],
+) -> Result<PathBuf, ReplyError> {
+    BspcClient::new().subscribe_fifo(count, subscriptions)
+}
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::{FakeBspwm, EVENTS};
+
+    /// All events, that bspwm can emit, as listed in `bspc(1)`.
+    const BSPWM_EVENTS: [&str; 29] = [
+        "report",
+        "monitor_add",
+        "monitor_rename",
+        "monitor_remove",
+        "monitor_swap",
+        "monitor_focus",
+        "monitor_geometry",
+        "desktop_add",
+        "desktop_rename",
+        "desktop_remove",
+        "desktop_swap",
+        "desktop_transfer",
+        "desktop_focus",
+        "desktop_activate",
+        "desktop_layout",
+        "node_add",
+        "node_remove",
+        "node_swap",
+        "node_transfer",
+        "node_focus",
+        "node_activate",
+        "node_presel",
+        "node_stack",
+        "node_geometry",
+        "node_state",
+        "node_flag",
+        "node_layer",
+        "pointer_action",
+        "all",
+    ];
+
+    #[test]
+    fn subscriptions_cover_all_events() {
+        let subscriptions: Vec<_> =
+            Subscription::iter().map(|x| x.to_string()).collect();
+
+        for event in BSPWM_EVENTS {
+            assert!(subscriptions.iter().any(|x| x == event), "{event}");
+        }
+
+        for subscription in &subscriptions {
+            let is_group =
+                ["monitor", "desktop", "node"].contains(&subscription.as_str());
+
+            assert!(
+                is_group || BSPWM_EVENTS.contains(&subscription.as_str()),
+                "{subscription}"
+            );
+        }
+    }
+
+    #[test]
+    fn subscription_matches_events() {
+        for line in EVENTS.lines() {
+            let event = Event::parse_strict(line).unwrap();
+            let name = event.name();
+
+            assert!(Subscription::All.matches(&event), "{line}");
+
+            for subscription in Subscription::iter() {
+                let expected = match subscription {
+                    Subscription::All => true,
+                    Subscription::Monitor
+                    | Subscription::Desktop
+                    | Subscription::Node => {
+                        name.starts_with(&format!("{subscription}_"))
+                    }
+                    _ => subscription.to_string() == name,
+                };
+
+                assert_eq!(subscription.matches(&event), expected, "{line}");
+            }
+        }
+
+        assert!(Subscription::Report.matches(&Event::Reconnected));
+    }
+
+    #[test]
+    fn subscriber_strict_mode() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let client = bspwm.client();
+
+        let mut subscriber =
+            client.subscribe(false, None, &[Subscription::All]).unwrap();
+        let mut strict_subscriber =
+            client.subscribe(false, None, &[Subscription::All]).unwrap();
+        strict_subscriber.set_strict(true);
+
+        assert!(!subscriber.is_strict());
+        assert!(strict_subscriber.is_strict());
+
+        assert!(bspwm.wait_for_subscribers(2, Duration::from_secs(5)));
+        bspwm.emit("node_teleport 0x00400002");
+
+        let event = subscriber.events().next().unwrap().unwrap();
+        assert!(
+            matches!(event, Event::Unknown { name, .. } if name == "node_teleport")
+        );
+
+        let event = strict_subscriber.events().next().unwrap();
+        assert!(matches!(event, Err(ReplyError::ParseError(_))));
+    }
+
+    #[test]
+    fn subscriber_status_prefix() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.set_config("status_prefix", "MyBar");
+
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::Report])
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        bspwm.emit("MyBarmHDMI1:O1:LT:MeDP1:fmusic:LM");
+
+        match subscriber.events().next() {
+            Some(Ok(Event::Report(report))) => {
+                let names: Vec<_> =
+                    report.monitors.iter().map(|x| &x.name).collect();
+
+                assert_eq!(names, ["HDMI1", "eDP1"]);
+                assert_eq!(report.focused_monitor().unwrap().name, "eDP1");
+            }
+            event => panic!("Unexpected event: {event:?}"),
+        }
+    }
+
+    #[test]
+    fn subscriber_tracks_names() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(
+                false,
+                None,
+                &[Subscription::Desktop, Subscription::MonitorRename],
+            )
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        assert_eq!(
+            bspwm.requests(),
+            vec![
+                vec!["wm", "-d"],
+                vec!["subscribe", "desktop", "monitor_rename"]
+            ]
+        );
+
+        bspwm.emit("monitor_rename 0x0020000A HDMI1 left screen");
+        bspwm.emit("desktop_rename 0x00200002 0x00200008 2 web 2 web code");
+        bspwm.emit("desktop_rename 0x00200002 0x00200008 2 web code web");
+        bspwm.emit("desktop_add 0x00200002 0x0020000C my desk");
+        bspwm.emit("desktop_rename 0x00200002 0x0020000C my desk my desk 2");
+
+        let names: Vec<_> = subscriber
+            .events()
+            .take(5)
+            .filter_map(|event| match event.unwrap() {
+                Event::MonitorEvent(MonitorEvent::MonitorRename(info)) => {
+                    Some((info.old_name, info.new_name))
+                }
+                Event::DesktopEvent(DesktopEvent::DesktopRename(info)) => {
+                    Some((info.old_name, info.new_name))
+                }
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            [
+                ("HDMI1", "left screen"),
+                ("2 web", "2 web code"),
+                ("2 web code", "web"),
+                ("my desk", "my desk 2"),
+            ]
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+        );
+    }
+
+    #[test]
+    fn subscriber_count() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(false, Some(2), &[Subscription::NodeFocus])
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        let line = "node_focus 0x00200002 0x00200007 0x00400002";
+        bspwm.emit(line);
+        bspwm.emit(line);
+        bspwm.emit(line);
+
+        let events: Vec<_> = subscriber.events().collect();
+
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|x| x.is_ok()));
+        assert!(subscriber.is_closed());
+        assert!(subscriber.events().next().is_none());
+    }
+
+    #[test]
+    fn subscriber_filter_by_id() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::All])
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.emit("node_focus 0x00200002 0x00200008 0x00400003");
+        bspwm.emit("node_stack 0x00400003 below 0x00400002");
+        bspwm.emit("desktop_focus 0x00200002 0x00200007");
+        bspwm.emit("WMeDP1:O1:LT:TT:G");
+        bspwm.close_subscribers();
+
+        let lines: Vec<_> = subscriber
+            .events()
+            .monitor(0x00200002)
+            .node(0x00400002)
+            .map(|x| x.unwrap().name().to_string())
+            .collect();
+
+        assert_eq!(lines, vec!["node_focus"]);
+
+        let event: Event =
+            "node_stack 0x00400003 below 0x00400002".parse().unwrap();
+        assert_eq!(event.node_ids(), vec![0x00400003, 0x00400002]);
+        assert!(event.monitor_ids().is_empty());
+
+        let event: Event =
+            "desktop_focus 0x00200002 0x00200007".parse().unwrap();
+        assert_eq!(event.monitor_ids(), vec![0x00200002]);
+        assert_eq!(event.desktop_ids(), vec![0x00200007]);
+        assert!(event.node_ids().is_empty());
+    }
+
+    #[test]
+    fn subscriber_closed_by_bspwm() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::All])
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        bspwm.close_subscribers();
+
+        assert!(subscriber.events().next().is_none());
+        assert!(subscriber.is_closed());
+    }
+
+    #[test]
+    fn subscriber_shutdown() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::All])
+            .unwrap();
+        let handle = subscriber.shutdown_handle();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            handle.shutdown().unwrap();
+        });
+
+        // Blocks until shut down from another thread
+        assert!(subscriber.events().next().is_none());
+        assert!(subscriber.is_closed());
+
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn subscriber_close() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::All])
+            .unwrap();
+        let handle = subscriber.shutdown_handle();
+
+        assert!(!handle.is_shutdown());
+        subscriber.close().unwrap();
+        assert!(handle.is_shutdown());
+
+        let subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::All])
+            .unwrap();
+        let handle = subscriber.shutdown_handle();
+
+        drop(subscriber);
+        assert!(handle.is_shutdown());
+    }
+
+    struct BrokenTransport;
+
+    struct BrokenConnection {
+        data: io::Cursor<Vec<u8>>,
+    }
+
+    impl Transport for BrokenTransport {
+        type Connection = BrokenConnection;
+
+        fn connect(&self) -> Result<BrokenConnection, ReplyError> {
+            let line = "node_focus 0x00200002 0x00200007 0x00400002\n";
+
+            Ok(BrokenConnection {
+                data: io::Cursor::new(line.as_bytes().to_vec()),
+            })
+        }
+    }
+
+    impl BspcCommunication for BrokenConnection {
+        fn send_message(&mut self, _message: &str) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn receive_message(&mut self) -> Result<Vec<String>, ReplyError> {
+            Err(ReplyError::NoReply)
+        }
+    }
+
+    impl Read for BrokenConnection {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.data.read(buf)? {
+                0 => Err(io::ErrorKind::ConnectionReset.into()),
+                n => Ok(n),
+            }
+        }
+    }
+
+    #[test]
+    fn subscriber_read_error() {
+        let client = BspcClient::with_transport(BrokenTransport);
+        let mut subscriber = client
+            .subscribe(false, None, &[Subscription::Node])
+            .unwrap();
+        let mut events = subscriber.events();
+
+        assert!(matches!(events.next(), Some(Ok(Event::NodeEvent(_)))));
+        assert!(matches!(
+            events.next(),
+            Some(Err(ReplyError::ConnectionError(_)))
+        ));
+        assert!(events.next().is_none());
+    }
+
+    #[test]
+    fn subscriber_fifo() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(true, Some(2), &[Subscription::NodeFocus])
+            .unwrap();
+        let fifo_path = subscriber.fifo_path().unwrap().to_path_buf();
+
+        assert!(fifo_path.exists());
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        assert_eq!(
+            bspwm.requests(),
+            vec![vec!["subscribe", "--fifo", "--count", "2", "node_focus"]]
+        );
+
+        let line = "node_focus 0x00200002 0x00200007 0x00400002";
+        bspwm.emit(line);
+        bspwm.emit(line);
+
+        let events: Vec<_> = subscriber.events().collect();
+        assert_eq!(events.len(), 2);
+        assert!(subscriber.is_closed());
+
+        drop(subscriber);
+        assert!(!fifo_path.exists());
+    }
+
+    #[test]
+    fn subscriber_fifo_shutdown() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(true, None, &[Subscription::All])
+            .unwrap();
+        let handle = subscriber.shutdown_handle();
+
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            handle.shutdown().unwrap();
+        });
+
+        assert!(subscriber.events().next().is_none());
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn subscribe_fifo_for_child() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let fifo_path = bspwm
+            .client()
+            .subscribe_fifo(None, &[Subscription::NodeFocus])
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        assert_eq!(
+            bspwm.requests(),
+            vec![vec!["subscribe", "--fifo", "node_focus"]]
+        );
+
+        let child = Command::new("head")
+            .args(["-n", "1"])
+            .arg(&fifo_path)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let line = "node_focus 0x00200002 0x00200007 0x00400002";
+        bspwm.emit(line);
+
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            line.to_string() + "\n"
+        );
+        assert!(fifo_path.exists());
+    }
+
+    #[test]
+    fn corpus_covers_all_events() {
+        for event in BSPWM_EVENTS {
+            if event == "all" {
+                continue;
+            }
+
+            let covered = EVENTS.lines().any(|line| {
+                line.starts_with(&format!("{event} "))
+                    || (event == "report" && line.starts_with('W'))
+            });
+
+            assert!(covered, "{event}");
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::{Event, ReceiveTime, ShutdownHandle, Subscriber, Subscription};
+use crate::client::BspcClient;
+use crate::errors::ReplyError;
+use crate::socket::{Transport, UnixSocketTransport};
+
+/// Delays between attempts to reconnect to bspwm. Delay starts with
+/// `initial` and doubles after every failed attempt, up to `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+
+    /// Number of attempts, after which reconnecting stops. It never stops,
+    /// if it is `None`.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(5),
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns delay before the given attempt, starting from `0`.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+}
+
+/// Subscriber, that reconnects to bspwm, when connection is lost, e.g.
+/// after `bspc wm -r` or crash, and subscribes to the same events again.
+///
+/// It is an iterator over events, that returns [`Event::Reconnected`] after
+/// every reconnect. It ends, when it is shut down or reconnecting fails
+/// more, than [`Backoff::max_attempts`] times.
+pub struct ReconnectingSubscriber<T: Transport = UnixSocketTransport> {
+    client: BspcClient<T>,
+    subscriptions: Vec<Subscription>,
+    backoff: Backoff,
+    strict: bool,
+    subscriber: Option<Subscriber>,
+    current: Arc<Mutex<Option<ShutdownHandle>>>,
+    shutdown: ShutdownHandle,
+    is_closed: bool,
+}
+
+impl<T: Transport> fmt::Debug for ReconnectingSubscriber<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReconnectingSubscriber")
+            .field("subscriptions", &self.subscriptions)
+            .field("backoff", &self.backoff)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Transport> ReconnectingSubscriber<T> {
+    fn new(
+        client: BspcClient<T>,
+        subscriptions: &[Subscription],
+    ) -> Result<Self, ReplyError> {
+        let current: Arc<Mutex<Option<ShutdownHandle>>> = Default::default();
+
+        // Shuts down subscriber, that is currently connected
+        let shutdown = {
+            let current = Arc::clone(&current);
+
+            ShutdownHandle::new(Some(Box::new(move || {
+                match &*current.lock().unwrap() {
+                    Some(handle) => handle.shutdown(),
+                    None => Ok(()),
+                }
+            })))
+        };
+
+        let mut subscriber = Self {
+            client,
+            subscriptions: subscriptions.to_vec(),
+            backoff: Backoff::default(),
+            strict: false,
+            subscriber: None,
+            current,
+            shutdown,
+            is_closed: false,
+        };
+
+        subscriber.subscribe()?;
+
+        Ok(subscriber)
+    }
+
+    /// Sets delays between attempts to reconnect.
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Returns delays between attempts to reconnect.
+    pub fn backoff(&self) -> &Backoff {
+        &self.backoff
+    }
+
+    /// Sets strict mode, see [`Subscriber::set_strict`].
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+
+        if let Some(subscriber) = &mut self.subscriber {
+            subscriber.set_strict(strict);
+        }
+    }
+
+    /// Returns events, this subscriber is subscribed to.
+    pub fn subscriptions(&self) -> &[Subscription] {
+        &self.subscriptions
+    }
+
+    /// Returns handle, that can shut down this subscriber from another
+    /// thread, including while it waits to reconnect.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    fn subscribe(&mut self) -> Result<(), ReplyError> {
+        let mut subscriber =
+            self.client.subscribe(false, None, &self.subscriptions)?;
+        subscriber.set_strict(self.strict);
+
+        *self.current.lock().unwrap() = Some(subscriber.shutdown_handle());
+        self.subscriber = Some(subscriber);
+
+        // Handle could be used before it was stored
+        if self.shutdown.is_shutdown() {
+            self.subscriber = None;
+        }
+
+        Ok(())
+    }
+
+    /// Reconnects with backoff. Returns `Ok(false)`, if it was shut down.
+    fn reconnect(&mut self) -> Result<bool, ReplyError> {
+        let mut attempt = 0;
+
+        loop {
+            if !self.sleep(self.backoff.delay(attempt)) {
+                return Ok(false);
+            }
+
+            match self.subscribe() {
+                Ok(()) => return Ok(!self.shutdown.is_shutdown()),
+
+                Err(err) => {
+                    attempt += 1;
+
+                    if let Some(max_attempts) = self.backoff.max_attempts {
+                        if attempt >= max_attempts {
+                            return Err(err);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sleeps for `duration`, waking up to check, if it was shut down.
+    /// Returns `false`, if it was.
+    fn sleep(&self, duration: Duration) -> bool {
+        let start = Instant::now();
+
+        while !self.shutdown.is_shutdown() {
+            let elapsed = start.elapsed();
+
+            if elapsed >= duration {
+                return true;
+            }
+
+            thread::sleep((duration - elapsed).min(Duration::from_millis(50)));
+        }
+
+        false
+    }
+}
+
+impl<T: Transport> Iterator for ReconnectingSubscriber<T> {
+    type Item = Result<Event, ReplyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_closed || self.shutdown.is_shutdown() {
+            return None;
+        }
+
+        if let Some(subscriber) = &mut self.subscriber {
+            match subscriber.events().next() {
+                Some(Ok(event)) => return Some(Ok(event)),
+
+                // Connection was lost, so reconnect below
+                Some(Err(ReplyError::ConnectionError(_))) | None => {}
+
+                Some(Err(err)) => return Some(Err(err)),
+            }
+        }
+
+        self.subscriber = None;
+        *self.current.lock().unwrap() = None;
+
+        match self.reconnect() {
+            Ok(true) => Some(Ok(Event::Reconnected)),
+
+            Ok(false) => None,
+
+            Err(err) => {
+                self.is_closed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<T: Transport> ReceiveTime for ReconnectingSubscriber<T> {
+    fn received_at(&self) -> Option<(Instant, SystemTime)> {
+        self.subscriber.as_ref()?.received_at
+    }
+}
+
+impl<T: Transport + Clone> BspcClient<T> {
+    /// Subscribes to the given events, reconnecting and subscribing again,
+    /// when connection to bspwm is lost. See [`ReconnectingSubscriber`].
+    pub fn subscribe_reconnecting(
+        &self,
+        subscriptions: &[Subscription],
+    ) -> Result<ReconnectingSubscriber<T>, ReplyError> {
+        ReconnectingSubscriber::new(self.clone(), subscriptions)
+    }
+}
+
+/// Subscribes to the given events, reconnecting and subscribing again, when
+/// connection to bspwm is lost. See [`ReconnectingSubscriber`].
+pub fn subscribe_reconnecting(
+    subscriptions: &[Subscription],
+) -> Result<ReconnectingSubscriber, ReplyError> {
+    BspcClient::new().subscribe_reconnecting(subscriptions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::FakeBspwm;
+
+    const NODE_FOCUS: &str = "node_focus 0x00200002 0x00200007 0x00400002";
+
+    fn backoff(max_attempts: Option<u32>) -> Backoff {
+        Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(40),
+            max_attempts,
+        }
+    }
+
+    #[test]
+    fn backoff_delay() {
+        let backoff = backoff(None);
+
+        assert_eq!(backoff.delay(0), Duration::from_millis(10));
+        assert_eq!(backoff.delay(1), Duration::from_millis(20));
+        assert_eq!(backoff.delay(2), Duration::from_millis(40));
+        assert_eq!(backoff.delay(3), Duration::from_millis(40));
+        assert_eq!(backoff.delay(100), Duration::from_millis(40));
+    }
+
+    #[test]
+    fn reconnect_after_restart() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let socket_path = bspwm.socket_path().to_path_buf();
+        let subscriptions = [Subscription::NodeFocus, Subscription::Report];
+
+        let mut subscriber = bspwm
+            .client()
+            .subscribe_reconnecting(&subscriptions)
+            .unwrap()
+            .with_backoff(backoff(None));
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        bspwm.emit(NODE_FOCUS);
+        assert!(matches!(subscriber.next(), Some(Ok(Event::NodeEvent(_)))));
+
+        // Restart bspwm, after subscriber notices, that it's gone
+        drop(bspwm);
+
+        let restarted = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            let bspwm = FakeBspwm::start_at(socket_path).unwrap();
+
+            assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+            bspwm.emit(NODE_FOCUS);
+            bspwm
+        });
+
+        assert!(matches!(subscriber.next(), Some(Ok(Event::Reconnected))));
+        assert!(matches!(subscriber.next(), Some(Ok(Event::NodeEvent(_)))));
+
+        let bspwm = restarted.join().unwrap();
+        assert_eq!(
+            bspwm.requests(),
+            vec![
+                vec!["config", "status_prefix"],
+                vec!["subscribe", "node_focus", "report"]
+            ]
+        );
+    }
+
+    #[test]
+    fn reconnect_gives_up() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe_reconnecting(&[Subscription::All])
+            .unwrap()
+            .with_backoff(backoff(Some(3)));
+
+        drop(bspwm);
+
+        assert!(matches!(subscriber.next(), Some(Err(_))));
+        assert!(subscriber.next().is_none());
+    }
+
+    #[test]
+    fn shutdown_while_reconnecting() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe_reconnecting(&[Subscription::All])
+            .unwrap()
+            .with_backoff(backoff(None));
+        let handle = subscriber.shutdown_handle();
+
+        drop(bspwm);
+
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            handle.shutdown().unwrap();
+        });
+
+        assert!(subscriber.next().is_none());
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn shutdown_while_connected() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe_reconnecting(&[Subscription::All])
+            .unwrap();
+        let handle = subscriber.shutdown_handle();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            handle.shutdown().unwrap();
+        });
+
+        assert!(subscriber.next().is_none());
+        thread.join().unwrap();
+    }
+}
@@ -0,0 +1,259 @@
+use std::iter::FusedIterator;
+use std::ops::Deref;
+use std::time::{Instant, SystemTime};
+
+use super::*;
+
+/// Event together with the time it was received and its sequence number,
+/// that is returned by [`Timestamps`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timestamped<T> {
+    pub event: T,
+
+    /// Monotonic time, when line of the event was read. Use it for
+    /// measuring intervals between events.
+    pub instant: Instant,
+
+    /// Wall-clock time, when line of the event was read. Use it for matching
+    /// events with other logs.
+    pub system_time: SystemTime,
+
+    /// Number of the event in the stream, starting from 0. Errors don't
+    /// have numbers, so numbers of events go without gaps.
+    pub seq: u64,
+}
+
+impl<T> Timestamped<T> {
+    /// Returns the event without timestamps.
+    pub fn into_inner(self) -> T {
+        self.event
+    }
+
+    /// Applies `f` to the event, keeping timestamps.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Timestamped<U> {
+        Timestamped {
+            event: f(self.event),
+            instant: self.instant,
+            system_time: self.system_time,
+            seq: self.seq,
+        }
+    }
+}
+
+impl<T> Deref for Timestamped<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.event
+    }
+}
+
+/// Source of events, that knows, when the last returned event was read.
+/// It is implemented by subscribers, so that [`Timestamps`] doesn't count time
+/// spent on parsing and filtering of events.
+pub trait ReceiveTime {
+    /// Returns monotonic and wall-clock time, when line of the last returned
+    /// event was read, or `None`, if it wasn't read from bspwm (e.g.
+    /// [`Event::Reconnected`]), in which case event is stamped, when it's
+    /// returned. By default it returns `None`.
+    fn received_at(&self) -> Option<(Instant, SystemTime)> {
+        None
+    }
+}
+
+/// Iterator, that stamps events with the time they were received and their
+/// sequence number. It is created with [`EventIterator::timestamped`],
+/// [`ReconnectingSubscriber::timestamped`] or [`Timestamps::new`], and is
+/// also a `Stream` over async subscriber, returned by
+/// `asynchronous::Subscriber::timestamped` with `tokio` feature.
+///
+/// Sequence numbers are counted by every `Timestamps` separately and keep
+/// growing after [`Event::Reconnected`], so time between it and the
+/// previous event shows, how long events were lost for.
+///
+/// Events are stamped right after their line is read, before it's parsed,
+/// see [`ReceiveTime`]. Time, that line spends in the socket buffer of the
+/// kernel, isn't counted, and neither is time in the buffer of subscriber,
+/// if several lines were read at once, so events, that come in bursts, are
+/// stamped later than bspwm sent them.
+///
+/// ```no_run
+/// use bspc_rs::events::{self, Subscription};
+///
+/// let mut subscriber = events::subscribe(false, None, &[Subscription::All])
+///     .unwrap();
+///
+/// for event in subscriber.events().timestamped() {
+///     let event = event.unwrap();
+///     println!("#{} at {:?}: {}", event.seq, event.system_time, event.event);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Timestamps<I> {
+    inner: I,
+    next_seq: u64,
+}
+
+impl<I> Timestamps<I> {
+    /// Stamps events of the given iterator.
+    pub fn new(inner: I) -> Self {
+        Self { inner, next_seq: 0 }
+    }
+
+    /// Returns reference to the underlying iterator.
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    /// Returns mutable reference to the underlying iterator.
+    pub fn get_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Returns the underlying iterator.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// Returns sequence number, that the next event will get.
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    pub(crate) fn stamp<T>(&mut self, event: T) -> Timestamped<T>
+    where
+        I: ReceiveTime,
+    {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        let (instant, system_time) = self
+            .inner
+            .received_at()
+            .unwrap_or_else(|| (Instant::now(), SystemTime::now()));
+
+        Timestamped {
+            event,
+            instant,
+            system_time,
+            seq,
+        }
+    }
+}
+
+impl<I, T> Iterator for Timestamps<I>
+where
+    I: Iterator<Item = Result<T, ReplyError>> + ReceiveTime,
+{
+    type Item = Result<Timestamped<T>, ReplyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.inner.next()?;
+        Some(event.map(|event| self.stamp(event)))
+    }
+}
+
+impl<I, T> FusedIterator for Timestamps<I> where
+    I: FusedIterator<Item = Result<T, ReplyError>> + ReceiveTime
+{
+}
+
+impl<'a> ReceiveTime for EventIterator<'a> {
+    fn received_at(&self) -> Option<(Instant, SystemTime)> {
+        self.subscriber.received_at
+    }
+}
+
+impl<'a> EventIterator<'a> {
+    /// Returns iterator, that stamps events with the time they were received
+    /// and their sequence number, see [`Timestamps`].
+    pub fn timestamped(self) -> Timestamps<Self> {
+        Timestamps::new(self)
+    }
+}
+
+impl<T: Transport> ReconnectingSubscriber<T> {
+    /// Returns iterator, that stamps events with the time they were received
+    /// and their sequence number, see [`Timestamps`].
+    pub fn timestamped(self) -> Timestamps<Self> {
+        Timestamps::new(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::FakeBspwm;
+
+    #[test]
+    fn stamp_events() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::All])
+            .unwrap();
+        subscriber.set_strict(true);
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        let before = SystemTime::now();
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.emit("node_focus 0x00200002 nonsense");
+        bspwm.emit("desktop_focus 0x00200002 0x00200007");
+        bspwm.close_subscribers();
+
+        let mut events = subscriber.events().timestamped();
+
+        let first = events.next().unwrap().unwrap();
+        assert!(events.next().unwrap().is_err());
+        let second = events.next().unwrap().unwrap();
+
+        assert_eq!(
+            events.get_ref().received_at(),
+            Some((second.instant, second.system_time)),
+        );
+
+        assert!(events.next().is_none());
+        assert_eq!(events.next_seq(), 2);
+
+        assert_eq!((first.seq, second.seq), (0, 1));
+        assert_eq!(second.name(), "desktop_focus");
+        assert!(first.instant <= second.instant);
+        assert!(first.system_time >= before);
+    }
+
+    #[test]
+    fn stamp_with_read_time() {
+        struct Received<I>(I, Option<(Instant, SystemTime)>);
+
+        impl<I: Iterator> Iterator for Received<I> {
+            type Item = I::Item;
+
+            fn next(&mut self) -> Option<I::Item> {
+                self.0.next()
+            }
+        }
+
+        impl<I> ReceiveTime for Received<I> {
+            fn received_at(&self) -> Option<(Instant, SystemTime)> {
+                self.1
+            }
+        }
+
+        let read_at = (Instant::now(), SystemTime::UNIX_EPOCH);
+        let events = [Ok(Event::Reconnected), Ok(Event::Reconnected)];
+        let mut events =
+            Timestamps::new(Received(events.into_iter(), Some(read_at)));
+
+        let first = events.next().unwrap().unwrap();
+        assert_eq!((first.instant, first.system_time), read_at);
+
+        // Events, that weren't read, are stamped, when they're returned
+        events.get_mut().1 = None;
+        let second = events.next().unwrap().unwrap();
+        assert!(second.instant >= read_at.0);
+        assert!(second.system_time > read_at.1);
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+
+use super::*;
+
+/// Info of a single event type, that can be subscribed to with
+/// [`BspcClient::subscribe_typed`].
+pub trait EventInfo: Sized {
+    /// Subscription, that bspwm sends this event for.
+    const SUBSCRIPTION: Subscription;
+
+    /// Returns info, if event is of this type.
+    fn from_event(event: Event) -> Option<Self>;
+
+    /// Returns reference to info, if event is of this type.
+    fn from_event_ref(event: &Event) -> Option<&Self>;
+}
+
+macro_rules! impl_event_info {
+    ($info:ty, $subscription:ident, $group:ident :: $variant:ident) => {
+        impl EventInfo for $info {
+            const SUBSCRIPTION: Subscription = Subscription::$subscription;
+
+            fn from_event(event: Event) -> Option<Self> {
+                match event {
+                    Event::$group($group::$variant(info)) => Some(info),
+                    _ => None,
+                }
+            }
+
+            fn from_event_ref(event: &Event) -> Option<&Self> {
+                match event {
+                    Event::$group($group::$variant(info)) => Some(info),
+                    _ => None,
+                }
+            }
+        }
+    };
+
+    ($info:ty, $subscription:ident, $variant:ident) => {
+        impl EventInfo for $info {
+            const SUBSCRIPTION: Subscription = Subscription::$subscription;
+
+            fn from_event(event: Event) -> Option<Self> {
+                match event {
+                    Event::$variant(info) => Some(info),
+                    _ => None,
+                }
+            }
+
+            fn from_event_ref(event: &Event) -> Option<&Self> {
+                match event {
+                    Event::$variant(info) => Some(info),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_event_info!(ReportInfo, Report, Report);
+impl_event_info!(PointerActionInfo, PointerAction, PointerAction);
+
+impl_event_info!(MonitorAddInfo, MonitorAdd, MonitorEvent::MonitorAdd);
+impl_event_info!(
+    MonitorRenameInfo,
+    MonitorRename,
+    MonitorEvent::MonitorRename
+);
+impl_event_info!(
+    MonitorRemoveInfo,
+    MonitorRemove,
+    MonitorEvent::MonitorRemove
+);
+impl_event_info!(MonitorSwapInfo, MonitorSwap, MonitorEvent::MonitorSwap);
+impl_event_info!(MonitorFocusInfo, MonitorFocus, MonitorEvent::MonitorFocus);
+impl_event_info!(
+    MonitorGeometryInfo,
+    MonitorGeometry,
+    MonitorEvent::MonitorGeometry
+);
+
+impl_event_info!(DesktopAddInfo, DesktopAdd, DesktopEvent::DesktopAdd);
+impl_event_info!(
+    DesktopRenameInfo,
+    DesktopRename,
+    DesktopEvent::DesktopRename
+);
+impl_event_info!(
+    DesktopRemoveInfo,
+    DesktopRemove,
+    DesktopEvent::DesktopRemove
+);
+impl_event_info!(DesktopSwapInfo, DesktopSwap, DesktopEvent::DesktopSwap);
+impl_event_info!(
+    DesktopTransferInfo,
+    DesktopTransfer,
+    DesktopEvent::DesktopTransfer
+);
+impl_event_info!(DesktopFocusInfo, DesktopFocus, DesktopEvent::DesktopFocus);
+impl_event_info!(
+    DesktopActivateInfo,
+    DesktopActivate,
+    DesktopEvent::DesktopActivate
+);
+impl_event_info!(
+    DesktopLayoutInfo,
+    DesktopLayout,
+    DesktopEvent::DesktopLayout
+);
+
+impl_event_info!(NodeAddInfo, NodeAdd, NodeEvent::NodeAdd);
+impl_event_info!(NodeRemoveInfo, NodeRemove, NodeEvent::NodeRemove);
+impl_event_info!(NodeSwapInfo, NodeSwap, NodeEvent::NodeSwap);
+impl_event_info!(NodeTransferInfo, NodeTransfer, NodeEvent::NodeTransfer);
+impl_event_info!(NodeFocusInfo, NodeFocus, NodeEvent::NodeFocus);
+impl_event_info!(NodeActivateInfo, NodeActivate, NodeEvent::NodeActivate);
+impl_event_info!(NodePreselInfo, NodePresel, NodeEvent::NodePresel);
+impl_event_info!(NodeStackInfo, NodeStack, NodeEvent::NodeStack);
+impl_event_info!(NodeGeometryInfo, NodeGeometry, NodeEvent::NodeGeometry);
+impl_event_info!(NodeStateInfo, NodeState, NodeEvent::NodeState);
+impl_event_info!(NodeFlagInfo, NodeFlag, NodeEvent::NodeFlag);
+impl_event_info!(NodeLayerInfo, NodeLayer, NodeEvent::NodeLayer);
+
+/// Subscriber to a single event type, that is returned by
+/// [`BspcClient::subscribe_typed`]. It is an iterator over infos of the
+/// events, that ends like [`EventIterator`].
+pub struct TypedSubscriber<I: EventInfo> {
+    subscriber: Subscriber,
+    marker: PhantomData<fn() -> I>,
+}
+
+impl<I: EventInfo> fmt::Debug for TypedSubscriber<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TypedSubscriber")
+            .field("subscription", &I::SUBSCRIPTION)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I: EventInfo> TypedSubscriber<I> {
+    /// Returns handle, that can shut down this subscriber from another
+    /// thread.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.subscriber.shutdown_handle()
+    }
+
+    /// Returns the underlying subscriber.
+    pub fn into_inner(self) -> Subscriber {
+        self.subscriber
+    }
+}
+
+impl<I: EventInfo> Iterator for TypedSubscriber<I> {
+    type Item = Result<I, ReplyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for event in self.subscriber.events() {
+            match event {
+                Ok(event) => {
+                    if let Some(info) = I::from_event(event) {
+                        return Some(Ok(info));
+                    }
+                }
+
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        None
+    }
+}
+
+impl<I: EventInfo> FusedIterator for TypedSubscriber<I> {}
+
+impl<T: Transport> BspcClient<T> {
+    /// Subscribes only to events of type `I`, returning their infos, e.g.
+    /// `client.subscribe_typed::<NodeFocusInfo>()`.
+    pub fn subscribe_typed<I: EventInfo>(
+        &self,
+    ) -> Result<TypedSubscriber<I>, ReplyError> {
+        let mut subscriber = self.subscribe(false, None, &[I::SUBSCRIPTION])?;
+
+        // Only events of requested type are expected, so others are errors
+        subscriber.set_strict(true);
+
+        Ok(TypedSubscriber {
+            subscriber,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Subscribes only to events of type `I`, returning their infos, e.g.
+/// `subscribe_typed::<NodeFocusInfo>()`.
+pub fn subscribe_typed<I: EventInfo>() -> Result<TypedSubscriber<I>, ReplyError>
+{
+    BspcClient::new().subscribe_typed()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::FakeBspwm;
+    use std::time::Duration;
+
+    #[test]
+    fn subscribe_node_focus() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber =
+            bspwm.client().subscribe_typed::<NodeFocusInfo>().unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+        assert_eq!(bspwm.requests(), vec![vec!["subscribe", "node_focus"]]);
+
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.close_subscribers();
+
+        let infos: Vec<_> = subscriber.by_ref().map(Result::unwrap).collect();
+
+        assert_eq!(
+            infos,
+            vec![NodeFocusInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200007,
+                node_id: 0x00400002,
+            }]
+        );
+        assert!(subscriber.next().is_none());
+    }
+
+    #[test]
+    fn event_info_from_event() {
+        for line in crate::testing::EVENTS.lines() {
+            let event = Event::parse_strict(line).unwrap();
+
+            assert_eq!(
+                NodeFocusInfo::from_event(event.clone()).is_some(),
+                NodeFocusInfo::SUBSCRIPTION.matches(&event),
+                "{line}"
+            );
+            assert_eq!(
+                ReportInfo::from_event(event.clone()).is_some(),
+                ReportInfo::SUBSCRIPTION.matches(&event),
+                "{line}"
+            );
+        }
+    }
+}
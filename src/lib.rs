@@ -46,17 +46,22 @@ extern crate doc_comment;
 #[cfg(doctest)]
 doc_comment::doctest!("../README.md");
 
+#[cfg(feature = "tokio")]
+pub mod asynchronous;
+pub mod client;
 pub mod errors;
 pub mod events;
 pub mod properties;
 pub mod query;
 pub mod selectors;
 pub mod settings;
+pub mod socket;
 pub mod state;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod tree;
 
 mod parser;
-mod socket;
 
 pub type Id = u32;
 
@@ -85,6 +90,50 @@ mod test {
     //     Bspc::subscribe(&subscriptions, false, None).unwrap();
     // }
 
+    // Tests with `real_bspwm` prefix require running bspwm, they are run by
+    // CI under Xvfb with `cargo test -- --ignored real_bspwm`
+
+    #[test]
+    #[ignore]
+    fn real_bspwm_state() {
+        let state = state::get_current_state().unwrap();
+
+        let monitors =
+            query::query_monitors(false, None, None, None, None).unwrap();
+        let desktops =
+            query::query_desktops(false, None, None, None, None).unwrap();
+        let nodes = query::query_nodes(None, None, None, None).unwrap();
+
+        assert_eq!(
+            state.monitors.iter().map(|x| x.id).collect::<Vec<_>>(),
+            monitors
+        );
+        assert_eq!(
+            state
+                .monitors
+                .iter()
+                .flat_map(|x| x.desktops.iter().map(|x| x.id))
+                .collect::<Vec<_>>(),
+            desktops
+        );
+        assert_eq!(
+            state
+                .desktops()
+                .flat_map(|(_, desktop)| desktop.nodes().map(|x| x.id))
+                .collect::<Vec<_>>(),
+            nodes
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn real_bspwm_status() {
+        let report = state::get_status().unwrap();
+        let state = state::get_current_state().unwrap();
+
+        assert_eq!(report.monitors.len(), state.monitors.len());
+    }
+
     #[test]
     #[ignore]
     fn test_iterator() {
@@ -99,8 +148,8 @@ mod test {
         let mut subscribers = subscribe(false, None, &subscriptions).unwrap();
 
         for event in subscribers.events() {
-            match event.unwrap() {
-                Event::NodeEvent(event) => match event {
+            if let Event::NodeEvent(event) = event.unwrap() {
+                match event {
                     NodeEvent::NodeFocus(_) => {
                         println!("focus!");
                     }
@@ -109,14 +158,13 @@ mod test {
                     }
                     NodeEvent::NodeRemove(_) => break,
                     _ => {}
-                },
-                _ => {}
+                }
             }
         }
 
         for event in subscribers.events() {
-            match event.unwrap() {
-                Event::NodeEvent(event) => match event {
+            if let Event::NodeEvent(event) = event.unwrap() {
+                match event {
                     NodeEvent::NodeFocus(_) => {
                         println!("focus!");
                     }
@@ -124,8 +172,7 @@ mod test {
                         println!("flag!");
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
         }
     }
@@ -1,4 +1,5 @@
 use core::fmt;
+use std::convert::Infallible;
 use std::error::Error;
 use std::num::{ParseFloatError, ParseIntError};
 use std::str::ParseBoolError;
@@ -10,9 +11,68 @@ pub enum ParseError {
     InsufficientData,
     InvalidEvent,
     ConversionFailed,
+    /// Value failed to convert, contains the original error.
+    InvalidValue(Box<dyn Error + Send + Sync>),
+    /// JSON failed to deserialize, see [`serde_json::Error::line`] and
+    /// [`serde_json::Error::column`] for the position of the error.
+    InvalidJson(serde_json::Error),
+    /// Line failed to parse, contains the line and the error.
+    InvalidLine(Box<LineError>),
+    /// Old and new names of rename event can't be told apart, because old
+    /// name is not known and names contain spaces.
+    AmbiguousNames,
 }
 
-impl Error for ParseError {}
+/// Context of the error, that occurred while parsing a line.
+#[derive(Debug)]
+pub struct LineError {
+    /// Line, that failed to parse.
+    pub line: String,
+
+    /// Name of the event, if it is known.
+    pub event: Option<String>,
+
+    /// Index of the field, that failed to parse. Fields of events are
+    /// separated by spaces, with event name being field `0`. Fields of reports
+    /// are separated by `:`.
+    pub field: Option<usize>,
+
+    /// Type, that was expected in the field.
+    pub expected: Option<&'static str>,
+
+    /// The error, that occurred.
+    pub error: ParseError,
+}
+
+impl ParseError {
+    /// Returns context of the error, if it occurred while parsing a line.
+    pub fn line_error(&self) -> Option<&LineError> {
+        match self {
+            ParseError::InvalidLine(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns line and column of JSON error.
+    pub fn json_position(&self) -> Option<(usize, usize)> {
+        match self {
+            ParseError::InvalidJson(err) => Some((err.line(), err.column())),
+            ParseError::InvalidLine(err) => err.error.json_position(),
+            _ => None,
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::InvalidValue(err) => Some(err.as_ref()),
+            ParseError::InvalidJson(err) => Some(err),
+            ParseError::InvalidLine(err) => err.error.source(),
+            _ => None,
+        }
+    }
+}
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -26,42 +86,114 @@ impl fmt::Display for ParseError {
             ParseError::ConversionFailed => {
                 write!(f, "Conversion failed")
             }
+            ParseError::InvalidValue(err) => {
+                write!(f, "Conversion failed: {}", err)
+            }
+            ParseError::InvalidJson(err) => {
+                write!(f, "Failed to parse JSON: {}", err)
+            }
+            ParseError::InvalidLine(err) => err.fmt(f),
+            ParseError::AmbiguousNames => {
+                write!(f, "Names are ambiguous without knowing old name")
+            }
+        }
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed to parse")?;
+
+        if let Some(field) = self.field {
+            write!(f, " field {}", field)?;
+        }
+
+        if let Some(expected) = self.expected {
+            write!(f, " as {}", expected)?;
+        }
+
+        if let Some(event) = &self.event {
+            write!(f, " of '{}' event", event)?;
         }
+
+        write!(f, " in line '{}': {}", self.line, self.error)
+    }
+}
+
+impl From<Infallible> for ParseError {
+    fn from(error: Infallible) -> ParseError {
+        match error {}
     }
 }
 
 impl From<ParseIntError> for ParseError {
-    fn from(_error: ParseIntError) -> ParseError {
-        ParseError::ConversionFailed
+    fn from(error: ParseIntError) -> ParseError {
+        ParseError::InvalidValue(Box::new(error))
     }
 }
 
 impl From<ParseBoolError> for ParseError {
-    fn from(_error: ParseBoolError) -> ParseError {
-        ParseError::ConversionFailed
+    fn from(error: ParseBoolError) -> ParseError {
+        ParseError::InvalidValue(Box::new(error))
     }
 }
 
 impl From<ParseFloatError> for ParseError {
-    fn from(_error: ParseFloatError) -> ParseError {
-        ParseError::ConversionFailed
+    fn from(error: ParseFloatError) -> ParseError {
+        ParseError::InvalidValue(Box::new(error))
     }
 }
 
 impl From<serde_json::Error> for ParseError {
-    fn from(_error: serde_json::Error) -> ParseError {
-        ParseError::ConversionFailed
+    fn from(error: serde_json::Error) -> ParseError {
+        ParseError::InvalidJson(error)
     }
 }
 
 impl From<strum::ParseError> for ParseError {
-    fn from(_error: strum::ParseError) -> ParseError {
-        ParseError::ConversionFailed
+    fn from(error: strum::ParseError) -> ParseError {
+        ParseError::InvalidValue(Box::new(error))
     }
 }
 
 impl From<FromUtf8Error> for ParseError {
-    fn from(_error: FromUtf8Error) -> ParseError {
-        ParseError::ConversionFailed
+    fn from(error: FromUtf8Error) -> ParseError {
+        ParseError::InvalidValue(Box::new(error))
+    }
+}
+
+impl From<LineError> for ParseError {
+    fn from(error: LineError) -> ParseError {
+        ParseError::InvalidLine(Box::new(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::State;
+
+    #[test]
+    fn json_error_position() {
+        let json = "{\n  \"focusedMonitorId\" 1\n}";
+        let error: ParseError =
+            serde_json::from_str::<State>(json).unwrap_err().into();
+
+        assert_eq!(error.json_position(), Some((2, 22)));
+        assert!(error.to_string().contains("expected `:`"));
+        assert!(error.to_string().contains("line 2 column 22"));
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn json_type_error_position() {
+        let json =
+            "{\n  \"focusedMonitorId\": 1,\n  \"clientsCount\": \"two\"\n}";
+        let error: ParseError =
+            serde_json::from_str::<State>(json).unwrap_err().into();
+
+        assert_eq!(error.json_position(), Some((3, 23)));
+        assert!(error.to_string().contains("invalid type"));
+        assert!(error.to_string().contains("line 3 column 23"));
     }
 }
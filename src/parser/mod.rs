@@ -13,10 +13,11 @@ impl FromStr for Presel {
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let split: Vec<_> = input.split(' ').collect();
 
-        match split[0] {
-            "dir" => Ok(Self::Dir(split[1].parse()?)),
-            "ratio" => Ok(Self::Ratio(split[1].parse()?)),
-            "cancel" => Ok(Self::Cancel),
+        match split[..] {
+            ["dir", dir] => Ok(Self::Dir(dir.parse()?)),
+            ["ratio", ratio] => Ok(Self::Ratio(ratio.parse()?)),
+            ["cancel"] => Ok(Self::Cancel),
+            ["dir"] | ["ratio"] => Err(ParseError::InsufficientData),
             _ => Err(ParseError::ConversionFailed),
         }
     }
@@ -26,17 +27,14 @@ impl FromStr for Rectangle {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let split: Vec<_> = input.split(&['+', 'x'][..]).collect();
-
-        if split.len() < 4 {
-            return Err(ParseError::InsufficientData);
-        }
+        let mut split = input.split(['+', 'x']);
+        let mut next = || split.next().ok_or(ParseError::InsufficientData);
 
         Ok(Self {
-            width: split[0].parse()?,
-            height: split[1].parse()?,
-            x: split[2].parse()?,
-            y: split[3].parse()?,
+            width: next()?.parse()?,
+            height: next()?.parse()?,
+            x: next()?.parse()?,
+            y: next()?.parse()?,
         })
     }
 }
@@ -1,16 +1,57 @@
 use super::*;
 
+impl FromFields for DesktopAddInfo {
+    const EVENT: &'static str = "desktop_add";
+    const ARG_NUM: usize = 3;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
+        Ok(Self {
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            desktop_name: join_name(reply, 3, reply.len())?,
+        })
+    }
+}
+
 impl FromStr for DesktopAddInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "desktop_add", 3)?;
+        parse_info(input)
+    }
+}
 
-        Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            desktop_name: reply[3].to_string(),
-        })
+fn desktop_rename(
+    reply: &[&str],
+    old_name: Option<&str>,
+) -> Result<DesktopRenameInfo, ParseError> {
+    let (old_name, new_name) = split_names(&reply[3..], old_name)
+        .map_err(|err| line_error(reply, Some(3), Some("names"), err))?;
+
+    Ok(DesktopRenameInfo {
+        monitor_id: parse_id(reply, 1)?,
+        desktop_id: parse_id(reply, 2)?,
+        old_name,
+        new_name,
+    })
+}
+
+pub(crate) fn parse_desktop_rename(
+    input: &str,
+    old_name: Option<&str>,
+) -> Result<DesktopRenameInfo, ParseError> {
+    with_fields(input, |reply| {
+        check_fields::<DesktopRenameInfo>(reply)?;
+        desktop_rename(reply, old_name)
+    })
+}
+
+impl FromFields for DesktopRenameInfo {
+    const EVENT: &'static str = "desktop_rename";
+    const ARG_NUM: usize = 4;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
+        desktop_rename(reply, None)
     }
 }
 
@@ -18,13 +59,18 @@ impl FromStr for DesktopRenameInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "desktop_rename", 4)?;
+        parse_info(input)
+    }
+}
 
+impl FromFields for DesktopRemoveInfo {
+    const EVENT: &'static str = "desktop_remove";
+    const ARG_NUM: usize = 2;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            old_name: reply[3].to_string(),
-            new_name: reply[4].to_string(),
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
         })
     }
 }
@@ -33,11 +79,20 @@ impl FromStr for DesktopRemoveInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "desktop_remove", 2)?;
+        parse_info(input)
+    }
+}
 
+impl FromFields for DesktopSwapInfo {
+    const EVENT: &'static str = "desktop_swap";
+    const ARG_NUM: usize = 4;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
+            src_monitor_id: parse_id(reply, 1)?,
+            src_desktop_id: parse_id(reply, 2)?,
+            dst_monitor_id: parse_id(reply, 3)?,
+            dst_desktop_id: parse_id(reply, 4)?,
         })
     }
 }
@@ -46,13 +101,19 @@ impl FromStr for DesktopSwapInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "desktop_swap", 4)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for DesktopTransferInfo {
+    const EVENT: &'static str = "desktop_transfer";
+    const ARG_NUM: usize = 3;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            src_monitor_id: from_hex_to_id(reply[1])?,
-            src_desktop_id: from_hex_to_id(reply[2])?,
-            dst_monitor_id: from_hex_to_id(reply[3])?,
-            dst_desktop_id: from_hex_to_id(reply[4])?,
+            src_monitor_id: parse_id(reply, 1)?,
+            src_desktop_id: parse_id(reply, 2)?,
+            dst_monitor_id: parse_id(reply, 3)?,
         })
     }
 }
@@ -61,12 +122,18 @@ impl FromStr for DesktopTransferInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "desktop_transfer", 3)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for DesktopFocusInfo {
+    const EVENT: &'static str = "desktop_focus";
+    const ARG_NUM: usize = 2;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            src_monitor_id: from_hex_to_id(reply[1])?,
-            src_desktop_id: from_hex_to_id(reply[2])?,
-            dst_monitor_id: from_hex_to_id(reply[3])?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
         })
     }
 }
@@ -75,11 +142,18 @@ impl FromStr for DesktopFocusInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "desktop_focus", 2)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for DesktopActivateInfo {
+    const EVENT: &'static str = "desktop_activate";
+    const ARG_NUM: usize = 2;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
         })
     }
 }
@@ -88,11 +162,19 @@ impl FromStr for DesktopActivateInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "desktop_activate", 2)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for DesktopLayoutInfo {
+    const EVENT: &'static str = "desktop_layout";
+    const ARG_NUM: usize = 3;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            layout: parse_field(reply, 3)?,
         })
     }
 }
@@ -101,13 +183,38 @@ impl FromStr for DesktopLayoutInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "desktop_layout", 2)?;
+        parse_info(input)
+    }
+}
 
-        Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            layout: reply[3].parse()?,
-        })
+pub(super) fn parse_desktop_event(
+    reply: &[&str],
+    context: &EventContext,
+) -> Result<DesktopEvent, ParseError> {
+    match reply[0] {
+        "desktop_add" => Ok(DesktopEvent::DesktopAdd(parse_fields(reply)?)),
+        "desktop_rename" => {
+            check_fields::<DesktopRenameInfo>(reply)?;
+            let old_name = context.desktop_names.get(&parse_id(reply, 2)?);
+            let info = desktop_rename(reply, old_name.map(String::as_str))?;
+
+            Ok(DesktopEvent::DesktopRename(info))
+        }
+        "desktop_remove" => {
+            Ok(DesktopEvent::DesktopRemove(parse_fields(reply)?))
+        }
+        "desktop_swap" => Ok(DesktopEvent::DesktopSwap(parse_fields(reply)?)),
+        "desktop_transfer" => {
+            Ok(DesktopEvent::DesktopTransfer(parse_fields(reply)?))
+        }
+        "desktop_focus" => Ok(DesktopEvent::DesktopFocus(parse_fields(reply)?)),
+        "desktop_activate" => {
+            Ok(DesktopEvent::DesktopActivate(parse_fields(reply)?))
+        }
+        "desktop_layout" => {
+            Ok(DesktopEvent::DesktopLayout(parse_fields(reply)?))
+        }
+        _ => Err(unknown_event(reply)),
     }
 }
 
@@ -115,22 +222,8 @@ impl FromStr for DesktopEvent {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let event_type = get_event_type(input)?;
-
-        match event_type {
-            "desktop_add" => Ok(DesktopEvent::DesktopAdd(input.parse()?)),
-            "desktop_rename" => Ok(DesktopEvent::DesktopRename(input.parse()?)),
-            "desktop_remove" => Ok(DesktopEvent::DesktopRemove(input.parse()?)),
-            "desktop_swap" => Ok(DesktopEvent::DesktopSwap(input.parse()?)),
-            "desktop_transfer" => {
-                Ok(DesktopEvent::DesktopTransfer(input.parse()?))
-            }
-            "desktop_focus" => Ok(DesktopEvent::DesktopFocus(input.parse()?)),
-            "desktop_activate" => {
-                Ok(DesktopEvent::DesktopActivate(input.parse()?))
-            }
-            "desktop_layout" => Ok(DesktopEvent::DesktopLayout(input.parse()?)),
-            _ => Err(ParseError::ConversionFailed),
-        }
+        with_fields(input, |reply| {
+            parse_desktop_event(reply, &EventContext::default())
+        })
     }
 }
@@ -1,61 +1,702 @@
 use super::errors::ParseError;
-use super::utils::{from_hex_to_id, get_event_type, process_event_reply};
+use super::utils::{
+    is_unknown_event, join_name, line_error, parse_field, parse_id,
+    parse_value, split_names, unknown_event, with_fields,
+};
 use crate::events::*;
+use crate::properties::{Layout, State};
+
+use desktop_events::parse_desktop_event;
+use monitor_events::parse_monitor_event;
+use node_events::parse_node_event;
+use report::parse_report;
 
 use std::str::FromStr;
 
 pub mod desktop_events;
 pub mod monitor_events;
 pub mod node_events;
+pub mod report;
 
-impl FromStr for Event {
-    type Err = ParseError;
+/// Event info, that can be parsed from fields of event line.
+pub(super) trait FromFields: Sized {
+    /// Name of the event.
+    const EVENT: &'static str;
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        if let Ok(x) = input.parse::<NodeEvent>() {
-            return Ok(Event::NodeEvent(x));
+    /// Number of arguments, that follow event name.
+    const ARG_NUM: usize;
+
+    /// Parses info from fields, that are checked with [`check_fields`].
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError>;
+}
+
+/// Checks, that fields belong to the event of type `T`.
+pub(super) fn check_fields<T: FromFields>(
+    reply: &[&str],
+) -> Result<(), ParseError> {
+    if reply[0] != T::EVENT {
+        return Err(line_error(reply, None, None, ParseError::InvalidEvent));
+    }
+
+    if reply.len() < T::ARG_NUM + 1 {
+        let error = ParseError::InsufficientData;
+        return Err(line_error(reply, Some(reply.len()), None, error));
+    }
+
+    Ok(())
+}
+
+pub(super) fn parse_fields<T: FromFields>(
+    reply: &[&str],
+) -> Result<T, ParseError> {
+    check_fields::<T>(reply)?;
+    T::from_fields(reply)
+}
+
+pub(super) fn parse_info<T: FromFields>(input: &str) -> Result<T, ParseError> {
+    with_fields(input, parse_fields)
+}
+
+/// Parses event, returning error, if it can't be recognised. Reports and
+/// renames are parsed with status prefix and names from `context`, if they
+/// are known.
+pub(crate) fn parse_event(
+    input: &str,
+    context: &EventContext,
+) -> Result<Event, ParseError> {
+    with_fields(input, |reply| {
+        let event_type = reply[0];
+
+        if event_type.starts_with("node_") {
+            return Ok(Event::NodeEvent(parse_node_event(reply)?));
         }
 
-        if let Ok(x) = input.parse::<DesktopEvent>() {
-            return Ok(Event::DesktopEvent(x));
+        if event_type.starts_with("desktop_") {
+            let event = parse_desktop_event(reply, context)?;
+            return Ok(Event::DesktopEvent(event));
         }
 
-        if let Ok(x) = input.parse::<MonitorEvent>() {
-            return Ok(Event::MonitorEvent(x));
+        if event_type.starts_with("monitor_") {
+            let event = parse_monitor_event(reply, context)?;
+            return Ok(Event::MonitorEvent(event));
         }
 
-        if let Ok(x) = input.parse::<ReportInfo>() {
-            return Ok(Event::Report(x));
+        if event_type == "pointer_action" {
+            return Ok(Event::PointerAction(parse_fields(reply)?));
         }
 
-        if let Ok(x) = input.parse::<PointerActionInfo>() {
-            return Ok(Event::PointerAction(x));
+        // Reports always contain several items, separated by `:`
+        if !input.contains(':') {
+            return Err(unknown_event(reply));
         }
 
-        Err(ParseError::ConversionFailed)
+        match &context.status_prefix {
+            Some(prefix) => Ok(Event::Report(parse_report(input, prefix)?)),
+            None => Ok(Event::Report(input.parse()?)),
+        }
+    })
+}
+
+/// Parses event, returning [`Event::Unknown`], if its name can't be
+/// recognised.
+pub(crate) fn parse_event_or_unknown(
+    input: &str,
+    context: &EventContext,
+) -> Result<Event, ParseError> {
+    match parse_event(input, context) {
+        Err(err) if is_unknown_event(&err) => Ok(unknown(input)),
+        result => result,
     }
 }
 
-impl FromStr for PointerActionInfo {
+fn unknown(input: &str) -> Event {
+    let raw = input.trim_end_matches(['\n', '\r']);
+    let mut split = raw.split(' ');
+
+    Event::Unknown {
+        name: split.next().unwrap_or_default().to_string(),
+        args: split.map(ToString::to_string).collect(),
+        raw: raw.to_string(),
+    }
+}
+
+impl FromStr for Event {
     type Err = ParseError;
 
+    /// Parses event, returning [`Event::Unknown`], if its name can't be
+    /// recognised. Known events in wrong format are still errors. Use
+    /// [`Event::parse_strict`] to get error for unknown events too.
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "pointer_action", 5)?;
+        parse_event_or_unknown(input, &EventContext::default())
+    }
+}
 
+impl FromFields for PointerActionInfo {
+    const EVENT: &'static str = "pointer_action";
+    const ARG_NUM: usize = 5;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
-            action: reply[4].parse()?,
-            action_state: reply[5].parse()?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
+            action: parse_field(reply, 4)?,
+            action_state: parse_field(reply, 5)?,
         })
     }
 }
 
-impl FromStr for ReportInfo {
+impl FromStr for PointerActionInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        todo!();
+        parse_info(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::properties::*;
+    use crate::testing::EVENTS;
+    use std::error::Error;
+
+    fn rectangle(width: i32, height: i32, x: i32, y: i32) -> Rectangle {
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn node_state(state: State, switch: Switch) -> Event {
+        Event::NodeEvent(NodeEvent::NodeState(NodeStateInfo {
+            monitor_id: 0x00200002,
+            desktop_id: 0x00200007,
+            node_id: 0x00400002,
+            state,
+            switch,
+        }))
+    }
+
+    fn node_flag(flag: Flag, switch: Switch) -> Event {
+        Event::NodeEvent(NodeEvent::NodeFlag(NodeFlagInfo {
+            monitor_id: 0x00200002,
+            desktop_id: 0x00200007,
+            node_id: 0x00400002,
+            flag,
+            switch,
+        }))
+    }
+
+    fn node_layer(layer: Layer) -> Event {
+        Event::NodeEvent(NodeEvent::NodeLayer(NodeLayerInfo {
+            monitor_id: 0x00200002,
+            desktop_id: 0x00200007,
+            node_id: 0x00400002,
+            layer,
+        }))
+    }
+
+    fn node_presel(presel: Presel) -> Event {
+        Event::NodeEvent(NodeEvent::NodePresel(NodePreselInfo {
+            monitor_id: 0x00200002,
+            desktop_id: 0x00200007,
+            node_id: 0x00400002,
+            presel,
+        }))
+    }
+
+    fn pointer_action(action: Action, action_state: ActionState) -> Event {
+        Event::PointerAction(PointerActionInfo {
+            monitor_id: 0x00200002,
+            desktop_id: 0x00200007,
+            node_id: 0x00400002,
+            action,
+            action_state,
+        })
+    }
+
+    fn expected_events() -> Vec<Event> {
+        vec![
+            Event::MonitorEvent(MonitorEvent::MonitorAdd(MonitorAddInfo {
+                monitor_id: 0x0020000A,
+                monitor_name: "HDMI1".to_string(),
+                monitor_geometry: rectangle(1920, 1080, 1920, 0),
+            })),
+            Event::MonitorEvent(MonitorEvent::MonitorRename(
+                MonitorRenameInfo {
+                    monitor_id: 0x0020000A,
+                    old_name: "HDMI1".to_string(),
+                    new_name: "HDMI-1".to_string(),
+                },
+            )),
+            Event::MonitorEvent(MonitorEvent::MonitorRemove(
+                MonitorRemoveInfo {
+                    monitor_id: 0x0020000A,
+                },
+            )),
+            Event::MonitorEvent(MonitorEvent::MonitorSwap(MonitorSwapInfo {
+                src_monitor_id: 0x00200002,
+                dst_monitor_id: 0x0020000A,
+            })),
+            Event::MonitorEvent(MonitorEvent::MonitorFocus(MonitorFocusInfo {
+                monitor_id: 0x0020000A,
+            })),
+            Event::MonitorEvent(MonitorEvent::MonitorGeometry(
+                MonitorGeometryInfo {
+                    monitor_id: 0x00200002,
+                    monitor_geometry: rectangle(2560, 1440, 0, 0),
+                },
+            )),
+            Event::DesktopEvent(DesktopEvent::DesktopAdd(DesktopAddInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200009,
+                desktop_name: "3".to_string(),
+            })),
+            Event::DesktopEvent(DesktopEvent::DesktopRename(
+                DesktopRenameInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200009,
+                    old_name: "3".to_string(),
+                    new_name: "music".to_string(),
+                },
+            )),
+            Event::DesktopEvent(DesktopEvent::DesktopRemove(
+                DesktopRemoveInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200009,
+                },
+            )),
+            Event::DesktopEvent(DesktopEvent::DesktopSwap(DesktopSwapInfo {
+                src_monitor_id: 0x00200002,
+                src_desktop_id: 0x00200007,
+                dst_monitor_id: 0x0020000A,
+                dst_desktop_id: 0x0020000B,
+            })),
+            Event::DesktopEvent(DesktopEvent::DesktopTransfer(
+                DesktopTransferInfo {
+                    src_monitor_id: 0x00200002,
+                    src_desktop_id: 0x00200009,
+                    dst_monitor_id: 0x0020000A,
+                },
+            )),
+            Event::DesktopEvent(DesktopEvent::DesktopFocus(DesktopFocusInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200008,
+            })),
+            Event::DesktopEvent(DesktopEvent::DesktopActivate(
+                DesktopActivateInfo {
+                    monitor_id: 0x0020000A,
+                    desktop_id: 0x0020000B,
+                },
+            )),
+            Event::DesktopEvent(DesktopEvent::DesktopLayout(
+                DesktopLayoutInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200007,
+                    layout: Layout::Monocle,
+                },
+            )),
+            Event::DesktopEvent(DesktopEvent::DesktopLayout(
+                DesktopLayoutInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200007,
+                    layout: Layout::Tiled,
+                },
+            )),
+            Event::NodeEvent(NodeEvent::NodeAdd(NodeAddInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200007,
+                ip_id: 0x00000000,
+                node_id: 0x00400002,
+            })),
+            Event::NodeEvent(NodeEvent::NodeAdd(NodeAddInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200007,
+                ip_id: 0x00400002,
+                node_id: 0x00800002,
+            })),
+            Event::NodeEvent(NodeEvent::NodeRemove(NodeRemoveInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200007,
+                node_id: 0x00800002,
+            })),
+            Event::NodeEvent(NodeEvent::NodeSwap(NodeSwapInfo {
+                src_monitor_id: 0x00200002,
+                src_desktop_id: 0x00200007,
+                src_node_id: 0x00400002,
+                dst_monitor_id: 0x00200002,
+                dst_desktop_id: 0x00200007,
+                dst_node_id: 0x00800002,
+            })),
+            Event::NodeEvent(NodeEvent::NodeTransfer(NodeTransferInfo {
+                src_monitor_id: 0x00200002,
+                src_desktop_id: 0x00200007,
+                src_node_id: 0x00A00002,
+                dst_monitor_id: 0x0020000A,
+                dst_desktop_id: 0x0020000B,
+                dst_node_id: 0x00E00002,
+            })),
+            Event::NodeEvent(NodeEvent::NodeFocus(NodeFocusInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200007,
+                node_id: 0x00400002,
+            })),
+            Event::NodeEvent(NodeEvent::NodeActivate(NodeActivateInfo {
+                monitor_id: 0x0020000A,
+                desktop_id: 0x0020000B,
+                node_id: 0x00E00002,
+            })),
+            node_presel(Presel::Dir(Dir::East)),
+            node_presel(Presel::Ratio(0.3)),
+            node_presel(Presel::Cancel),
+            Event::NodeEvent(NodeEvent::NodeStack(NodeStackInfo {
+                node_id_1: 0x00400002,
+                stack: Stack::Below,
+                node_id_2: 0x00800002,
+            })),
+            Event::NodeEvent(NodeEvent::NodeStack(NodeStackInfo {
+                node_id_1: 0x00A00002,
+                stack: Stack::Above,
+                node_id_2: 0x00400002,
+            })),
+            Event::NodeEvent(NodeEvent::NodeGeometry(NodeGeometryInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200007,
+                node_id: 0x00400002,
+                node_geometry: rectangle(951, 1048, 6, 26),
+            })),
+            Event::NodeEvent(NodeEvent::NodeGeometry(NodeGeometryInfo {
+                monitor_id: 0x0020000A,
+                desktop_id: 0x0020000B,
+                node_id: 0x00E00002,
+                node_geometry: rectangle(900, 600, -40, 300),
+            })),
+            node_state(State::Tiled, Switch::On),
+            node_state(State::PseudoTiled, Switch::On),
+            node_state(State::Floating, Switch::On),
+            node_state(State::Fullscreen, Switch::Off),
+            node_flag(Flag::Hidden, Switch::On),
+            node_flag(Flag::Sticky, Switch::Off),
+            node_flag(Flag::Private, Switch::On),
+            node_flag(Flag::Locked, Switch::On),
+            node_flag(Flag::Marked, Switch::Off),
+            node_flag(Flag::Urgent, Switch::On),
+            node_layer(Layer::Below),
+            node_layer(Layer::Normal),
+            node_layer(Layer::Above),
+            pointer_action(Action::Move, ActionState::Begin),
+            pointer_action(Action::Move, ActionState::End),
+            pointer_action(Action::ResizeCorner, ActionState::Begin),
+            pointer_action(Action::ResizeSide, ActionState::End),
+            Event::Report(ReportInfo {
+                monitors: vec![
+                    ReportMonitorInfo {
+                        name: "eDP1".to_string(),
+                        focused: true,
+                        desktops: vec![
+                            ReportDesktopInfo {
+                                name: "1".to_string(),
+                                state: ReportDesktopState::Occupied,
+                                focused: true,
+                            },
+                            ReportDesktopInfo {
+                                name: "2 web".to_string(),
+                                state: ReportDesktopState::Occupied,
+                                focused: false,
+                            },
+                            ReportDesktopInfo {
+                                name: "3".to_string(),
+                                state: ReportDesktopState::Free,
+                                focused: false,
+                            },
+                        ],
+                        layout: Some(Layout::Tiled),
+                        focused_node: Some(ReportNodeInfo {
+                            state: Some(State::Tiled),
+                            sticky: false,
+                            private: false,
+                            locked: false,
+                            marked: false,
+                        }),
+                    },
+                    ReportMonitorInfo {
+                        name: "HDMI1".to_string(),
+                        focused: false,
+                        desktops: vec![ReportDesktopInfo {
+                            name: "4".to_string(),
+                            state: ReportDesktopState::Urgent,
+                            focused: true,
+                        }],
+                        layout: Some(Layout::Monocle),
+                        focused_node: Some(ReportNodeInfo {
+                            state: None,
+                            sticky: true,
+                            private: true,
+                            locked: true,
+                            marked: true,
+                        }),
+                    },
+                ],
+            }),
+        ]
+    }
+
+    #[test]
+    fn parse_event_corpus() {
+        let lines: Vec<_> = EVENTS.lines().collect();
+        let expected = expected_events();
+
+        assert_eq!(lines.len(), expected.len());
+
+        for (line, expected) in lines.into_iter().zip(expected) {
+            let event = Event::parse_strict(line)
+                .unwrap_or_else(|e| panic!("Failed to parse '{line}': {e}"));
+
+            assert_eq!(event, expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn parse_event_corpus_with_newline() {
+        for (line, expected) in EVENTS.lines().zip(expected_events()) {
+            let event: Event = format!("{line}\n").parse().unwrap();
+
+            assert_eq!(event, expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn parse_truncated_events() {
+        for line in EVENTS.lines() {
+            let mut split: Vec<_> = line.split(' ').collect();
+
+            // Reports are not space-separated, so dropping the last word
+            // may still leave a valid report
+            if split.len() < 2 || line.starts_with('W') {
+                continue;
+            }
+
+            split.pop();
+            let truncated = split.join(" ");
+
+            assert!(Event::parse_strict(&truncated).is_err(), "{truncated}");
+        }
+    }
+
+    #[test]
+    fn parse_names_with_spaces() {
+        let event: Event =
+            "desktop_add 0x00200002 0x00200008 2 web".parse().unwrap();
+        assert_eq!(
+            event,
+            Event::DesktopEvent(DesktopEvent::DesktopAdd(DesktopAddInfo {
+                monitor_id: 0x00200002,
+                desktop_id: 0x00200008,
+                desktop_name: "2 web".to_string(),
+            }))
+        );
+
+        let event: Event =
+            "monitor_add 0x0020000A left  screen 1920x1080+1920+0\n"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            event,
+            Event::MonitorEvent(MonitorEvent::MonitorAdd(MonitorAddInfo {
+                monitor_id: 0x0020000A,
+                monitor_name: "left  screen".to_string(),
+                monitor_geometry: rectangle(1920, 1080, 1920, 0),
+            }))
+        );
+
+        let info = DesktopRenameInfo::parse_with_old_name(
+            "desktop_rename 0x00200002 0x00200008 1 web 2 code",
+            "1 web",
+        )
+        .unwrap();
+        assert_eq!(info.old_name, "1 web");
+        assert_eq!(info.new_name, "2 code");
+
+        let info = MonitorRenameInfo::parse_with_old_name(
+            "monitor_rename 0x0020000A HDMI1 HDMI 1",
+            "HDMI1",
+        )
+        .unwrap();
+        assert_eq!(info.old_name, "HDMI1");
+        assert_eq!(info.new_name, "HDMI 1");
+
+        let info: DesktopRenameInfo =
+            "desktop_rename 0x00200002 0x00200008 web code"
+                .parse()
+                .unwrap();
+        assert_eq!(info.old_name, "web");
+        assert_eq!(info.new_name, "code");
+    }
+
+    #[test]
+    fn parse_ambiguous_renames() {
+        let is_ambiguous = |err: ParseError| {
+            matches!(
+                err.line_error().unwrap().error,
+                ParseError::AmbiguousNames
+            )
+        };
+
+        let line = "desktop_rename 0x00200002 0x00200008 web 1 web";
+
+        assert!(is_ambiguous(line.parse::<DesktopRenameInfo>().unwrap_err()));
+        assert!(is_ambiguous(line.parse::<Event>().unwrap_err()));
+
+        let info =
+            DesktopRenameInfo::parse_with_old_name(line, "web 1").unwrap();
+        assert_eq!(info.old_name, "web 1");
+        assert_eq!(info.new_name, "web");
+
+        assert!(DesktopRenameInfo::parse_with_old_name(line, "1").is_err());
+
+        let line = "monitor_rename 0x0020000A left screen right";
+
+        assert!(is_ambiguous(line.parse::<MonitorRenameInfo>().unwrap_err()));
+        assert!(is_ambiguous(Event::parse_strict(line).unwrap_err()));
+
+        let info = MonitorRenameInfo::parse_with_old_name(line, "left screen")
+            .unwrap();
+        assert_eq!(info.old_name, "left screen");
+        assert_eq!(info.new_name, "right");
+    }
+
+    #[test]
+    fn parse_missing_names() {
+        assert!(
+            Event::parse_strict("desktop_add 0x00200002 0x00200008").is_err()
+        );
+        assert!(
+            Event::parse_strict("monitor_add 0x0020000A 1920x1080+1920+0")
+                .is_err()
+        );
+        assert!(Event::parse_strict(
+            "desktop_rename 0x00200002 0x00200008 web"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parse_error_context() {
+        let line = "node_state 0x00200002 0x00200007 0x00400002 tiled maybe";
+        let error = Event::parse_strict(line).unwrap_err();
+        let context = error.line_error().unwrap();
+
+        assert_eq!(context.line, line);
+        assert_eq!(context.event.as_deref(), Some("node_state"));
+        assert_eq!(context.field, Some(5));
+        assert_eq!(context.expected, Some("Switch"));
+        assert!(error
+            .source()
+            .unwrap()
+            .downcast_ref::<strum::ParseError>()
+            .is_some());
+
+        let line = "node_focus 0x00200002 0x0020000G 0x00400002";
+        let error = Event::parse_strict(line).unwrap_err();
+        let context = error.line_error().unwrap();
+
+        assert_eq!(context.field, Some(2));
+        assert_eq!(context.expected, Some("hex id"));
+        assert!(error
+            .source()
+            .unwrap()
+            .downcast_ref::<std::num::ParseIntError>()
+            .is_some());
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse field 2 as hex id of 'node_focus' event in line \
+             'node_focus 0x00200002 0x0020000G 0x00400002': Conversion \
+             failed: invalid digit found in string"
+        );
+
+        let line = "monitor_geometry 0x00200002 1920x1080+0";
+        let context = Event::parse_strict(line).unwrap_err();
+        let context = context.line_error().unwrap();
+
+        assert_eq!(context.field, Some(2));
+        assert_eq!(context.expected, Some("Rectangle"));
+    }
+
+    #[test]
+    fn parse_error_missing_fields() {
+        let line = "desktop_focus 0x00200002";
+        let error = Event::parse_strict(line).unwrap_err();
+        let context = error.line_error().unwrap();
+
+        assert_eq!(context.event.as_deref(), Some("desktop_focus"));
+        assert_eq!(context.field, Some(2));
+        assert!(matches!(context.error, ParseError::InsufficientData));
+    }
+
+    #[test]
+    fn parse_error_unknown_event() {
+        for line in ["node_teleport 0x00200002", "wallpaper_change 1"] {
+            let error = Event::parse_strict(line).unwrap_err();
+            let context = error.line_error().unwrap();
+
+            assert_eq!(context.line, line);
+            assert_eq!(context.field, Some(0));
+            assert!(matches!(context.error, ParseError::InvalidEvent));
+        }
+    }
+
+    #[test]
+    fn parse_error_report() {
+        let line = "WMeDP1:O1:LX:TT:G";
+        let error = Event::parse_strict(line).unwrap_err();
+        let context = error.line_error().unwrap();
+
+        assert_eq!(context.line, line);
+        assert_eq!(context.event.as_deref(), Some("report"));
+        assert_eq!(context.field, Some(2));
+        assert_eq!(context.expected, Some("layout"));
+    }
+
+    #[test]
+    fn parse_unknown_events() {
+        let event: Event =
+            "node_teleport 0x00200002 far away\n".parse().unwrap();
+
+        assert_eq!(
+            event,
+            Event::Unknown {
+                name: "node_teleport".to_string(),
+                args: vec![
+                    "0x00200002".to_string(),
+                    "far".to_string(),
+                    "away".to_string()
+                ],
+                raw: "node_teleport 0x00200002 far away".to_string(),
+            }
+        );
+
+        let event: Event = "wallpaper_change".parse().unwrap();
+
+        assert_eq!(
+            event,
+            Event::Unknown {
+                name: "wallpaper_change".to_string(),
+                args: Vec::new(),
+                raw: "wallpaper_change".to_string(),
+            }
+        );
+
+        // Known events in wrong format are errors, not unknown events
+        for line in [
+            "node_focus 0x00200002",
+            "node_focus 0x00200002 0x00200007 nonsense",
+            "WMeDP1:O1:LX:TT:G",
+        ] {
+            let error = line.parse::<Event>().unwrap_err();
+            assert!(error.line_error().is_some(), "{line}");
+        }
     }
 }
@@ -1,16 +1,58 @@
 use super::*;
 
+impl FromFields for MonitorAddInfo {
+    const EVENT: &'static str = "monitor_add";
+    const ARG_NUM: usize = 3;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
+        let last = reply.len() - 1;
+
+        Ok(Self {
+            monitor_id: parse_id(reply, 1)?,
+            monitor_name: join_name(reply, 2, last)?,
+            monitor_geometry: parse_field(reply, last)?,
+        })
+    }
+}
+
 impl FromStr for MonitorAddInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "monitor_add", 3)?;
+        parse_info(input)
+    }
+}
 
-        Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            monitor_name: reply[2].to_string(),
-            monitor_geometry: reply[3].parse()?,
-        })
+fn monitor_rename(
+    reply: &[&str],
+    old_name: Option<&str>,
+) -> Result<MonitorRenameInfo, ParseError> {
+    let (old_name, new_name) = split_names(&reply[2..], old_name)
+        .map_err(|err| line_error(reply, Some(2), Some("names"), err))?;
+
+    Ok(MonitorRenameInfo {
+        monitor_id: parse_id(reply, 1)?,
+        old_name,
+        new_name,
+    })
+}
+
+pub(crate) fn parse_monitor_rename(
+    input: &str,
+    old_name: Option<&str>,
+) -> Result<MonitorRenameInfo, ParseError> {
+    with_fields(input, |reply| {
+        check_fields::<MonitorRenameInfo>(reply)?;
+        monitor_rename(reply, old_name)
+    })
+}
+
+impl FromFields for MonitorRenameInfo {
+    const EVENT: &'static str = "monitor_rename";
+    const ARG_NUM: usize = 3;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
+        monitor_rename(reply, None)
     }
 }
 
@@ -18,12 +60,17 @@ impl FromStr for MonitorRenameInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "monitor_rename", 3)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for MonitorRemoveInfo {
+    const EVENT: &'static str = "monitor_remove";
+    const ARG_NUM: usize = 1;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            old_name: reply[2].to_string(),
-            new_name: reply[3].to_string(),
+            monitor_id: parse_id(reply, 1)?,
         })
     }
 }
@@ -32,10 +79,18 @@ impl FromStr for MonitorRemoveInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "monitor_remove", 1)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for MonitorSwapInfo {
+    const EVENT: &'static str = "monitor_swap";
+    const ARG_NUM: usize = 2;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
+            src_monitor_id: parse_id(reply, 1)?,
+            dst_monitor_id: parse_id(reply, 2)?,
         })
     }
 }
@@ -44,11 +99,17 @@ impl FromStr for MonitorSwapInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "monitor_swap", 2)?;
+        parse_info(input)
+    }
+}
 
+impl FromFields for MonitorFocusInfo {
+    const EVENT: &'static str = "monitor_focus";
+    const ARG_NUM: usize = 1;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            src_monitor_id: from_hex_to_id(reply[1])?,
-            dst_monitor_id: from_hex_to_id(reply[2])?,
+            monitor_id: parse_id(reply, 1)?,
         })
     }
 }
@@ -57,10 +118,18 @@ impl FromStr for MonitorFocusInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "monitor_focus", 1)?;
+        parse_info(input)
+    }
+}
 
+impl FromFields for MonitorGeometryInfo {
+    const EVENT: &'static str = "monitor_geometry";
+    const ARG_NUM: usize = 2;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
+            monitor_id: parse_id(reply, 1)?,
+            monitor_geometry: parse_field(reply, 2)?,
         })
     }
 }
@@ -69,12 +138,33 @@ impl FromStr for MonitorGeometryInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "monitor_geometry", 2)?;
+        parse_info(input)
+    }
+}
 
-        Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            monitor_geometry: reply[2].parse()?,
-        })
+pub(super) fn parse_monitor_event(
+    reply: &[&str],
+    context: &EventContext,
+) -> Result<MonitorEvent, ParseError> {
+    match reply[0] {
+        "monitor_add" => Ok(MonitorEvent::MonitorAdd(parse_fields(reply)?)),
+        "monitor_rename" => {
+            check_fields::<MonitorRenameInfo>(reply)?;
+            let old_name = context.monitor_names.get(&parse_id(reply, 1)?);
+            let info = monitor_rename(reply, old_name.map(String::as_str))?;
+
+            Ok(MonitorEvent::MonitorRename(info))
+        }
+        "monitor_remove" => {
+            Ok(MonitorEvent::MonitorRemove(parse_fields(reply)?))
+        }
+        "monitor_swap" => Ok(MonitorEvent::MonitorSwap(parse_fields(reply)?)),
+        "monitor_focus" => Ok(MonitorEvent::MonitorFocus(parse_fields(reply)?)),
+        "monitor_geometry" => {
+            Ok(MonitorEvent::MonitorGeometry(parse_fields(reply)?))
+        }
+
+        _ => Err(unknown_event(reply)),
     }
 }
 
@@ -82,19 +172,8 @@ impl FromStr for MonitorEvent {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let event_type = get_event_type(input)?;
-
-        match event_type {
-            "monitor_add" => Ok(MonitorEvent::MonitorAdd(input.parse()?)),
-            "monitor_rename" => Ok(MonitorEvent::MonitorRename(input.parse()?)),
-            "monitor_remove" => Ok(MonitorEvent::MonitorRemove(input.parse()?)),
-            "monitor_swap" => Ok(MonitorEvent::MonitorSwap(input.parse()?)),
-            "monitor_focus" => Ok(MonitorEvent::MonitorFocus(input.parse()?)),
-            "monitor_geometry" => {
-                Ok(MonitorEvent::MonitorGeometry(input.parse()?))
-            }
-
-            _ => Err(ParseError::ConversionFailed),
-        }
+        with_fields(input, |reply| {
+            parse_monitor_event(reply, &EventContext::default())
+        })
     }
 }
@@ -1,16 +1,36 @@
 use super::*;
 
+impl FromFields for NodeAddInfo {
+    const EVENT: &'static str = "node_add";
+    const ARG_NUM: usize = 4;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
+        Ok(Self {
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            ip_id: parse_id(reply, 3)?,
+            node_id: parse_id(reply, 4)?,
+        })
+    }
+}
+
 impl FromStr for NodeAddInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_add", 4)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for NodeRemoveInfo {
+    const EVENT: &'static str = "node_remove";
+    const ARG_NUM: usize = 3;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            ip_id: from_hex_to_id(reply[3])?,
-            node_id: from_hex_to_id(reply[4])?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
         })
     }
 }
@@ -19,12 +39,22 @@ impl FromStr for NodeRemoveInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_remove", 3)?;
+        parse_info(input)
+    }
+}
 
+impl FromFields for NodeSwapInfo {
+    const EVENT: &'static str = "node_swap";
+    const ARG_NUM: usize = 6;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
+            src_monitor_id: parse_id(reply, 1)?,
+            src_desktop_id: parse_id(reply, 2)?,
+            src_node_id: parse_id(reply, 3)?,
+            dst_monitor_id: parse_id(reply, 4)?,
+            dst_desktop_id: parse_id(reply, 5)?,
+            dst_node_id: parse_id(reply, 6)?,
         })
     }
 }
@@ -33,15 +63,22 @@ impl FromStr for NodeSwapInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_swap", 6)?;
+        parse_info(input)
+    }
+}
 
+impl FromFields for NodeTransferInfo {
+    const EVENT: &'static str = "node_transfer";
+    const ARG_NUM: usize = 6;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            src_monitor_id: from_hex_to_id(reply[1])?,
-            src_desktop_id: from_hex_to_id(reply[2])?,
-            src_node_id: from_hex_to_id(reply[3])?,
-            dst_monitor_id: from_hex_to_id(reply[4])?,
-            dst_desktop_id: from_hex_to_id(reply[5])?,
-            dst_node_id: from_hex_to_id(reply[6])?,
+            src_monitor_id: parse_id(reply, 1)?,
+            src_desktop_id: parse_id(reply, 2)?,
+            src_node_id: parse_id(reply, 3)?,
+            dst_monitor_id: parse_id(reply, 4)?,
+            dst_desktop_id: parse_id(reply, 5)?,
+            dst_node_id: parse_id(reply, 6)?,
         })
     }
 }
@@ -50,15 +87,19 @@ impl FromStr for NodeTransferInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_transfer", 6)?;
+        parse_info(input)
+    }
+}
 
+impl FromFields for NodeFocusInfo {
+    const EVENT: &'static str = "node_focus";
+    const ARG_NUM: usize = 3;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            src_monitor_id: from_hex_to_id(reply[1])?,
-            src_desktop_id: from_hex_to_id(reply[2])?,
-            src_node_id: from_hex_to_id(reply[3])?,
-            dst_monitor_id: from_hex_to_id(reply[4])?,
-            dst_desktop_id: from_hex_to_id(reply[5])?,
-            dst_node_id: from_hex_to_id(reply[6])?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
         })
     }
 }
@@ -67,12 +108,19 @@ impl FromStr for NodeFocusInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_focus", 3)?;
+        parse_info(input)
+    }
+}
 
+impl FromFields for NodeActivateInfo {
+    const EVENT: &'static str = "node_activate";
+    const ARG_NUM: usize = 3;
+
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
         })
     }
 }
@@ -81,12 +129,20 @@ impl FromStr for NodeActivateInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_activate", 3)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for NodePreselInfo {
+    const EVENT: &'static str = "node_presel";
+    const ARG_NUM: usize = 4;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
+            presel: parse_value(reply, 4, &reply[4..].join(" "))?,
         })
     }
 }
@@ -95,13 +151,19 @@ impl FromStr for NodePreselInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_presel", 4)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for NodeStackInfo {
+    const EVENT: &'static str = "node_stack";
+    const ARG_NUM: usize = 3;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
-            presel: reply[4].parse()?,
+            node_id_1: parse_id(reply, 1)?,
+            stack: parse_field(reply, 2)?,
+            node_id_2: parse_id(reply, 3)?,
         })
     }
 }
@@ -110,12 +172,20 @@ impl FromStr for NodeStackInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_stack", 3)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for NodeLayerInfo {
+    const EVENT: &'static str = "node_layer";
+    const ARG_NUM: usize = 4;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            node_id_1: from_hex_to_id(reply[1])?,
-            stack: reply[2].parse()?,
-            node_id_2: from_hex_to_id(reply[3])?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
+            layer: parse_field(reply, 4)?,
         })
     }
 }
@@ -124,13 +194,21 @@ impl FromStr for NodeLayerInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_layer", 4)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for NodeFlagInfo {
+    const EVENT: &'static str = "node_flag";
+    const ARG_NUM: usize = 5;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
-            layer: reply[4].parse()?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
+            flag: parse_field(reply, 4)?,
+            switch: parse_field(reply, 5)?,
         })
     }
 }
@@ -139,14 +217,21 @@ impl FromStr for NodeFlagInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_flag", 5)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for NodeStateInfo {
+    const EVENT: &'static str = "node_state";
+    const ARG_NUM: usize = 5;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
-            flag: reply[4].parse()?,
-            switch: reply[5].parse()?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
+            state: parse_field(reply, 4)?,
+            switch: parse_field(reply, 5)?,
         })
     }
 }
@@ -155,14 +240,20 @@ impl FromStr for NodeStateInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_state", 5)?;
+        parse_info(input)
+    }
+}
+
+impl FromFields for NodeGeometryInfo {
+    const EVENT: &'static str = "node_geometry";
+    const ARG_NUM: usize = 4;
 
+    fn from_fields(reply: &[&str]) -> Result<Self, ParseError> {
         Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
-            state: reply[4].parse()?,
-            switch: reply[5].parse()?,
+            monitor_id: parse_id(reply, 1)?,
+            desktop_id: parse_id(reply, 2)?,
+            node_id: parse_id(reply, 3)?,
+            node_geometry: parse_field(reply, 4)?,
         })
     }
 }
@@ -171,14 +262,27 @@ impl FromStr for NodeGeometryInfo {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let reply = process_event_reply(input, "node_geometry", 4)?;
+        parse_info(input)
+    }
+}
 
-        Ok(Self {
-            monitor_id: from_hex_to_id(reply[1])?,
-            desktop_id: from_hex_to_id(reply[2])?,
-            node_id: from_hex_to_id(reply[3])?,
-            node_geometry: reply[4].parse()?,
-        })
+pub(super) fn parse_node_event(
+    reply: &[&str],
+) -> Result<NodeEvent, ParseError> {
+    match reply[0] {
+        "node_add" => Ok(NodeEvent::NodeAdd(parse_fields(reply)?)),
+        "node_remove" => Ok(NodeEvent::NodeRemove(parse_fields(reply)?)),
+        "node_swap" => Ok(NodeEvent::NodeSwap(parse_fields(reply)?)),
+        "node_transfer" => Ok(NodeEvent::NodeTransfer(parse_fields(reply)?)),
+        "node_focus" => Ok(NodeEvent::NodeFocus(parse_fields(reply)?)),
+        "node_activate" => Ok(NodeEvent::NodeActivate(parse_fields(reply)?)),
+        "node_presel" => Ok(NodeEvent::NodePresel(parse_fields(reply)?)),
+        "node_stack" => Ok(NodeEvent::NodeStack(parse_fields(reply)?)),
+        "node_geometry" => Ok(NodeEvent::NodeGeometry(parse_fields(reply)?)),
+        "node_state" => Ok(NodeEvent::NodeState(parse_fields(reply)?)),
+        "node_flag" => Ok(NodeEvent::NodeFlag(parse_fields(reply)?)),
+        "node_layer" => Ok(NodeEvent::NodeLayer(parse_fields(reply)?)),
+        _ => Err(unknown_event(reply)),
     }
 }
 
@@ -186,23 +290,7 @@ impl FromStr for NodeEvent {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let event_type = get_event_type(input)?;
-
-        match event_type {
-            "node_add" => Ok(NodeEvent::NodeAdd(input.parse()?)),
-            "node_remove" => Ok(NodeEvent::NodeRemove(input.parse()?)),
-            "node_swap" => Ok(NodeEvent::NodeSwap(input.parse()?)),
-            "node_transfer" => Ok(NodeEvent::NodeTransfer(input.parse()?)),
-            "node_focus" => Ok(NodeEvent::NodeFocus(input.parse()?)),
-            "node_activate" => Ok(NodeEvent::NodeActivate(input.parse()?)),
-            "node_presel" => Ok(NodeEvent::NodePresel(input.parse()?)),
-            "node_stack" => Ok(NodeEvent::NodeStack(input.parse()?)),
-            "node_geometry" => Ok(NodeEvent::NodeGeometry(input.parse()?)),
-            "node_state" => Ok(NodeEvent::NodeState(input.parse()?)),
-            "node_flag" => Ok(NodeEvent::NodeFlag(input.parse()?)),
-            "node_layer" => Ok(NodeEvent::NodeLayer(input.parse()?)),
-            _ => Err(ParseError::ConversionFailed),
-        }
+        with_fields(input, parse_node_event)
     }
 }
 
@@ -0,0 +1,227 @@
+use super::*;
+use crate::errors::LineError;
+use ParseError::{ConversionFailed, InsufficientData, InvalidEvent};
+
+/// Guesses status prefix of the report. Prefix is everything before the
+/// first `M` or `m` character, which starts the first monitor.
+fn guess_prefix(input: &str) -> Result<&str, ParseError> {
+    let first_item = input.split(':').next().unwrap_or_default();
+
+    match first_item.find(['M', 'm']) {
+        Some(pos) => Ok(&input[..pos]),
+        None => Err(report_error(input, Some(0), "monitor", InvalidEvent)),
+    }
+}
+
+/// Returns error with context of the report.
+fn report_error(
+    input: &str,
+    field: Option<usize>,
+    expected: &'static str,
+    error: ParseError,
+) -> ParseError {
+    ParseError::from(LineError {
+        line: input.to_string(),
+        event: Some("report".to_string()),
+        field,
+        expected: Some(expected),
+        error,
+    })
+}
+
+fn parse_node_state(input: &str) -> Result<Option<State>, ParseError> {
+    match input {
+        "T" => Ok(Some(State::Tiled)),
+        "P" => Ok(Some(State::PseudoTiled)),
+        "F" => Ok(Some(State::Floating)),
+        "=" => Ok(Some(State::Fullscreen)),
+        "@" => Ok(None),
+        _ => Err(ParseError::ConversionFailed),
+    }
+}
+
+fn parse_layout(input: &str) -> Result<Layout, ParseError> {
+    match input {
+        "T" => Ok(Layout::Tiled),
+        "M" => Ok(Layout::Monocle),
+        _ => Err(ParseError::ConversionFailed),
+    }
+}
+
+fn focused_node(monitor: &mut ReportMonitorInfo) -> &mut ReportNodeInfo {
+    monitor.focused_node.get_or_insert(ReportNodeInfo {
+        state: None,
+        sticky: false,
+        private: false,
+        locked: false,
+        marked: false,
+    })
+}
+
+pub(crate) fn parse_report(
+    input: &str,
+    status_prefix: &str,
+) -> Result<ReportInfo, ParseError> {
+    let input = input.trim_end_matches(['\n', '\r']);
+    let report = input.strip_prefix(status_prefix).ok_or_else(|| {
+        report_error(input, Some(0), "status prefix", InvalidEvent)
+    })?;
+
+    let mut monitors: Vec<ReportMonitorInfo> = Vec::new();
+
+    for (index, item) in report.split(':').enumerate() {
+        let error =
+            |expected, error| report_error(input, Some(index), expected, error);
+
+        let mut chars = item.chars();
+        let kind = chars
+            .next()
+            .ok_or_else(|| error("report item", InsufficientData))?;
+        let value = chars.as_str();
+
+        if let 'M' | 'm' = kind {
+            monitors.push(ReportMonitorInfo {
+                name: value.to_string(),
+                focused: kind == 'M',
+                desktops: Vec::new(),
+                layout: None,
+                focused_node: None,
+            });
+
+            continue;
+        }
+
+        let monitor = monitors
+            .last_mut()
+            .ok_or_else(|| error("monitor", InvalidEvent))?;
+
+        match kind {
+            'O' | 'o' | 'F' | 'f' | 'U' | 'u' => {
+                let state = match kind.to_ascii_lowercase() {
+                    'o' => ReportDesktopState::Occupied,
+                    'f' => ReportDesktopState::Free,
+                    _ => ReportDesktopState::Urgent,
+                };
+
+                monitor.desktops.push(ReportDesktopInfo {
+                    name: value.to_string(),
+                    state,
+                    focused: kind.is_ascii_uppercase(),
+                });
+            }
+
+            'L' => {
+                let layout =
+                    parse_layout(value).map_err(|err| error("layout", err))?;
+                monitor.layout = Some(layout);
+            }
+
+            'T' => {
+                let state = parse_node_state(value)
+                    .map_err(|err| error("node state", err))?;
+                focused_node(monitor).state = state;
+            }
+
+            'G' => {
+                let node = focused_node(monitor);
+
+                for flag in value.chars() {
+                    match flag {
+                        'S' => node.sticky = true,
+                        'P' => node.private = true,
+                        'L' => node.locked = true,
+                        'M' => node.marked = true,
+                        _ => return Err(error("node flags", ConversionFailed)),
+                    }
+                }
+            }
+
+            _ => return Err(error("report item", ConversionFailed)),
+        }
+    }
+
+    if monitors.is_empty() {
+        return Err(report_error(input, None, "monitor", InsufficientData));
+    }
+
+    Ok(ReportInfo { monitors })
+}
+
+impl FromStr for ReportInfo {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_report(input, guess_prefix(input)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_default_prefix() {
+        let input = "WMeDP1:O1:o2 web:f3:LT:TT:GSL:mHDMI1:Fmusic:u5:LM:T@:G\n";
+        let report: ReportInfo = input.parse().unwrap();
+
+        assert_eq!(report.monitors.len(), 2);
+
+        let edp = &report.monitors[0];
+        assert_eq!(edp.name, "eDP1");
+        assert!(edp.focused);
+        assert!(matches!(edp.layout, Some(Layout::Tiled)));
+
+        let names: Vec<_> = edp.desktops.iter().map(|d| &d.name).collect();
+        assert_eq!(names, ["1", "2 web", "3"]);
+        assert!(edp.desktops[0].focused);
+        assert!(matches!(
+            edp.desktops[1].state,
+            ReportDesktopState::Occupied
+        ));
+        assert!(matches!(edp.desktops[2].state, ReportDesktopState::Free));
+
+        let node = edp.focused_node.as_ref().unwrap();
+        assert!(matches!(node.state, Some(State::Tiled)));
+        assert!(node.sticky && node.locked && !node.private && !node.marked);
+
+        let hdmi = &report.monitors[1];
+        assert_eq!(hdmi.name, "HDMI1");
+        assert!(!hdmi.focused);
+        assert!(matches!(hdmi.layout, Some(Layout::Monocle)));
+        assert!(hdmi.desktops[0].focused);
+        assert!(matches!(hdmi.desktops[1].state, ReportDesktopState::Urgent));
+
+        let node = hdmi.focused_node.as_ref().unwrap();
+        assert!(node.state.is_none());
+        assert!(!node.sticky);
+
+        assert_eq!(report.focused_monitor().unwrap().name, "eDP1");
+    }
+
+    #[test]
+    fn parse_custom_prefix() {
+        let input = "status>mDP-1:f1:LT:MHDMI-0:O2:LM:T=:GMP";
+        let report: ReportInfo = input.parse().unwrap();
+
+        assert_eq!(report.monitors[0].name, "DP-1");
+        assert!(report.monitors[0].focused_node.is_none());
+        assert_eq!(report.monitors[1].name, "HDMI-0");
+
+        let node = report.monitors[1].focused_node.as_ref().unwrap();
+        assert!(matches!(node.state, Some(State::Fullscreen)));
+        assert!(node.marked && node.private);
+
+        let report =
+            ReportInfo::parse_with_prefix("Mon:MeDP1:F1:LT", "Mon:").unwrap();
+        assert_eq!(report.monitors[0].name, "eDP1");
+    }
+
+    #[test]
+    fn parse_invalid_report() {
+        for input in
+            ["", "W", "WO1:MeDP1", "WMeDP1:X1", "WMeDP1:LX", "WMeDP1::"]
+        {
+            assert!(input.parse::<ReportInfo>().is_err(), "{input}");
+        }
+    }
+}
@@ -1,50 +1,203 @@
-use super::errors::ParseError;
+use super::errors::{LineError, ParseError};
 use crate::Id;
+use std::any;
 use std::num::ParseIntError;
+use std::str::FromStr;
 
 pub(crate) fn from_hex_to_id(input: &str) -> Result<Id, ParseIntError> {
     let without_prefix = input.trim_start_matches("0x");
     Id::from_str_radix(without_prefix, 16)
 }
 
-pub(super) fn process_event_reply<'a>(
+/// Number of fields, that are split without allocation. Only names with
+/// many spaces make events longer.
+const INLINE_FIELDS: usize = 16;
+
+/// Splits event line into fields, that are separated by spaces, and passes
+/// them to `f`. Only line endings are trimmed, because names can contain
+/// spaces. Fields are kept on the stack, unless there are more of them, than
+/// [`INLINE_FIELDS`].
+pub(super) fn with_fields<'a, R>(
     reply: &'a str,
-    event: &str,
-    arg_num: usize,
-) -> Result<Vec<&'a str>, ParseError> {
-    let split: Vec<_> = reply.trim().split(' ').collect();
+    f: impl FnOnce(&[&'a str]) -> R,
+) -> R {
+    let reply = reply.trim_end_matches(['\n', '\r']);
+    let mut fields = [""; INLINE_FIELDS];
+    let mut len = 0;
 
-    if split.is_empty() {
-        return Err(ParseError::InsufficientData);
+    for field in reply.split(' ') {
+        if len == INLINE_FIELDS {
+            return f(&reply.split(' ').collect::<Vec<_>>());
+        }
+
+        fields[len] = field;
+        len += 1;
     }
 
-    if split[0] != event {
-        return Err(ParseError::InvalidEvent);
+    f(&fields[..len])
+}
+
+/// Returns error with context of the event, that was split into `reply`.
+pub(super) fn line_error(
+    reply: &[&str],
+    field: Option<usize>,
+    expected: Option<&'static str>,
+    error: ParseError,
+) -> ParseError {
+    ParseError::from(LineError {
+        line: reply.join(" "),
+        event: reply.first().map(|x| x.to_string()),
+        field,
+        expected,
+        error,
+    })
+}
+
+/// Parses id in the field with the given `index`.
+pub(super) fn parse_id(reply: &[&str], index: usize) -> Result<Id, ParseError> {
+    from_hex_to_id(reply[index]).map_err(|err| {
+        line_error(reply, Some(index), Some("hex id"), err.into())
+    })
+}
+
+/// Parses the field with the given `index`.
+pub(super) fn parse_field<T>(
+    reply: &[&str],
+    index: usize,
+) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Into<ParseError>,
+{
+    parse_value(reply, index, reply[index])
+}
+
+/// Parses `value`, that was taken from the field with the given `index`.
+pub(super) fn parse_value<T>(
+    reply: &[&str],
+    index: usize,
+    value: &str,
+) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Into<ParseError>,
+{
+    value.parse().map_err(|err: T::Err| {
+        line_error(reply, Some(index), Some(type_name::<T>()), err.into())
+    })
+}
+
+/// Returns name of the type without its path.
+fn type_name<T>() -> &'static str {
+    let name = any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Joins words of the name in fields from `start` to `end`, that was split on
+/// spaces.
+pub(super) fn join_name(
+    reply: &[&str],
+    start: usize,
+    end: usize,
+) -> Result<String, ParseError> {
+    if start >= end {
+        let error = ParseError::InsufficientData;
+        return Err(line_error(reply, Some(start), Some("name"), error));
     }
 
-    if split.len() < arg_num + 1 {
+    Ok(reply[start..end].join(" "))
+}
+
+/// Splits words into old and new names of rename event. If `old_name` is
+/// not known, words can be split only if there are two of them.
+pub(super) fn split_names(
+    words: &[&str],
+    old_name: Option<&str>,
+) -> Result<(String, String), ParseError> {
+    if words.len() < 2 {
         return Err(ParseError::InsufficientData);
     }
 
-    Ok(split)
-}
+    match old_name {
+        Some(old_name) => {
+            let names = words.join(" ");
+            let new_name = names
+                .strip_prefix(old_name)
+                .and_then(|x| x.strip_prefix(' '))
+                .ok_or(ParseError::ConversionFailed)?;
 
-pub(super) fn get_event_type(reply: &str) -> Result<&str, ParseError> {
-    let split: Vec<_> = reply.trim().split(' ').collect();
+            Ok((old_name.to_string(), new_name.to_string()))
+        }
 
-    if split.is_empty() {
-        return Err(ParseError::InsufficientData);
+        None if words.len() == 2 => {
+            Ok((words[0].to_string(), words[1].to_string()))
+        }
+
+        None => Err(ParseError::AmbiguousNames),
     }
+}
+
+/// Returns error for the event of unknown type.
+pub(super) fn unknown_event(reply: &[&str]) -> ParseError {
+    line_error(reply, Some(0), Some("event"), ParseError::InvalidEvent)
+}
 
-    Ok(split[0])
+/// Checks, if error was returned by [`unknown_event`], i.e. name of the event
+/// isn't recognised.
+pub(super) fn is_unknown_event(error: &ParseError) -> bool {
+    error.line_error().is_some_and(|context| {
+        context.field == Some(0)
+            && context.expected == Some("event")
+            && matches!(context.error, ParseError::InvalidEvent)
+    })
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn split_fields() {
+        let short = "desktop_rename 0x00200002 0x00200008 2 web 3\n";
+        let long = format!("{}\r\n", ["word"; 40].join(" "));
+
+        for line in [short, &long] {
+            let expected: Vec<_> = line.trim_end().split(' ').collect();
+            with_fields(line, |fields| assert_eq!(fields, expected));
+        }
+    }
+
     #[test]
     fn parse_hex() {
         println!("{:?}", from_hex_to_id("0x00200002"));
     }
+
+    #[test]
+    fn split_rename_names() {
+        let words = ["1", "web", "2", "code"];
+
+        assert_eq!(
+            split_names(&words, Some("1")).unwrap(),
+            ("1".to_string(), "web 2 code".to_string())
+        );
+        assert_eq!(
+            split_names(&words, Some("1 web 2")).unwrap(),
+            ("1 web 2".to_string(), "code".to_string())
+        );
+        assert_eq!(
+            split_names(&["a", "b"], None).unwrap(),
+            ("a".to_string(), "b".to_string())
+        );
+        assert!(matches!(
+            split_names(&words, None),
+            Err(ParseError::AmbiguousNames)
+        ));
+        assert!(matches!(
+            split_names(&["a", "b", "c"], None),
+            Err(ParseError::AmbiguousNames)
+        ));
+        assert!(split_names(&words, Some("2")).is_err());
+        assert!(split_names(&words, Some("1 web 2 code")).is_err());
+        assert!(split_names(&["1"], None).is_err());
+    }
 }
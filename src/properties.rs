@@ -1,7 +1,9 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
-use strum_macros::EnumString;
+use strum_macros::{Display, EnumString};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Rectangle {
     pub x: i32,
     pub y: i32,
@@ -9,7 +11,9 @@ pub struct Rectangle {
     pub height: i32,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize, Clone)]
+#[derive(
+    Debug, Display, EnumString, Serialize, Deserialize, Clone, PartialEq,
+)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
 pub enum Layout {
@@ -17,7 +21,9 @@ pub enum Layout {
     Monocle,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize, Clone)]
+#[derive(
+    Debug, Display, EnumString, Serialize, Deserialize, Clone, PartialEq,
+)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
 pub enum Dir {
@@ -27,7 +33,9 @@ pub enum Dir {
     East,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize, Clone)]
+#[derive(
+    Debug, Display, EnumString, Serialize, Deserialize, Clone, PartialEq,
+)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
 pub enum CycleDir {
@@ -35,29 +43,38 @@ pub enum CycleDir {
     Prev,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(
+    Debug, Display, EnumString, Serialize, Deserialize, Clone, PartialEq,
+)]
 #[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
 pub enum SplitType {
     Vertical,
     Horizontal,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
 pub enum Presel {
     Dir(Dir),
     Ratio(f32),
     Cancel,
 }
 
-#[derive(Debug, EnumString, Clone)]
+#[derive(
+    Debug, Display, EnumString, Serialize, Deserialize, Clone, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Stack {
     Below,
     Above,
 }
 
-#[derive(Debug, Serialize, Deserialize, EnumString, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(
+    Debug, Display, Serialize, Deserialize, EnumString, Clone, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum State {
     Tiled,
@@ -66,14 +83,19 @@ pub enum State {
     Fullscreen,
 }
 
-#[derive(Debug, EnumString, Clone)]
+#[derive(
+    Debug, Display, EnumString, Serialize, Deserialize, Clone, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Switch {
     On,
     Off,
 }
 
-#[derive(Debug, Serialize, Deserialize, EnumString, Clone)]
+#[derive(
+    Debug, Display, Serialize, Deserialize, EnumString, Clone, PartialEq,
+)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
 pub enum Flag {
@@ -85,7 +107,9 @@ pub enum Flag {
     Urgent,
 }
 
-#[derive(Debug, Serialize, Deserialize, EnumString, Clone)]
+#[derive(
+    Debug, Display, Serialize, Deserialize, EnumString, Clone, PartialEq,
+)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
 pub enum Layer {
@@ -94,7 +118,10 @@ pub enum Layer {
     Above,
 }
 
-#[derive(Debug, EnumString, Clone)]
+#[derive(
+    Debug, Display, EnumString, Serialize, Deserialize, Clone, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Action {
     Move,
@@ -102,9 +129,31 @@ pub enum Action {
     ResizeSide,
 }
 
-#[derive(Debug, EnumString, Clone)]
+#[derive(
+    Debug, Display, EnumString, Serialize, Deserialize, Clone, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum ActionState {
     Begin,
     End,
 }
+
+impl fmt::Display for Rectangle {
+    /// Formats rectangle as bspwm does, e.g. `1920x1080+0+0`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}+{}+{}", self.width, self.height, self.x, self.y)
+    }
+}
+
+impl fmt::Display for Presel {
+    /// Formats preselection as bspwm does in `node_presel` event, e.g.
+    /// `dir east` or `ratio 0.300000`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Presel::Dir(dir) => write!(f, "dir {}", dir),
+            Presel::Ratio(ratio) => write!(f, "ratio {:.6}", ratio),
+            Presel::Cancel => write!(f, "cancel"),
+        }
+    }
+}
@@ -1,23 +1,23 @@
 use std::string::ToString;
 
+use crate::client::BspcClient;
 use crate::errors::{QueryError, ReplyError};
 use crate::parser::utils::from_hex_to_id;
 use crate::selectors::{
     DesktopSelector, MonitorSelector, NodeSelector, Selector,
 };
-use crate::socket::{self, BspcCommunication};
+use crate::socket::{BspcCommunication, Transport};
 use crate::tree::Tree;
 use crate::Id;
 
-fn query(
+pub(crate) fn query_request(
     query_type: &str,
     names_flag: bool,
     selector: Option<&str>,
     monitor_selector: Option<&str>,
     desktop_selector: Option<&str>,
     node_selector: Option<&str>,
-) -> Result<Vec<Id>, ReplyError> {
-    let mut conn = socket::connect()?;
+) -> Result<String, ReplyError> {
     let mut request = format!("query\x00--{query_type}\x00");
 
     if names_flag {
@@ -46,10 +46,10 @@ fn query(
         request = format!("{request}--node\x00{sel}\x00");
     }
 
-    conn.send_message(&request)?;
-
-    let reply = conn.receive_message()?;
+    Ok(request)
+}
 
+pub(crate) fn parse_ids(reply: &[String]) -> Result<Vec<Id>, ReplyError> {
     let mut ids = Vec::new();
 
     for reply_id in reply.iter() {
@@ -60,96 +60,38 @@ fn query(
     Ok(ids)
 }
 
-/// Returns ids of the nodes, that match certain criteria
-pub fn query_nodes(
-    selector: Option<NodeSelector>,
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    node_selector: Option<NodeSelector>,
-) -> Result<Vec<Id>, ReplyError> {
-    query(
-        "nodes",
-        false,
-        extract(&selector)?,
-        extract(&monitor_selector)?,
-        extract(&desktop_selector)?,
-        extract(&node_selector)?,
-    )
-}
-
-/// Returns ids of the desktops, that match certain criteria
-pub fn query_desktops(
+fn query(
+    conn: &mut impl BspcCommunication,
+    query_type: &str,
     names_flag: bool,
-    selector: Option<DesktopSelector>,
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    node_selector: Option<NodeSelector>,
+    selector: Option<&str>,
+    monitor_selector: Option<&str>,
+    desktop_selector: Option<&str>,
+    node_selector: Option<&str>,
 ) -> Result<Vec<Id>, ReplyError> {
-    query(
-        "desktops",
+    let request = query_request(
+        query_type,
         names_flag,
-        extract(&selector)?,
-        extract(&monitor_selector)?,
-        extract(&desktop_selector)?,
-        extract(&node_selector)?,
-    )
-}
-
-fn extract<S>(selector: &Option<S>) -> Result<Option<&str>, ReplyError>
-where
-    S: Selector,
-{
-    if let Some(sel) = selector {
-        if !sel.is_valid() {
-            return Err(ReplyError::InvalidSelector(format!(
-                "This {} selector is invalid: '{}'",
-                sel.kind(),
-                sel.extract(),
-            )));
-        }
+        selector,
+        monitor_selector,
+        desktop_selector,
+        node_selector,
+    )?;
 
-        return Ok(Some(sel.extract()));
-    }
+    conn.send_message(&request)?;
 
-    Ok(None)
-}
+    let reply = conn.receive_message()?;
 
-/// Returns ids of monitors, that match certain criteria
-pub fn query_monitors(
-    names_flag: bool,
-    selector: Option<MonitorSelector>,
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    node_selector: Option<NodeSelector>,
-) -> Result<Vec<Id>, ReplyError> {
-    query(
-        "monitors",
-        names_flag,
-        extract(&selector)?,
-        extract(&monitor_selector)?,
-        extract(&desktop_selector)?,
-        extract(&node_selector)?,
-    )
+    parse_ids(&reply)
 }
 
-/// Returnes tree representation of the matching item
-///
-/// Note: when more then one of the arguments are not `None`, then the
-/// matching will give the result in this priority: Node, Desktop, Monitor.
-/// For example, if Desktop and Node are both not `None`, than this will give the
-/// output for Node.
-pub fn query_tree(
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    node_selector: Option<NodeSelector>,
-) -> Result<Tree, ReplyError> {
-    let mut conn = socket::connect()?;
+pub(crate) fn tree_request(
+    monitor_selector: Option<&str>,
+    desktop_selector: Option<&str>,
+    node_selector: Option<&str>,
+) -> String {
     let mut request = "query\x00--tree\x00".to_string();
 
-    let monitor_selector = extract(&monitor_selector)?;
-    let desktop_selector = extract(&desktop_selector)?;
-    let node_selector = extract(&node_selector)?;
-
     if let Some(sel) = monitor_selector {
         request = format!("{request}--monitor\x00{sel}\x00");
     }
@@ -162,10 +104,15 @@ pub fn query_tree(
         request = format!("{request}--node\x00{sel}\x00");
     }
 
-    conn.send_message(&request)?;
-
-    let reply = conn.receive_message()?;
+    request
+}
 
+pub(crate) fn parse_tree(
+    reply: &[String],
+    monitor_selector: Option<&str>,
+    desktop_selector: Option<&str>,
+    node_selector: Option<&str>,
+) -> Result<Tree, ReplyError> {
     if reply.len() > 1 {
         // TODO: Test if this can happen
         panic!("{}", format!("Something is weird, reply has more than one element, this is debug log: {:#?}", reply));
@@ -188,51 +135,250 @@ pub fn query_tree(
     Err(ReplyError::QueryError(QueryError::InvalidRequest(
         "No options were given".to_string(),
     )))
+}
 
-    // match option {
-    //     QueryOptions::Monitor => {
-    //         Ok(Tree::Monitor(serde_json::from_str(reply)?))
-    //     }
+pub(crate) fn extract<S>(
+    selector: &Option<S>,
+) -> Result<Option<&str>, ReplyError>
+where
+    S: Selector,
+{
+    if let Some(sel) = selector {
+        if !sel.is_valid() {
+            return Err(ReplyError::InvalidSelector(format!(
+                "This {} selector is invalid: '{}'",
+                sel.kind(),
+                sel.extract(),
+            )));
+        }
 
-    //     QueryOptions::Desktop => {
-    //         Ok(Tree::Desktop(serde_json::from_str(reply)?))
-    //     }
+        return Ok(Some(sel.extract()));
+    }
 
-    //     QueryOptions::Node => Ok(Tree::Node(serde_json::from_str(reply)?)),
-    // }
+    Ok(None)
+}
+
+impl<T: Transport> BspcClient<T> {
+    /// Returns ids of the nodes, that match certain criteria
+    pub fn query_nodes(
+        &self,
+        selector: Option<NodeSelector>,
+        monitor_selector: Option<MonitorSelector>,
+        desktop_selector: Option<DesktopSelector>,
+        node_selector: Option<NodeSelector>,
+    ) -> Result<Vec<Id>, ReplyError> {
+        query(
+            &mut self.connect()?,
+            "nodes",
+            false,
+            extract(&selector)?,
+            extract(&monitor_selector)?,
+            extract(&desktop_selector)?,
+            extract(&node_selector)?,
+        )
+    }
+
+    /// Returns ids of the desktops, that match certain criteria
+    pub fn query_desktops(
+        &self,
+        names_flag: bool,
+        selector: Option<DesktopSelector>,
+        monitor_selector: Option<MonitorSelector>,
+        desktop_selector: Option<DesktopSelector>,
+        node_selector: Option<NodeSelector>,
+    ) -> Result<Vec<Id>, ReplyError> {
+        query(
+            &mut self.connect()?,
+            "desktops",
+            names_flag,
+            extract(&selector)?,
+            extract(&monitor_selector)?,
+            extract(&desktop_selector)?,
+            extract(&node_selector)?,
+        )
+    }
+
+    /// Returns ids of monitors, that match certain criteria
+    pub fn query_monitors(
+        &self,
+        names_flag: bool,
+        selector: Option<MonitorSelector>,
+        monitor_selector: Option<MonitorSelector>,
+        desktop_selector: Option<DesktopSelector>,
+        node_selector: Option<NodeSelector>,
+    ) -> Result<Vec<Id>, ReplyError> {
+        query(
+            &mut self.connect()?,
+            "monitors",
+            names_flag,
+            extract(&selector)?,
+            extract(&monitor_selector)?,
+            extract(&desktop_selector)?,
+            extract(&node_selector)?,
+        )
+    }
+
+    /// Returnes tree representation of the matching item
+    ///
+    /// Note: when more then one of the arguments are not `None`, then the
+    /// matching will give the result in this priority: Node, Desktop,
+    /// Monitor. For example, if Desktop and Node are both not `None`, than
+    /// this will give the output for Node.
+    pub fn query_tree(
+        &self,
+        monitor_selector: Option<MonitorSelector>,
+        desktop_selector: Option<DesktopSelector>,
+        node_selector: Option<NodeSelector>,
+    ) -> Result<Tree, ReplyError> {
+        let mut conn = self.connect()?;
+
+        let monitor_selector = extract(&monitor_selector)?;
+        let desktop_selector = extract(&desktop_selector)?;
+        let node_selector = extract(&node_selector)?;
+
+        let request =
+            tree_request(monitor_selector, desktop_selector, node_selector);
+
+        conn.send_message(&request)?;
+
+        let reply = conn.receive_message()?;
+
+        parse_tree(&reply, monitor_selector, desktop_selector, node_selector)
+    }
+}
+
+/// Returns ids of the nodes, that match certain criteria
+pub fn query_nodes(
+    selector: Option<NodeSelector>,
+    monitor_selector: Option<MonitorSelector>,
+    desktop_selector: Option<DesktopSelector>,
+    node_selector: Option<NodeSelector>,
+) -> Result<Vec<Id>, ReplyError> {
+    BspcClient::new().query_nodes(
+        selector,
+        monitor_selector,
+        desktop_selector,
+        node_selector,
+    )
+}
+
+/// Returns ids of the desktops, that match certain criteria
+pub fn query_desktops(
+    names_flag: bool,
+    selector: Option<DesktopSelector>,
+    monitor_selector: Option<MonitorSelector>,
+    desktop_selector: Option<DesktopSelector>,
+    node_selector: Option<NodeSelector>,
+) -> Result<Vec<Id>, ReplyError> {
+    BspcClient::new().query_desktops(
+        names_flag,
+        selector,
+        monitor_selector,
+        desktop_selector,
+        node_selector,
+    )
+}
+
+/// Returns ids of monitors, that match certain criteria
+pub fn query_monitors(
+    names_flag: bool,
+    selector: Option<MonitorSelector>,
+    monitor_selector: Option<MonitorSelector>,
+    desktop_selector: Option<DesktopSelector>,
+    node_selector: Option<NodeSelector>,
+) -> Result<Vec<Id>, ReplyError> {
+    BspcClient::new().query_monitors(
+        names_flag,
+        selector,
+        monitor_selector,
+        desktop_selector,
+        node_selector,
+    )
+}
+
+/// Returnes tree representation of the matching item
+///
+/// Note: when more then one of the arguments are not `None`, then the
+/// matching will give the result in this priority: Node, Desktop, Monitor.
+/// For example, if Desktop and Node are both not `None`, than this will give the
+/// output for Node.
+pub fn query_tree(
+    monitor_selector: Option<MonitorSelector>,
+    desktop_selector: Option<DesktopSelector>,
+    node_selector: Option<NodeSelector>,
+) -> Result<Tree, ReplyError> {
+    BspcClient::new().query_tree(
+        monitor_selector,
+        desktop_selector,
+        node_selector,
+    )
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::events::*;
-    // use std::error::Error;
-    // use std::io::{self, Read, Write};
-    // use std::os::unix::net::UnixStream;
-
-    // #[test]
-    // fn test_query_nodes() {
-    //     println!(
-    //         "{:#?}",
-    //         Bspc::query_nodes(None, None, None, Some(".!hidden"))
-    //             .unwrap()
-    //     );
-    // }
+    use crate::testing::{FakeBspwm, Reply, STATE_DUMP};
 
     #[test]
     fn test_fullscreen_node() {
-        let node_request = format!(".fullscreen.window");
-        let query_result =
-            query_nodes(None, None, None, Some(NodeSelector(&node_request)));
-
-        println!("{query_result:#?}");
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.on_request(
+            &["query", "--nodes", "--node", ".fullscreen.window"],
+            Reply::lines(["0x00C00002"]),
+        );
+
+        let node_request = ".fullscreen.window".to_string();
+        let query_result = bspwm.client().query_nodes(
+            None,
+            None,
+            None,
+            Some(NodeSelector(&node_request)),
+        );
+
+        assert_eq!(query_result.unwrap(), vec![0x00C00002]);
     }
 
     #[test]
     fn test_query_tree() {
-        let tree =
-            query_tree(Some(MonitorSelector("focused")), None, None).unwrap();
+        let state: serde_json::Value =
+            serde_json::from_str(STATE_DUMP).unwrap();
+        let monitor = state["monitors"][0].to_string();
+
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.on_request(
+            &["query", "--tree", "--monitor", "focused"],
+            Reply::lines([monitor]),
+        );
+
+        let tree = bspwm
+            .client()
+            .query_tree(Some(MonitorSelector("focused")), None, None)
+            .unwrap();
+
+        match tree {
+            Tree::Monitor(monitor) => assert_eq!(monitor.name, "eDP1"),
+            tree => panic!("Unexpected tree: {tree:#?}"),
+        }
+    }
 
-        println!("{tree:#?}");
+    #[test]
+    fn test_no_matches() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.on_request(
+            &["query", "--nodes", "--node", ".fullscreen"],
+            Reply::failure(""),
+        );
+
+        let query_result = bspwm.client().query_nodes(
+            None,
+            None,
+            None,
+            Some(NodeSelector(".fullscreen")),
+        );
+
+        match query_result {
+            Err(ReplyError::RequestFailed(reply)) => assert!(reply.is_empty()),
+            result => panic!("Unexpected result: {result:?}"),
+        }
     }
 }
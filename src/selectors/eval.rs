@@ -0,0 +1,613 @@
+//! Evaluation of selectors against [`State`], without querying bspwm.
+
+use super::{DesktopSelector, MonitorSelector, NodeSelector, Path, Selector};
+use crate::errors::QueryError;
+use crate::parser::utils::from_hex_to_id;
+use crate::state::State;
+use crate::tree::{Desktop, Monitor, Node};
+use crate::Id;
+
+const NODE_MODIFIERS: &[&str] = &[
+    "focused",
+    "active",
+    "automatic",
+    "local",
+    "leaf",
+    "window",
+    "tiled",
+    "pseudo_tiled",
+    "floating",
+    "fullscreen",
+    "hidden",
+    "sticky",
+    "private",
+    "locked",
+    "marked",
+    "urgent",
+    "below",
+    "normal",
+    "above",
+    "horizontal",
+    "vertical",
+    "same_class",
+    "descendant_of",
+    "ancestor_of",
+];
+
+const DESKTOP_MODIFIERS: &[&str] = &[
+    "focused",
+    "active",
+    "occupied",
+    "urgent",
+    "local",
+    "tiled",
+    "monocle",
+    "user_tiled",
+    "user_monocle",
+];
+
+const MONITOR_MODIFIERS: &[&str] = &["focused", "occupied"];
+
+/// Descriptors, that depend on history, pointer or geometry of the screen,
+/// which state dump doesn't describe well enough.
+const UNSUPPORTED: &[&str] = &[
+    "north", "west", "south", "east", "next", "prev", "last", "newest",
+    "older", "newer", "pointed", "biggest", "smallest",
+];
+
+/// Monitor, desktop and node, that selector is matched against.
+#[derive(Clone, Copy)]
+struct Loc<'a> {
+    monitor: &'a Monitor,
+    desktop: &'a Desktop,
+    node: Option<&'a Node>,
+}
+
+fn invalid(selector: &str, reason: &str) -> QueryError {
+    QueryError::InvalidSelector(format!(
+        "Selector '{selector}' can't be evaluated: {reason}"
+    ))
+}
+
+/// Modifiers of the selector with their expected values, e.g. `false` for
+/// `!hidden`.
+type Modifiers<'s> = Vec<(&'s str, bool)>;
+
+/// Splits selector into descriptor and modifiers, e.g. `focused.!hidden`
+/// into `focused` and `[("hidden", false)]`.
+fn split<'s>(
+    selector: &'s str,
+    known: &[&str],
+) -> Result<(&'s str, Modifiers<'s>), QueryError> {
+    let (descriptor, modifiers) = match selector.find('.') {
+        Some(index) => (&selector[..index], &selector[index + 1..]),
+        None => return Ok((selector, Vec::new())),
+    };
+
+    let mut result = Vec::new();
+
+    for modifier in modifiers.split('.') {
+        let (name, expected) = match modifier.strip_prefix('!') {
+            Some(name) => (name, false),
+            None => (modifier, true),
+        };
+
+        if !known.contains(&name) {
+            return Err(invalid(
+                selector,
+                &format!("unknown modifier '{name}'"),
+            ));
+        }
+
+        result.push((name, expected));
+    }
+
+    Ok((descriptor, result))
+}
+
+fn focused(state: &State) -> Option<Loc<'_>> {
+    let monitor = state.find_monitor(state.focused_monitor_id)?;
+    let desktop = monitor.find_desktop(monitor.focused_desktop_id)?;
+
+    Some(Loc {
+        monitor,
+        desktop,
+        node: desktop.find_node(desktop.focused_node_id),
+    })
+}
+
+/// Returns all nodes in the order, bspwm lists them in replies to queries,
+/// i.e. pre-order traversal of every desktop.
+fn all_nodes(state: &State) -> Vec<Loc<'_>> {
+    state
+        .desktops()
+        .flat_map(|(monitor, desktop)| {
+            desktop.nodes().map(move |node| Loc {
+                monitor,
+                desktop,
+                node: Some(node),
+            })
+        })
+        .collect()
+}
+
+fn all_desktops(state: &State) -> Vec<Loc<'_>> {
+    state
+        .desktops()
+        .map(|(monitor, desktop)| Loc {
+            monitor,
+            desktop,
+            node: None,
+        })
+        .collect()
+}
+
+fn locate_node(state: &State, id: Id) -> Option<Loc<'_>> {
+    state.desktops().find_map(|(monitor, desktop)| {
+        Some(Loc {
+            monitor,
+            desktop,
+            node: Some(desktop.find_node(id)?),
+        })
+    })
+}
+
+/// Returns value of the modifier for the node, or `None`, if node can't
+/// match it regardless of negation, e.g. client state of a non-window.
+fn node_modifier(
+    name: &str,
+    loc: &Loc,
+    reference: Option<&Loc>,
+    focused: Option<&Loc>,
+) -> Option<bool> {
+    let node = loc.node?;
+    let client = node.client.as_ref();
+    let reference_node = reference.and_then(|x| x.node);
+
+    let value = match name {
+        "focused" => {
+            focused.and_then(|x| x.node).map(|x| x.id) == Some(node.id)
+        }
+        "active" => loc.desktop.focused_node_id == node.id,
+        "automatic" => node.presel.is_none(),
+        "local" => reference.map(|x| x.desktop.id) == Some(loc.desktop.id),
+        "leaf" => node.is_leaf(),
+        "window" => client.is_some(),
+        "tiled" | "pseudo_tiled" | "floating" | "fullscreen" => {
+            client?.state.to_string() == name
+        }
+        "hidden" => node.hidden,
+        "sticky" => node.sticky,
+        "private" => node.private,
+        "locked" => node.locked,
+        "marked" => node.marked,
+        "urgent" => client?.urgent,
+        "below" | "normal" | "above" => client?.layer.to_string() == name,
+        "horizontal" | "vertical" => node.split_type.to_string() == name,
+        "same_class" => {
+            match (client, reference_node.and_then(|x| x.client.as_ref())) {
+                (Some(client), Some(other)) => {
+                    client.class_name == other.class_name
+                }
+                _ => false,
+            }
+        }
+        "descendant_of" => {
+            reference_node.is_some_and(|x| x.find(node.id).is_some())
+        }
+        "ancestor_of" => {
+            reference_node.is_some_and(|x| node.find(x.id).is_some())
+        }
+        _ => unreachable!("modifiers are checked, when parsed"),
+    };
+
+    Some(value)
+}
+
+fn desktop_modifier(
+    name: &str,
+    loc: &Loc,
+    reference: Option<&Loc>,
+    focused: Option<&Loc>,
+) -> bool {
+    let desktop = loc.desktop;
+
+    match name {
+        "focused" => focused.map(|x| x.desktop.id) == Some(desktop.id),
+        "active" => loc.monitor.focused_desktop_id == desktop.id,
+        "occupied" => desktop.root.is_some(),
+        "urgent" => desktop
+            .windows()
+            .any(|node| node.client.as_ref().is_some_and(|x| x.urgent)),
+        "local" => reference.map(|x| x.monitor.id) == Some(loc.monitor.id),
+        "tiled" | "monocle" => desktop.layout.to_string() == name,
+        "user_tiled" | "user_monocle" => {
+            format!("user_{}", desktop.user_layout) == name
+        }
+        _ => unreachable!("modifiers are checked, when parsed"),
+    }
+}
+
+fn monitor_modifier(name: &str, monitor: &Monitor, state: &State) -> bool {
+    match name {
+        "focused" => monitor.id == state.focused_monitor_id,
+        "occupied" => monitor
+            .find_desktop(monitor.focused_desktop_id)
+            .is_some_and(|desktop| desktop.root.is_some()),
+        _ => unreachable!("modifiers are checked, when parsed"),
+    }
+}
+
+/// Parses index of `^n` descriptor, which starts from 1.
+fn parse_index(selector: &str, descriptor: &str) -> Result<usize, QueryError> {
+    descriptor[1..]
+        .parse::<usize>()
+        .ok()
+        .filter(|index| *index > 0)
+        .map(|index| index - 1)
+        .ok_or_else(|| invalid(selector, "invalid index"))
+}
+
+fn parse_id(selector: &str, descriptor: &str) -> Result<Id, QueryError> {
+    from_hex_to_id(descriptor).map_err(|_| invalid(selector, "invalid id"))
+}
+
+fn check_supported(selector: &str, descriptor: &str) -> Result<(), QueryError> {
+    if UNSUPPORTED.contains(&descriptor) {
+        return Err(invalid(
+            selector,
+            &format!("descriptor '{descriptor}' isn't supported offline"),
+        ));
+    }
+
+    Ok(())
+}
+
+fn eval_nodes<'a>(
+    state: &'a State,
+    selector: &str,
+) -> Result<Vec<Loc<'a>>, QueryError> {
+    let focused = focused(state);
+
+    let (reference, rest) = match selector.rsplit_once('#') {
+        Some((reference, rest)) => {
+            (eval_nodes(state, reference)?.into_iter().next(), rest)
+        }
+        None => (focused, selector),
+    };
+
+    let (descriptor, modifiers) = split(rest, NODE_MODIFIERS)?;
+
+    let matches = |loc: &Loc| {
+        modifiers.iter().all(|(name, expected)| {
+            node_modifier(name, loc, reference.as_ref(), focused.as_ref())
+                == Some(*expected)
+        })
+    };
+
+    let candidates = match descriptor {
+        "" => all_nodes(state),
+
+        "any" => {
+            return Ok(all_nodes(state)
+                .into_iter()
+                .find(matches)
+                .into_iter()
+                .collect())
+        }
+
+        "first_ancestor" => {
+            let ancestors = reference
+                .and_then(|x| x.desktop.path_to(x.node?.id))
+                .unwrap_or_default();
+
+            return Ok(ancestors
+                .into_iter()
+                .rev()
+                .skip(1)
+                .filter_map(|node| locate_node(state, node.id))
+                .find(matches)
+                .into_iter()
+                .collect());
+        }
+
+        "focused" => focused.filter(|x| x.node.is_some()).into_iter().collect(),
+
+        _ if descriptor.starts_with('@') => {
+            let path: Path = descriptor
+                .parse()
+                .map_err(|_| invalid(selector, "invalid path"))?;
+
+            let loc = match &path.desktop {
+                Some(desktop) => {
+                    eval_desktops(state, desktop)?.into_iter().next()
+                }
+                None => reference,
+            };
+
+            loc.and_then(|loc| {
+                let start = match (&path.desktop, loc.node) {
+                    (None, Some(node)) => node.id,
+                    _ => loc.desktop.focused_node_id,
+                };
+
+                Some(Loc {
+                    node: Some(path.resolve_from(loc.desktop, start)?),
+                    ..loc
+                })
+            })
+            .into_iter()
+            .collect()
+        }
+
+        _ if descriptor.starts_with("0x") => {
+            let id = parse_id(selector, descriptor)?;
+            locate_node(state, id).into_iter().collect()
+        }
+
+        _ => {
+            check_supported(selector, descriptor)?;
+            return Err(invalid(selector, "unknown descriptor"));
+        }
+    };
+
+    Ok(candidates.into_iter().filter(matches).collect())
+}
+
+fn eval_desktops<'a>(
+    state: &'a State,
+    selector: &str,
+) -> Result<Vec<Loc<'a>>, QueryError> {
+    let focused = focused(state);
+
+    let (reference, rest) = match selector.rsplit_once('#') {
+        Some((reference, rest)) => {
+            (eval_desktops(state, reference)?.into_iter().next(), rest)
+        }
+        None => (focused, selector),
+    };
+
+    let (descriptor, modifiers) = split(rest, DESKTOP_MODIFIERS)?;
+
+    let matches = |loc: &Loc| {
+        modifiers.iter().all(|(name, expected)| {
+            desktop_modifier(name, loc, reference.as_ref(), focused.as_ref())
+                == *expected
+        })
+    };
+
+    let desktops = all_desktops(state);
+
+    let candidates: Vec<_> = match descriptor {
+        "" => desktops,
+
+        "any" => {
+            return Ok(desktops.into_iter().find(matches).into_iter().collect())
+        }
+
+        "focused" => focused.into_iter().collect(),
+
+        _ if descriptor.starts_with('^') => {
+            let index = parse_index(selector, descriptor)?;
+            desktops.get(index).copied().into_iter().collect()
+        }
+
+        _ if descriptor.starts_with("0x") => {
+            let id = parse_id(selector, descriptor)?;
+            desktops
+                .into_iter()
+                .filter(|x| x.desktop.id == id)
+                .collect()
+        }
+
+        _ => {
+            check_supported(selector, descriptor)?;
+
+            match descriptor.rsplit_once(':') {
+                Some((monitor, "focused")) => eval_monitors(state, monitor)?
+                    .into_iter()
+                    .take(1)
+                    .flat_map(|monitor| {
+                        desktops.iter().copied().filter(move |x| {
+                            x.monitor.id == monitor.id
+                                && x.desktop.id == monitor.focused_desktop_id
+                        })
+                    })
+                    .collect(),
+
+                Some((monitor, index)) if index.starts_with('^') => {
+                    let index = parse_index(selector, index)?;
+
+                    eval_monitors(state, monitor)?
+                        .into_iter()
+                        .take(1)
+                        .filter_map(|monitor| {
+                            desktops
+                                .iter()
+                                .copied()
+                                .filter(|x| x.monitor.id == monitor.id)
+                                .nth(index)
+                        })
+                        .collect()
+                }
+
+                _ => desktops
+                    .into_iter()
+                    .filter(|x| x.desktop.name == descriptor)
+                    .take(1)
+                    .collect(),
+            }
+        }
+    };
+
+    Ok(candidates.into_iter().filter(matches).collect())
+}
+
+fn eval_monitors<'a>(
+    state: &'a State,
+    selector: &str,
+) -> Result<Vec<&'a Monitor>, QueryError> {
+    let (descriptor, modifiers) = match selector.rsplit_once('#') {
+        // Modifiers of monitors don't depend on the reference
+        Some((reference, rest)) => {
+            eval_monitors(state, reference)?;
+            split(rest, MONITOR_MODIFIERS)?
+        }
+        None => split(selector, MONITOR_MODIFIERS)?,
+    };
+
+    let matches = |monitor: &&Monitor| {
+        modifiers.iter().all(|(name, expected)| {
+            monitor_modifier(name, monitor, state) == *expected
+        })
+    };
+
+    let monitors = state.monitors.iter();
+
+    let candidates: Vec<_> = match descriptor {
+        "" => monitors.collect(),
+        "any" => return Ok(monitors.filter(matches).take(1).collect()),
+        "focused" => monitors
+            .filter(|x| x.id == state.focused_monitor_id)
+            .collect(),
+        "primary" => monitors
+            .filter(|x| Some(x.id) == state.primary_monitor_id)
+            .collect(),
+
+        _ if descriptor.starts_with('^') => {
+            let index = parse_index(selector, descriptor)?;
+            state.monitors.get(index).into_iter().collect()
+        }
+
+        _ if descriptor.starts_with("0x") => {
+            let id = parse_id(selector, descriptor)?;
+            monitors.filter(|x| x.id == id).collect()
+        }
+
+        _ => {
+            check_supported(selector, descriptor)?;
+            monitors.filter(|x| x.name == descriptor).take(1).collect()
+        }
+    };
+
+    Ok(candidates.into_iter().filter(matches).collect())
+}
+
+/// Returns the first desktop, that matches the selector.
+pub(super) fn find_desktop<'a>(
+    state: &'a State,
+    selector: &str,
+) -> Option<&'a Desktop> {
+    let desktops = eval_desktops(state, selector).ok()?;
+    desktops.first().map(|x| x.desktop)
+}
+
+impl State {
+    /// Returns ids of the nodes, that match the selector, like `bspc query
+    /// -N -n <selector>` does, but without querying bspwm.
+    ///
+    /// Selector, that consists only of modifiers (e.g. `.local.window`),
+    /// matches every node, that has them, and other selectors match at most
+    /// one node. Nodes are returned in the same order, as bspwm returns them.
+    ///
+    /// Descriptors, that depend on focus history, pointer or directions,
+    /// aren't supported and result in [`QueryError::InvalidSelector`].
+    ///
+    /// ```no_run
+    /// use bspc_rs::selectors::NodeSelector;
+    /// use bspc_rs::state;
+    ///
+    /// let state = state::get_current_state().unwrap();
+    /// let nodes = state
+    ///     .select_nodes(NodeSelector(".local.window.!hidden.tiled"))
+    ///     .unwrap();
+    ///
+    /// println!("{:?}", nodes);
+    /// ```
+    pub fn select_nodes(
+        &self,
+        selector: NodeSelector,
+    ) -> Result<Vec<Id>, QueryError> {
+        let nodes = eval_nodes(self, selector.extract())?;
+        Ok(nodes.iter().filter_map(|x| x.node).map(|x| x.id).collect())
+    }
+
+    /// Returns ids of the desktops, that match the selector, like `bspc
+    /// query -D -d <selector>` does, but without querying bspwm. See
+    /// [`State::select_nodes`].
+    pub fn select_desktops(
+        &self,
+        selector: DesktopSelector,
+    ) -> Result<Vec<Id>, QueryError> {
+        let desktops = eval_desktops(self, selector.extract())?;
+        Ok(desktops.iter().map(|x| x.desktop.id).collect())
+    }
+
+    /// Returns ids of the monitors, that match the selector, like `bspc
+    /// query -M -m <selector>` does, but without querying bspwm. See
+    /// [`State::select_nodes`].
+    pub fn select_monitors(
+        &self,
+        selector: MonitorSelector,
+    ) -> Result<Vec<Id>, QueryError> {
+        let monitors = eval_monitors(self, selector.extract())?;
+        Ok(monitors.iter().map(|x| x.id).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::{QUERIES, STATE_DUMP};
+
+    fn state() -> State {
+        serde_json::from_str(STATE_DUMP).unwrap()
+    }
+
+    fn parse_id(id: &str) -> Id {
+        Id::from_str_radix(id.trim_start_matches("0x"), 16).unwrap()
+    }
+
+    #[test]
+    fn compare_with_queries() {
+        let state = state();
+        let queries = QUERIES
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for line in queries {
+            let mut fields = line.split('\t');
+            let (domain, selector) = (fields.next(), fields.next().unwrap());
+            let expected: Vec<Id> = fields
+                .next()
+                .unwrap()
+                .split_whitespace()
+                .map(parse_id)
+                .collect();
+
+            let ids = match domain {
+                Some("-N") => state.select_nodes(NodeSelector(selector)),
+                Some("-D") => state.select_desktops(DesktopSelector(selector)),
+                Some("-M") => state.select_monitors(MonitorSelector(selector)),
+                _ => panic!("Unknown domain in line: {line}"),
+            };
+
+            assert_eq!(ids.unwrap(), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn invalid_selectors() {
+        let state = state();
+
+        for selector in ["north", ".bogus", "focused.!", "older.window"] {
+            assert!(matches!(
+                state.select_nodes(NodeSelector(selector)),
+                Err(QueryError::InvalidSelector(_))
+            ));
+        }
+
+        assert!(state.select_desktops(DesktopSelector("^0")).is_err());
+        assert!(state.select_monitors(MonitorSelector("pointed")).is_err());
+    }
+}
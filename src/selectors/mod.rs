@@ -13,19 +13,6 @@
 // pub struct MonitorSelector {}
 // pub struct DesktopSelector {}
 
-// pub enum PathJump {
-//     First,
-//     Second,
-//     Brother,
-//     Parent,
-//     Dir(Dir),
-// }
-
-// pub struct Path {
-//     pub root: DesktopSelector,
-//     pub jumps: Vec<PathJump>,
-// }
-
 // pub enum NodeDescriptor {
 //     Dir(Dir),
 //     CycleDir(CycleDir),
@@ -67,6 +54,11 @@
 //     pub modifier: Option<NodeModifier>,
 // }
 
+mod eval;
+mod path;
+
+pub use path::{Path, PathJump};
+
 /// String slice inside represents node selector.
 pub struct NodeSelector<'a>(pub &'a str);
 
@@ -132,10 +124,26 @@ impl<'a> Selector for MonitorSelector<'a> {
     }
 }
 
-fn collect(
+/// Joins given selectors into the form, in which they are sent to bspwm,
+/// i.e. `--monitor\x00<sel>\x00--desktop\x00<sel>\x00--node\x00<sel>\x00`.
+pub(crate) fn collect(
     monitor_selector: Option<MonitorSelector>,
     desktop_selector: Option<DesktopSelector>,
     node_selector: Option<NodeSelector>,
 ) -> String {
-    todo!();
+    let mut result = String::new();
+
+    if let Some(sel) = monitor_selector {
+        result = format!("{result}--monitor\x00{}\x00", sel.extract());
+    }
+
+    if let Some(sel) = desktop_selector {
+        result = format!("{result}--desktop\x00{}\x00", sel.extract());
+    }
+
+    if let Some(sel) = node_selector {
+        result = format!("{result}--node\x00{}\x00", sel.extract());
+    }
+
+    result
 }
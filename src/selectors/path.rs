@@ -0,0 +1,347 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::eval::find_desktop;
+use crate::errors::ParseError;
+use crate::properties::{Dir, SplitType};
+use crate::state::State;
+use crate::tree::{Desktop, Node};
+use crate::Id;
+
+/// Single step of the [`Path`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathJump {
+    /// First child, written as `first` or `1`.
+    First,
+
+    /// Second child, written as `second` or `2`.
+    Second,
+
+    /// Other child of the parent.
+    Brother,
+
+    Parent,
+
+    /// The closest ancestor, which splits the node from the area in the
+    /// given direction, i.e. fence of the node in that direction.
+    Dir(Dir),
+}
+
+impl FromStr for PathJump {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "first" | "1" => Ok(PathJump::First),
+            "second" | "2" => Ok(PathJump::Second),
+            "brother" => Ok(PathJump::Brother),
+            "parent" => Ok(PathJump::Parent),
+            _ => Dir::from_str(input)
+                .map(PathJump::Dir)
+                .map_err(|err| ParseError::InvalidValue(err.into())),
+        }
+    }
+}
+
+impl fmt::Display for PathJump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathJump::First => write!(f, "first"),
+            PathJump::Second => write!(f, "second"),
+            PathJump::Brother => write!(f, "brother"),
+            PathJump::Parent => write!(f, "parent"),
+            PathJump::Dir(dir) => write!(f, "{}", dir),
+        }
+    }
+}
+
+/// Path descriptor of node selector, i.e. `@[DESKTOP_SEL:][[/]JUMP](/JUMP)*`,
+/// e.g. `@/first/second`, `@brother` or `@0x00200007:/2`.
+///
+/// Path starts from the root of the desktop, if it starts with `/`, and from
+/// its focused node otherwise. It can be resolved against local tree with
+/// [`Path::resolve`] and [`Path::resolve_in_state`], and formatted back with
+/// [`Display`](fmt::Display), so it can be sent to bspwm.
+///
+/// ```rust
+/// use bspc_rs::selectors::{Path, PathJump};
+///
+/// let path: Path = "@web:/1/brother".parse().unwrap();
+///
+/// assert_eq!(path.desktop.as_deref(), Some("web"));
+/// assert_eq!(path.jumps, [PathJump::First, PathJump::Brother]);
+/// assert_eq!(path.to_string(), "@web:/first/brother");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    /// Selector of the desktop, that path is in. Focused desktop is used, if
+    /// it's `None`.
+    pub desktop: Option<String>,
+
+    /// Whether path starts from the root of the desktop.
+    pub is_absolute: bool,
+
+    pub jumps: Vec<PathJump>,
+}
+
+impl Path {
+    /// Returns canonical path to the node with the given id, that starts
+    /// from the root of desktop with id of the desktop as its selector, e.g.
+    /// `@0x00200007:/second/first`.
+    pub fn canonical(desktop: &Desktop, id: Id) -> Option<Path> {
+        let nodes = desktop.path_to(id)?;
+        let jumps = nodes
+            .windows(2)
+            .map(|pair| match &pair[0].first_child {
+                Some(child) if child.id == pair[1].id => PathJump::First,
+                _ => PathJump::Second,
+            })
+            .collect();
+
+        Some(Path {
+            desktop: Some(format!("0x{:08X}", desktop.id)),
+            is_absolute: true,
+            jumps,
+        })
+    }
+
+    /// Returns canonical path to the node with the given id, see
+    /// [`Path::canonical`].
+    pub fn canonical_in_state(state: &State, id: Id) -> Option<Path> {
+        state
+            .desktops()
+            .find_map(|(_, desktop)| Path::canonical(desktop, id))
+    }
+
+    /// Returns node, that path refers to in the given desktop. Desktop
+    /// selector of the path is ignored.
+    pub fn resolve<'a>(&self, desktop: &'a Desktop) -> Option<&'a Node> {
+        self.resolve_from(desktop, desktop.focused_node_id)
+    }
+
+    /// Returns node, that path refers to in the given state. Desktop
+    /// selector is evaluated like in [`State::select_desktops`].
+    pub fn resolve_in_state<'a>(&self, state: &'a State) -> Option<&'a Node> {
+        let selector = self.desktop.as_deref().unwrap_or("focused");
+        self.resolve(find_desktop(state, selector)?)
+    }
+
+    /// Resolves path, that starts from the node with the given id, unless
+    /// it's absolute.
+    pub(super) fn resolve_from<'a>(
+        &self,
+        desktop: &'a Desktop,
+        start: Id,
+    ) -> Option<&'a Node> {
+        let mut nodes = if self.is_absolute {
+            vec![desktop.root.as_ref()?]
+        } else {
+            desktop.path_to(start)?
+        };
+
+        for jump in &self.jumps {
+            jump_to(&mut nodes, jump)?;
+        }
+
+        nodes.pop()
+    }
+}
+
+/// Applies jump to the path from the root to the current node.
+fn jump_to(nodes: &mut Vec<&Node>, jump: &PathJump) -> Option<()> {
+    let node = *nodes.last()?;
+
+    match jump {
+        PathJump::First => nodes.push(node.first_child.as_deref()?),
+        PathJump::Second => nodes.push(node.second_child.as_deref()?),
+
+        PathJump::Parent => {
+            nodes.len().checked_sub(2)?;
+            nodes.pop();
+        }
+
+        PathJump::Brother => {
+            let parent = nodes.get(nodes.len().checked_sub(2)?)?;
+            let brother = parent.children().find(|x| x.id != node.id)?;
+
+            nodes.pop();
+            nodes.push(brother);
+        }
+
+        PathJump::Dir(dir) => {
+            let depth = nodes[..nodes.len() - 1]
+                .iter()
+                .rposition(|ancestor| is_fence(ancestor, node, dir))?;
+
+            nodes.truncate(depth + 1);
+        }
+    }
+
+    Some(())
+}
+
+/// Checks, if ancestor splits the node from the area in the given
+/// direction, the same way bspwm does.
+fn is_fence(ancestor: &Node, node: &Node, dir: &Dir) -> bool {
+    let a = &ancestor.rectangle;
+    let n = &node.rectangle;
+
+    match (dir, &ancestor.split_type) {
+        (Dir::North, SplitType::Horizontal) => a.y < n.y,
+        (Dir::South, SplitType::Horizontal) => a.y + a.height > n.y + n.height,
+        (Dir::West, SplitType::Vertical) => a.x < n.x,
+        (Dir::East, SplitType::Vertical) => a.x + a.width > n.x + n.width,
+        _ => false,
+    }
+}
+
+impl FromStr for Path {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let path = input.strip_prefix('@').ok_or_else(|| {
+            ParseError::InvalidValue(
+                format!("Path has to start with '@': '{input}'").into(),
+            )
+        })?;
+
+        // Jumps can't contain ':', so the last one ends desktop selector
+        let (desktop, path) = match path.rsplit_once(':') {
+            Some((desktop, path)) => (Some(desktop.to_string()), path),
+            None => (None, path),
+        };
+
+        let (is_absolute, path) = match path.strip_prefix('/') {
+            Some(path) => (true, path),
+            None => (false, path),
+        };
+
+        let jumps = path
+            .split('/')
+            .filter(|jump| !jump.is_empty())
+            .map(PathJump::from_str)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Path {
+            desktop,
+            is_absolute,
+            jumps,
+        })
+    }
+}
+
+impl fmt::Display for Path {
+    /// Formats path as it is written in node selector, e.g.
+    /// `@0x00200007:/first/second`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "@")?;
+
+        if let Some(desktop) = &self.desktop {
+            write!(f, "{}:", desktop)?;
+        }
+
+        if self.is_absolute {
+            write!(f, "/")?;
+        }
+
+        for (index, jump) in self.jumps.iter().enumerate() {
+            if index > 0 {
+                write!(f, "/")?;
+            }
+
+            write!(f, "{}", jump)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::STATE_DUMP;
+
+    fn state() -> State {
+        serde_json::from_str(STATE_DUMP).unwrap()
+    }
+
+    fn resolve(state: &State, path: &str) -> Option<Id> {
+        let path: Path = path.parse().unwrap();
+        path.resolve_in_state(state).map(|node| node.id)
+    }
+
+    #[test]
+    fn parse_path() {
+        let path: Path = "@2 web:/second/parent/west".parse().unwrap();
+
+        assert_eq!(path.desktop.as_deref(), Some("2 web"));
+        assert!(path.is_absolute);
+        assert_eq!(
+            path.jumps,
+            [PathJump::Second, PathJump::Parent, PathJump::Dir(Dir::West)]
+        );
+
+        let path: Path = "@brother".parse().unwrap();
+        assert_eq!(path.desktop, None);
+        assert!(!path.is_absolute);
+        assert_eq!(path.to_string(), "@brother");
+
+        assert!("first".parse::<Path>().is_err());
+        assert!("@/third".parse::<Path>().is_err());
+    }
+
+    #[test]
+    fn resolve_path() {
+        let state = state();
+
+        // Focused node of the focused desktop is 0x00400002
+        assert_eq!(resolve(&state, "@/"), Some(0x00600001));
+        assert_eq!(resolve(&state, "@/2/1"), Some(0x00800002));
+        assert_eq!(resolve(&state, "@brother"), Some(0x00600002));
+        assert_eq!(resolve(&state, "@parent"), Some(0x00600001));
+        assert_eq!(resolve(&state, "@/second/first/brother"), Some(0x00A00002));
+        assert_eq!(resolve(&state, "@2 web:/"), Some(0x00C00002));
+        assert_eq!(resolve(&state, "@0x0020000B:/"), Some(0x00E00002));
+
+        assert_eq!(resolve(&state, "@/1/1"), None);
+        assert_eq!(resolve(&state, "@/parent"), None);
+        assert_eq!(resolve(&state, "@3:/"), None);
+        assert_eq!(resolve(&state, "@unknown:/"), None);
+    }
+
+    #[test]
+    fn resolve_fence() {
+        let state = state();
+
+        // Root splits vertically, its second child splits horizontally
+        assert_eq!(resolve(&state, "@/2/2/north"), Some(0x00600002));
+        assert_eq!(resolve(&state, "@/2/2/west"), Some(0x00600001));
+        assert_eq!(resolve(&state, "@/2/1/south"), Some(0x00600002));
+        assert_eq!(resolve(&state, "@/2/2/south"), None);
+        assert_eq!(resolve(&state, "@east"), Some(0x00600001));
+        assert_eq!(resolve(&state, "@west"), None);
+    }
+
+    #[test]
+    fn canonical_path() {
+        let state = state();
+
+        for (_, desktop) in state.desktops() {
+            for node in desktop.nodes() {
+                let path = Path::canonical_in_state(&state, node.id).unwrap();
+                let parsed: Path = path.to_string().parse().unwrap();
+
+                assert_eq!(parsed, path);
+                assert_eq!(resolve(&state, &path.to_string()), Some(node.id));
+            }
+        }
+
+        assert_eq!(
+            Path::canonical_in_state(&state, 0x00A00002)
+                .unwrap()
+                .to_string(),
+            "@0x00200007:/second/second"
+        );
+        assert!(Path::canonical_in_state(&state, 0x12345678).is_none());
+    }
+}
@@ -2,13 +2,15 @@
 //! equivalent to those, which are run by `bspc config <setting>` command in
 //! your shell.
 
+use std::str::FromStr;
 use std::string::ToString;
 use strum_macros::Display;
 use strum_macros::EnumString;
 
+use crate::client::BspcClient;
 use crate::errors::{ParseError, ReplyError};
-use crate::selectors::{DesktopSelector, MonitorSelector, NodeSelector};
-use crate::socket::{self, BspcCommunication};
+use crate::selectors::{self, DesktopSelector, MonitorSelector, NodeSelector};
+use crate::socket::{BspcCommunication, Transport};
 
 #[derive(Debug, Clone, Copy, EnumString, Display)]
 #[strum(serialize_all = "snake_case")]
@@ -120,6 +122,26 @@ trait ConfigProperties {
     ) -> Result<(), ReplyError>;
 }
 
+pub(crate) fn process_get_reply(reply: Vec<String>) -> String {
+    if reply.len() > 1 {
+        // TODO: Test if this can happen
+        panic!("{}", format!("Something is weird, reply has more than one element, this is debug log: {:#?}", reply));
+    }
+
+    reply[0].to_string()
+}
+
+pub(crate) fn process_set_reply(
+    reply: Result<Vec<String>, ReplyError>,
+) -> Result<(), ReplyError> {
+    // bspwm doesn't reply anything, if setting was set successfully
+    match reply {
+        Err(ReplyError::NoReply) => Ok(()),
+        Err(err) => Err(err),
+        Ok(_) => Err(ParseError::ConversionFailed)?,
+    }
+}
+
 impl<T: BspcCommunication> ConfigProperties for T {
     fn get_config_property(
         &mut self,
@@ -128,14 +150,7 @@ impl<T: BspcCommunication> ConfigProperties for T {
         self.send_message(&format!("config\x00{}\x00", property))?;
         let reply = self.receive_message()?;
 
-        if reply.len() > 1 {
-            // TODO: Test if this can happen
-            panic!("{}", format!("Something is weird, reply has more than one element, this is debug log: {:#?}", reply));
-        }
-
-        let reply = &reply[0];
-
-        Ok(reply.to_string())
+        Ok(process_get_reply(reply))
     }
 
     fn set_config_property(
@@ -144,601 +159,178 @@ impl<T: BspcCommunication> ConfigProperties for T {
         value: &str,
     ) -> Result<(), ReplyError> {
         self.send_message(&format!("config\x00{}\x00{}\x00", property, value))?;
-        let reply = self.receive_message()?;
-
-        if reply.is_empty() {
-            return Ok(());
-        }
 
-        Err(ParseError::ConversionFailed)?
+        process_set_reply(self.receive_message())
     }
 }
 
-pub fn get_normal_border_color() -> Result<String, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("normal_border_color")
-}
-
-pub fn get_active_border_color() -> Result<String, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("active_border_color")
-}
-
-pub fn get_focused_border_color() -> Result<String, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("focused_border_color")
-}
-
-pub fn get_presel_feedback_color() -> Result<String, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("presel_feedback_color")
-}
-
-pub fn get_split_ratio() -> Result<f32, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("split_ratio")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_status_prefix() -> Result<String, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("status_prefix")
-}
-
-pub fn get_external_rules_command() -> Result<String, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("external_rules_command")
-}
-
-pub fn get_automatic_scheme() -> Result<Scheme, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("automatic_scheme")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_initial_polarity() -> Result<Polarity, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("initial_polarity")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_directional_focus_tightness() -> Result<Tightness, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("directional_focus_tightness")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_removal_adjustment() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("removal_adjustment")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_presel_feedback() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("presel_feedback")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_borderless_monocle() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("borderless_monocle")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_gapless_monocle() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("gapless_monocle")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_top_monocle_padding() -> Result<i16, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("top_monocle_padding")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_right_monocle_padding() -> Result<i16, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("right_monocle_padding")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_bottom_monocle_padding() -> Result<i16, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("bottom_monocle_padding")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_left_monocle_padding() -> Result<i16, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("left_monocle_padding")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_single_monocle() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("single_monocle")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_pointer_motion_interval() -> Result<u16, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("pointer_motion_interval")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_pointer_modifier() -> Result<PointerModifier, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("pointer_modifier")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_pointer_action1() -> Result<PointerAction, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("pointer_action1")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_pointer_action2() -> Result<PointerAction, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("pointer_action2")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_pointer_action3() -> Result<PointerAction, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("pointer_action3")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_click_to_focus() -> Result<ClickToFocus, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("click_to_focus")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_swallow_first_click() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("swallow_first_click")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_focus_follows_pointer() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("focus_follows_pointer")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_pointer_follows_focus() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("pointer_follows_focus")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_pointer_follows_monitor() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("pointer_follows_monitor")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_mapping_events_count() -> Result<i32, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("mapping_events_count")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_ignore_ewmh_focus() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("ignore_ewmh_focus")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_ignore_ewmh_fullscreen() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("ignore_ewmh_fullscreen")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_ignore_ewmh_struts() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("ignore_ewmh_struts")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_center_pseudo_tiled() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("center_pseudo_tiled")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_honor_size_hints() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("honor_size_hints")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_remove_disabled_monitors() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("remove_disabled_monitors")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_remove_unplugged_monitors() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("remove_unplugged_monitors")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn get_merge_overlapping_monitors() -> Result<bool, ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.get_config_property("merge_overlapping_monitors")?
-        .parse()
-        .map_err(From::from)
-}
-
-pub fn set_normal_border_color(value: String) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("normal_border_color", &value)
-}
-
-pub fn set_active_border_color(value: String) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("active_border_color", &value)
-}
-
-pub fn set_focused_border_color(value: String) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("focused_border_color", &value)
-}
-
-pub fn set_presel_feedback_color(value: String) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("presel_feedback_color", &value)
-}
-
-pub fn set_split_ratio(value: f32) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("split_ratio", &value.to_string())
-}
-
-pub fn set_status_prefix(value: String) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("status_prefix", &value)
-}
-
-pub fn set_external_rules_command(value: String) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("external_rules_command", &value)
-}
-
-pub fn set_automatic_scheme(value: Scheme) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("automatic_scheme", &value.to_string())
-}
-
-pub fn set_initial_polarity(value: Polarity) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("initial_polarity", &value.to_string())
-}
-
-pub fn set_directional_focus_tightness(
-    value: Tightness,
-) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("directional_focus_tightness", &value.to_string())
-}
-
-pub fn set_removal_adjustment(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("removal_adjustment", &value.to_string())
-}
-
-pub fn set_presel_feedback(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("presel_feedback", &value.to_string())
-}
-
-pub fn set_borderless_monocle(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("borderless_monocle", &value.to_string())
-}
-
-pub fn set_gapless_monocle(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("gapless_monocle", &value.to_string())
-}
-
-pub fn set_top_monocle_padding(value: i16) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("top_monocle_padding", &value.to_string())
-}
-
-pub fn set_right_monocle_padding(value: i16) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("right_monocle_padding", &value.to_string())
-}
-
-pub fn set_bottom_monocle_padding(value: i16) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("bottom_monocle_padding", &value.to_string())
-}
-
-pub fn set_left_monocle_padding(value: i16) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("left_monocle_padding", &value.to_string())
-}
-
-pub fn set_single_monocle(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("single_monocle", &value.to_string())
-}
-
-pub fn set_pointer_motion_interval(value: u16) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("pointer_motion_interval", &value.to_string())
-}
-
-pub fn set_pointer_modifier(value: PointerModifier) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("pointer_modifier", &value.to_string())
-}
-
-pub fn set_pointer_action1(value: PointerAction) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("pointer_action1", &value.to_string())
-}
-
-pub fn set_pointer_action2(value: PointerAction) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("pointer_action2", &value.to_string())
-}
-
-pub fn set_pointer_action3(value: PointerAction) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("pointer_action3", &value.to_string())
-}
-
-pub fn set_click_to_focus(value: ClickToFocus) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("click_to_focus", &value.to_string())
-}
-
-pub fn set_swallow_first_click(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("swallow_first_click", &value.to_string())
-}
-
-pub fn set_focus_follows_pointer(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("focus_follows_pointer", &value.to_string())
-}
-
-pub fn set_pointer_follows_focus(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("pointer_follows_focus", &value.to_string())
-}
-
-pub fn set_pointer_follows_monitor(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("pointer_follows_monitor", &value.to_string())
-}
-
-pub fn set_mapping_events_count(value: i32) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("mapping_events_count", &value.to_string())
-}
-
-pub fn set_ignore_ewmh_focus(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("ignore_ewmh_focus", &value.to_string())
-}
-
-pub fn set_ignore_ewmh_fullscreen(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("ignore_ewmh_fullscreen", &value.to_string())
-}
-
-pub fn set_ignore_ewmh_struts(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("ignore_ewmh_struts", &value.to_string())
-}
-
-pub fn set_center_pseudo_tiled(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("center_pseudo_tiled", &value.to_string())
-}
-
-pub fn set_honor_size_hints(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("honor_size_hints", &value.to_string())
-}
-
-pub fn set_remove_disabled_monitors(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("remove_disabled_monitors", &value.to_string())
-}
-
-pub fn set_remove_unplugged_monitors(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("remove_unplugged_monitors", &value.to_string())
-}
-
-pub fn set_merge_overlapping_monitors(value: bool) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    conn.set_config_property("merge_overlapping_monitors", &value.to_string())
-}
-
-/// Sets `border_width` for nodes, that satisfy given conditions.
-pub fn set_border_width(
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    node_selector: Option<NodeSelector>,
-    border_width: i32,
-) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    todo!();
-}
-
-/// Sets `window_gap` for desktops, that satisfy given conditions.
-pub fn set_window_gap(
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    window_gap: i32,
-) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
-
-    todo!();
+/// Calls `$generate` macro with the table of global settings: name of the
+/// setting, type of its value and names of getter and setter. Blocking and
+/// async clients generate their methods from it.
+macro_rules! for_each_setting {
+    ($generate:ident) => {
+        $generate! {
+            normal_border_color: String => get_normal_border_color, set_normal_border_color;
+            active_border_color: String => get_active_border_color, set_active_border_color;
+            focused_border_color: String => get_focused_border_color, set_focused_border_color;
+            presel_feedback_color: String => get_presel_feedback_color, set_presel_feedback_color;
+            split_ratio: f32 => get_split_ratio, set_split_ratio;
+            status_prefix: String => get_status_prefix, set_status_prefix;
+            external_rules_command: String => get_external_rules_command, set_external_rules_command;
+            automatic_scheme: Scheme => get_automatic_scheme, set_automatic_scheme;
+            initial_polarity: Polarity => get_initial_polarity, set_initial_polarity;
+            directional_focus_tightness: Tightness => get_directional_focus_tightness, set_directional_focus_tightness;
+            removal_adjustment: bool => get_removal_adjustment, set_removal_adjustment;
+            presel_feedback: bool => get_presel_feedback, set_presel_feedback;
+            borderless_monocle: bool => get_borderless_monocle, set_borderless_monocle;
+            gapless_monocle: bool => get_gapless_monocle, set_gapless_monocle;
+            top_monocle_padding: i16 => get_top_monocle_padding, set_top_monocle_padding;
+            right_monocle_padding: i16 => get_right_monocle_padding, set_right_monocle_padding;
+            bottom_monocle_padding: i16 => get_bottom_monocle_padding, set_bottom_monocle_padding;
+            left_monocle_padding: i16 => get_left_monocle_padding, set_left_monocle_padding;
+            single_monocle: bool => get_single_monocle, set_single_monocle;
+            pointer_motion_interval: u16 => get_pointer_motion_interval, set_pointer_motion_interval;
+            pointer_modifier: PointerModifier => get_pointer_modifier, set_pointer_modifier;
+            pointer_action1: PointerAction => get_pointer_action1, set_pointer_action1;
+            pointer_action2: PointerAction => get_pointer_action2, set_pointer_action2;
+            pointer_action3: PointerAction => get_pointer_action3, set_pointer_action3;
+            click_to_focus: ClickToFocus => get_click_to_focus, set_click_to_focus;
+            swallow_first_click: bool => get_swallow_first_click, set_swallow_first_click;
+            focus_follows_pointer: bool => get_focus_follows_pointer, set_focus_follows_pointer;
+            pointer_follows_focus: bool => get_pointer_follows_focus, set_pointer_follows_focus;
+            pointer_follows_monitor: bool => get_pointer_follows_monitor, set_pointer_follows_monitor;
+            mapping_events_count: i32 => get_mapping_events_count, set_mapping_events_count;
+            ignore_ewmh_focus: bool => get_ignore_ewmh_focus, set_ignore_ewmh_focus;
+            ignore_ewmh_fullscreen: bool => get_ignore_ewmh_fullscreen, set_ignore_ewmh_fullscreen;
+            ignore_ewmh_struts: bool => get_ignore_ewmh_struts, set_ignore_ewmh_struts;
+            center_pseudo_tiled: bool => get_center_pseudo_tiled, set_center_pseudo_tiled;
+            honor_size_hints: bool => get_honor_size_hints, set_honor_size_hints;
+            remove_disabled_monitors: bool => get_remove_disabled_monitors, set_remove_disabled_monitors;
+            remove_unplugged_monitors: bool => get_remove_unplugged_monitors, set_remove_unplugged_monitors;
+            merge_overlapping_monitors: bool => get_merge_overlapping_monitors, set_merge_overlapping_monitors;
+        }
+    };
+}
+
+/// Calls `$generate` macro with the table of settings, that are set for
+/// selected monitors, desktops or nodes: name of setter, selectors, that it
+/// takes besides monitor and desktop ones, and name of the setting.
+macro_rules! for_each_selected_setting {
+    ($generate:ident) => {
+        $generate! {
+            /// Sets `border_width` for nodes, that satisfy given conditions.
+            set_border_width(node_selector) => border_width;
+            /// Sets `window_gap` for desktops, that satisfy given conditions.
+            set_window_gap() => window_gap;
+            /// Sets `top_padding` for desktops/monitors, that satisfy given
+            /// conditions.
+            top_padding() => top_padding;
+            /// Sets `right_padding` for desktops/monitors, that satisfy given
+            /// conditions.
+            right_padding() => right_padding;
+            /// Sets `bottom_padding` for desktops/monitors, that satisfy given
+            /// conditions.
+            bottom_padding() => bottom_padding;
+            /// Sets `left_padding` for desktops/monitors, that satisfy given
+            /// conditions.
+            left_padding() => left_padding;
+        }
+    };
 }
 
-/// Sets `top_padding` for desktops/monitors, that satisfy given conditions.
-pub fn top_padding(
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    top_padding: i32,
-) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
+#[cfg(feature = "tokio")]
+pub(crate) use {for_each_selected_setting, for_each_setting};
 
-    todo!();
+/// Parses value of the setting, as it is returned by bspwm.
+pub(crate) fn parse_setting<V>(value: String) -> Result<V, ReplyError>
+where
+    V: FromStr,
+    V::Err: Into<ParseError>,
+{
+    value.parse().map_err(|err: V::Err| From::from(err.into()))
 }
 
-/// Sets `right_padding` for deskrights/monitors, that satisfy given conditions.
-pub fn right_padding(
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    right_padding: i32,
-) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
+macro_rules! impl_settings {
+    ($($name:ident: $type:ty => $getter:ident, $setter:ident;)*) => {
+        impl<T: Transport> BspcClient<T> {
+            $(
+                pub fn $getter(&self) -> Result<$type, ReplyError> {
+                    let mut conn = self.connect()?;
 
-    todo!();
-}
+                    parse_setting(conn.get_config_property(stringify!($name))?)
+                }
 
-/// Sets `bottom_padding` for deskbottoms/monitors, that satisfy given conditions.
-pub fn bottom_padding(
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    bottom_padding: i32,
-) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
+                pub fn $setter(&self, value: $type) -> Result<(), ReplyError> {
+                    let mut conn = self.connect()?;
 
-    todo!();
-}
+                    conn.set_config_property(
+                        stringify!($name),
+                        &value.to_string(),
+                    )
+                }
+            )*
+        }
 
-/// Sets `left_padding` for desklefts/monitors, that satisfy given conditions.
-pub fn left_padding(
-    monitor_selector: Option<MonitorSelector>,
-    desktop_selector: Option<DesktopSelector>,
-    left_padding: i32,
-) -> Result<(), ReplyError> {
-    let mut conn = socket::connect()?;
+        $(
+            pub fn $getter() -> Result<$type, ReplyError> {
+                BspcClient::new().$getter()
+            }
+
+            pub fn $setter(value: $type) -> Result<(), ReplyError> {
+                BspcClient::new().$setter(value)
+            }
+        )*
+    };
+}
+
+macro_rules! impl_selected_settings {
+    ($(
+        $(#[$attr:meta])*
+        $setter:ident($($node_selector:ident)?) => $name:ident;
+    )*) => {
+        impl<T: Transport> BspcClient<T> {
+            $(
+                $(#[$attr])*
+                pub fn $setter(
+                    &self,
+                    monitor_selector: Option<MonitorSelector>,
+                    desktop_selector: Option<DesktopSelector>,
+                    $($node_selector: Option<NodeSelector>,)?
+                    $name: i32,
+                ) -> Result<(), ReplyError> {
+                    let mut conn = self.connect()?;
+                    let selectors = selectors::collect(
+                        monitor_selector,
+                        desktop_selector,
+                        None $(.or($node_selector))?,
+                    );
+
+                    conn.set_config_property(
+                        &format!("{}{}", selectors, stringify!($name)),
+                        &$name.to_string(),
+                    )
+                }
+            )*
+        }
 
-    todo!();
-}
+        $(
+            $(#[$attr])*
+            pub fn $setter(
+                monitor_selector: Option<MonitorSelector>,
+                desktop_selector: Option<DesktopSelector>,
+                $($node_selector: Option<NodeSelector>,)?
+                $name: i32,
+            ) -> Result<(), ReplyError> {
+                BspcClient::new().$setter(
+                    monitor_selector,
+                    desktop_selector,
+                    $($node_selector,)?
+                    $name,
+                )
+            }
+        )*
+    };
+}
+
+for_each_setting!(impl_settings);
+for_each_selected_setting!(impl_selected_settings);
@@ -1,41 +1,224 @@
+//! Low level communication with bspwm.
+//!
+//! Everything, that talks to bspwm, does so via [`Transport`], which opens a
+//! new connection for each request. By default it is a unix socket, but you
+//! can provide your own implementation, for example to use a different
+//! socket or a fake bspwm in tests.
+
 use std::env;
-use std::io::{self, BufRead, BufReader, Write};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
 use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::errors::{ReplyError, SocketError};
+
+/// Directory, where bspwm creates its sockets.
+const SOCKET_DIR: &str = "/tmp";
+
+/// Parsed value of the `DISPLAY` environment variable, i.e.
+/// `[host]:display[.screen]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XDisplay {
+    pub host: String,
+    pub display: u32,
+    pub screen: u32,
+}
+
+impl XDisplay {
+    /// Returns path to the socket, that bspwm running on this display
+    /// listens to.
+    pub fn socket_path(&self) -> PathBuf {
+        Path::new(SOCKET_DIR).join(format!(
+            "bspwm{}_{}_{}-socket",
+            self.host, self.display, self.screen
+        ))
+    }
+
+    /// Extracts display from the socket path, created by bspwm. Returns
+    /// `None` if path doesn't follow bspwm naming scheme.
+    pub fn from_socket_path(path: impl AsRef<Path>) -> Option<XDisplay> {
+        let file_name = path.as_ref().file_name()?.to_str()?;
+        let name = file_name.strip_prefix("bspwm")?.strip_suffix("-socket")?;
+
+        let (rest, screen) = name.rsplit_once('_')?;
+        let (host, display) = rest.rsplit_once('_')?;
+
+        Some(XDisplay {
+            host: host.to_string(),
+            display: display.parse().ok()?,
+            screen: screen.parse().ok()?,
+        })
+    }
+}
+
+impl FromStr for XDisplay {
+    type Err = SocketError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || SocketError::InvalidDisplay(input.to_string());
+
+        let (host, rest) = input.rsplit_once(':').ok_or_else(invalid)?;
+
+        // DECnet displays are separated with double colon
+        let host = host.strip_suffix(':').unwrap_or(host);
+
+        let (display, screen) = match rest.split_once('.') {
+            Some((display, screen)) => {
+                (display, screen.parse().map_err(|_| invalid())?)
+            }
+            None => (rest, 0),
+        };
+
+        Ok(XDisplay {
+            host: host.to_string(),
+            display: display.parse().map_err(|_| invalid())?,
+            screen,
+        })
+    }
+}
+
+/// Describes, where to look for the bspwm socket.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SocketLocator {
+    /// Locates socket the same way as `bspc` does it: path is taken from
+    /// `BSPWM_SOCKET` if it is set, otherwise it is derived from `DISPLAY`.
+    #[default]
+    Environment,
+
+    /// Socket with the given path.
+    Path(PathBuf),
+
+    /// Socket of bspwm, running on the given display.
+    Display(XDisplay),
+}
+
+impl SocketLocator {
+    /// Returns path to the socket or error, if it can't be determined.
+    pub fn locate(&self) -> Result<PathBuf, SocketError> {
+        match self {
+            SocketLocator::Environment => locate_from(
+                env::var_os("BSPWM_SOCKET").map(PathBuf::from),
+                env::var("DISPLAY").ok(),
+            ),
+            SocketLocator::Path(path) => Ok(path.clone()),
+            SocketLocator::Display(display) => Ok(display.socket_path()),
+        }
+    }
+
+    /// Returns paths of all bspwm sockets on this machine, i.e. all files
+    /// matching `/tmp/bspwm*-socket`, sorted by path.
+    pub fn list_sockets() -> Result<Vec<PathBuf>, SocketError> {
+        Self::list_sockets_in(SOCKET_DIR)
+    }
+
+    /// Same as [`SocketLocator::list_sockets`], but looks for sockets in the
+    /// given directory.
+    pub fn list_sockets_in(
+        dir: impl AsRef<Path>,
+    ) -> Result<Vec<PathBuf>, SocketError> {
+        let mut sockets = Vec::new();
+
+        for entry in fs::read_dir(dir).map_err(SocketError::SearchFailed)? {
+            let entry = entry.map_err(SocketError::SearchFailed)?;
+            let file_name = entry.file_name();
+
+            if let Some(name) = file_name.to_str() {
+                if name.starts_with("bspwm") && name.ends_with("-socket") {
+                    sockets.push(entry.path());
+                }
+            }
+        }
+
+        sockets.sort();
+
+        Ok(sockets)
+    }
+}
+
+fn locate_from(
+    bspwm_socket: Option<PathBuf>,
+    display: Option<String>,
+) -> Result<PathBuf, SocketError> {
+    if let Some(path) = bspwm_socket {
+        return Ok(path);
+    }
+
+    match display {
+        Some(display) => Ok(display.parse::<XDisplay>()?.socket_path()),
+        None => Err(SocketError::DisplayNotSet),
+    }
+}
 
-use crate::errors::ReplyError;
+/// Opens connections to bspwm.
+pub trait Transport {
+    /// Connection, that is returned by [`Transport::connect`]. It has to be
+    /// readable, because events are read from it after subscribing.
+    type Connection: BspcCommunication + Read + Send + 'static;
 
-fn locate_socket() -> String {
-    if let Ok(path) = env::var("BSPWM_SOCKET") {
-        path
-    } else if let Ok(display) = env::var("DISPLAY") {
-        let mut parsed = display.split(':');
-        let host = if let Some(x) = parsed.next() { x } else { "" };
+    /// Opens a new connection to bspwm.
+    fn connect(&self) -> Result<Self::Connection, ReplyError>;
+}
 
-        let mut parsed = parsed
-            .next()
-            .expect("Error occured while parsing DISPLAY variable")
-            .split('.');
+/// Transport, that connects to the unix socket of bspwm.
+///
+/// By default socket is located the same way as `bspc` does it: using
+/// `BSPWM_SOCKET` and `DISPLAY` environment variables. See [`SocketLocator`]
+/// for other options.
+#[derive(Debug, Clone, Default)]
+pub struct UnixSocketTransport {
+    locator: SocketLocator,
+}
 
-        let display_num = if let Some(x) = parsed.next() { x } else { "" };
+impl UnixSocketTransport {
+    /// Creates transport, that locates socket from environment variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        let screen_num = if let Some(x) = parsed.next() { x } else { "0" };
+    /// Creates transport, that always connects to the given socket.
+    pub fn with_path(socket_path: impl Into<PathBuf>) -> Self {
+        Self::with_locator(SocketLocator::Path(socket_path.into()))
+    }
 
-        format!("/tmp/bspwm{host}_{display_num}_{screen_num}-socket")
-    } else {
-        panic!("$DISPLAY variable is not set, can't proceed ...");
+    /// Creates transport, that finds socket using given locator.
+    pub fn with_locator(locator: SocketLocator) -> Self {
+        Self { locator }
+    }
+
+    /// Returns locator, used by this transport.
+    pub fn locator(&self) -> &SocketLocator {
+        &self.locator
     }
 }
 
-pub(crate) fn connect() -> io::Result<UnixStream> {
-    let socket_path = locate_socket();
-    let stream = UnixStream::connect(socket_path)?;
+impl Transport for UnixSocketTransport {
+    type Connection = UnixStream;
+
+    fn connect(&self) -> Result<UnixStream, ReplyError> {
+        let socket_path = self.locator.locate()?;
+        let stream = UnixStream::connect(socket_path)?;
 
-    Ok(stream)
+        Ok(stream)
+    }
 }
 
+/// Function, that shuts down connection, see
+/// [`BspcCommunication::shutdown_fn`].
+pub type ShutdownFn = Box<dyn Fn() -> io::Result<()> + Send + Sync>;
+
 pub trait BspcCommunication {
     fn send_message(&mut self, message: &str) -> io::Result<()>;
     fn receive_message(&mut self) -> Result<Vec<String>, ReplyError>;
+
+    /// Returns function, that shuts down connection from another thread,
+    /// unblocking reads in progress. Returns `None`, if connection can't be
+    /// shut down this way, which is the default.
+    fn shutdown_fn(&self) -> io::Result<Option<ShutdownFn>> {
+        Ok(None)
+    }
 }
 
 impl BspcCommunication for UnixStream {
@@ -47,30 +230,119 @@ impl BspcCommunication for UnixStream {
     }
 
     fn receive_message(&mut self) -> Result<Vec<String>, ReplyError> {
-        let buf_reader = BufReader::new(self);
-        let mut lines_iter = buf_reader.lines();
-        let mut result = Vec::new();
-
-        let first_line = match lines_iter.next() {
-            Some(x) => x?,
-            None => {
-                return Err(ReplyError::NoReply);
-            }
-        };
-        let first_line = first_line.as_bytes();
-
-        if first_line[0] == 7 {
-            let reply = String::from_utf8(first_line[1..].to_vec())?;
-            return Err(ReplyError::RequestFailed(reply));
-        } else {
-            let first_line = String::from_utf8(first_line.to_vec())?;
-            result.push(first_line);
+        let mut reply = Vec::new();
+        self.read_to_end(&mut reply)?;
+
+        process_reply(reply)
+    }
+
+    fn shutdown_fn(&self) -> io::Result<Option<ShutdownFn>> {
+        let stream = self.try_clone()?;
+
+        Ok(Some(Box::new(move || stream.shutdown(Shutdown::Both))))
+    }
+}
+
+/// Splits raw reply of bspwm into lines. Returns error if reply is empty or
+/// bspwm reported failure, i.e. reply starts with `\x07`.
+pub(crate) fn process_reply(reply: Vec<u8>) -> Result<Vec<String>, ReplyError> {
+    if reply.is_empty() {
+        return Err(ReplyError::NoReply);
+    }
+
+    if reply[0] == 7 {
+        let reply = String::from_utf8(reply[1..].to_vec())?;
+        let message = reply.lines().next().unwrap_or_default();
+
+        return Err(ReplyError::RequestFailed(message.to_string()));
+    }
+
+    let reply = String::from_utf8(reply)?;
+
+    Ok(reply.lines().map(ToString::to_string).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_display() {
+        let tests = [
+            (":0", "", 0, 0),
+            (":1.2", "", 1, 2),
+            ("localhost:10.0", "localhost", 10, 0),
+            ("host::3", "host", 3, 0),
+        ];
+
+        for (input, host, display, screen) in tests {
+            let parsed: XDisplay = input.parse().unwrap();
+
+            assert_eq!(
+                parsed,
+                XDisplay {
+                    host: host.to_string(),
+                    display,
+                    screen
+                }
+            );
         }
 
-        for line in lines_iter {
-            result.push(line?);
+        for input in ["", "0", ":", ":x", ":0.x", ":0."] {
+            assert!(matches!(
+                input.parse::<XDisplay>(),
+                Err(SocketError::InvalidDisplay(_))
+            ));
         }
+    }
+
+    #[test]
+    fn socket_path_from_display() {
+        let display: XDisplay = "localhost:1.2".parse().unwrap();
+        let path = display.socket_path();
+
+        assert_eq!(path, PathBuf::from("/tmp/bspwmlocalhost_1_2-socket"));
+        assert_eq!(XDisplay::from_socket_path(&path), Some(display));
+        assert_eq!(XDisplay::from_socket_path("/tmp/bspwm-socket"), None);
+    }
+
+    #[test]
+    fn locate_socket() {
+        assert_eq!(
+            locate_from(Some("/run/bspwm".into()), Some(":0".to_string()))
+                .unwrap(),
+            PathBuf::from("/run/bspwm")
+        );
+        assert_eq!(
+            locate_from(None, Some(":0".to_string())).unwrap(),
+            PathBuf::from("/tmp/bspwm_0_0-socket")
+        );
+        assert!(matches!(
+            locate_from(None, None),
+            Err(SocketError::DisplayNotSet)
+        ));
+        assert!(matches!(
+            locate_from(None, Some("garbage".to_string())),
+            Err(SocketError::InvalidDisplay(_))
+        ));
+    }
+
+    #[test]
+    fn list_sockets() {
+        let dir = env::temp_dir()
+            .join(format!("bspc-rs-list-sockets-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        for name in ["bspwm_1_0-socket", "bspwm_0_0-socket", "other-socket"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let sockets = SocketLocator::list_sockets_in(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
 
-        Ok(result)
+        assert_eq!(
+            sockets,
+            vec![dir.join("bspwm_0_0-socket"), dir.join("bspwm_1_0-socket")]
+        );
     }
 }
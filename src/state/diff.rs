@@ -0,0 +1,688 @@
+use std::collections::HashMap;
+
+use super::State;
+use crate::events::{
+    DesktopActivateInfo, DesktopAddInfo, DesktopEvent, DesktopFocusInfo,
+    DesktopLayoutInfo, DesktopRemoveInfo, DesktopRenameInfo,
+    DesktopTransferInfo, Event, MonitorAddInfo, MonitorEvent, MonitorFocusInfo,
+    MonitorGeometryInfo, MonitorRemoveInfo, MonitorRenameInfo,
+    NodeActivateInfo, NodeAddInfo, NodeEvent, NodeFlagInfo, NodeFocusInfo,
+    NodeLayerInfo, NodeRemoveInfo, NodeStateInfo, NodeTransferInfo,
+};
+use crate::properties::{Flag, Switch};
+use crate::tree::{Desktop, Monitor, Node};
+use crate::Id;
+
+/// Desktop, that changed its position among desktops of the monitor. It
+/// has no matching bspwm event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesktopReorderInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub old_index: usize,
+    pub new_index: usize,
+}
+
+/// Node, that changed its split ratio. It has no matching bspwm event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitRatioInfo {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+    pub old_ratio: f32,
+    pub new_ratio: f32,
+}
+
+/// Single change between two state dumps, that is returned by
+/// [`State::diff`]. Changes, that bspwm reports with events, are returned as
+/// these events, so they can be replayed through the same handlers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    MonitorEvent(MonitorEvent),
+    DesktopEvent(DesktopEvent),
+    NodeEvent(NodeEvent),
+    DesktopReorder(DesktopReorderInfo),
+    SplitRatio(SplitRatioInfo),
+}
+
+impl Change {
+    /// Returns event, that bspwm would send for this change, if there is
+    /// one.
+    pub fn into_event(self) -> Option<Event> {
+        match self {
+            Change::MonitorEvent(event) => Some(event.into()),
+            Change::DesktopEvent(event) => Some(event.into()),
+            Change::NodeEvent(event) => Some(event.into()),
+            Change::DesktopReorder(_) | Change::SplitRatio(_) => None,
+        }
+    }
+}
+
+/// Node together with monitor and desktop, that contain it.
+#[derive(Clone, Copy)]
+struct Loc<'a> {
+    monitor: &'a Monitor,
+    desktop: &'a Desktop,
+    node: &'a Node,
+}
+
+impl<'a> Loc<'a> {
+    fn all(state: &'a State) -> Vec<Loc<'a>> {
+        state
+            .desktops()
+            .flat_map(|(monitor, desktop)| {
+                desktop.nodes().map(move |node| Loc {
+                    monitor,
+                    desktop,
+                    node,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns focused monitor and its focused desktop.
+fn focus(state: &State) -> Option<(&Monitor, &Desktop)> {
+    let monitor = state.find_monitor(state.focused_monitor_id)?;
+    let desktop = monitor.find_desktop(monitor.focused_desktop_id)?;
+
+    Some((monitor, desktop))
+}
+
+fn flags(node: &Node) -> [(Flag, bool); 6] {
+    [
+        (Flag::Hidden, node.hidden),
+        (Flag::Sticky, node.sticky),
+        (Flag::Private, node.private),
+        (Flag::Locked, node.locked),
+        (Flag::Marked, node.marked),
+        (
+            Flag::Urgent,
+            node.client.as_ref().is_some_and(|client| client.urgent),
+        ),
+    ]
+}
+
+fn switch(value: bool) -> Switch {
+    if value {
+        Switch::On
+    } else {
+        Switch::Off
+    }
+}
+
+struct Diff<'a> {
+    old: &'a State,
+    new: &'a State,
+    old_nodes: HashMap<Id, Loc<'a>>,
+    new_nodes: Vec<Loc<'a>>,
+    changes: Vec<Change>,
+}
+
+impl<'a> Diff<'a> {
+    fn new(old: &'a State, new: &'a State) -> Self {
+        Self {
+            old,
+            new,
+            old_nodes: Loc::all(old)
+                .into_iter()
+                .map(|loc| (loc.node.id, loc))
+                .collect(),
+            new_nodes: Loc::all(new),
+            changes: Vec::new(),
+        }
+    }
+
+    fn monitor(&mut self, event: MonitorEvent) {
+        self.changes.push(Change::MonitorEvent(event));
+    }
+
+    fn desktop(&mut self, event: DesktopEvent) {
+        self.changes.push(Change::DesktopEvent(event));
+    }
+
+    fn node(&mut self, event: NodeEvent) {
+        self.changes.push(Change::NodeEvent(event));
+    }
+
+    fn monitors(&mut self) {
+        for monitor in &self.new.monitors {
+            let Some(old) = self.old.find_monitor(monitor.id) else {
+                self.monitor(MonitorEvent::MonitorAdd(MonitorAddInfo {
+                    monitor_id: monitor.id,
+                    monitor_name: monitor.name.clone(),
+                    monitor_geometry: monitor.rectangle.clone(),
+                }));
+                continue;
+            };
+
+            if old.name != monitor.name {
+                self.monitor(MonitorEvent::MonitorRename(MonitorRenameInfo {
+                    monitor_id: monitor.id,
+                    old_name: old.name.clone(),
+                    new_name: monitor.name.clone(),
+                }));
+            }
+
+            if old.rectangle != monitor.rectangle {
+                self.monitor(MonitorEvent::MonitorGeometry(
+                    MonitorGeometryInfo {
+                        monitor_id: monitor.id,
+                        monitor_geometry: monitor.rectangle.clone(),
+                    },
+                ));
+            }
+        }
+    }
+
+    fn desktops(&mut self) {
+        let (old, new) = (self.old, self.new);
+
+        for (monitor, desktop) in new.desktops() {
+            let Some((old_monitor, old)) =
+                old.desktops().find(|(_, old)| old.id == desktop.id)
+            else {
+                self.desktop(DesktopEvent::DesktopAdd(DesktopAddInfo {
+                    monitor_id: monitor.id,
+                    desktop_id: desktop.id,
+                    desktop_name: desktop.name.clone(),
+                }));
+                continue;
+            };
+
+            if old.name != desktop.name {
+                self.desktop(DesktopEvent::DesktopRename(DesktopRenameInfo {
+                    monitor_id: monitor.id,
+                    desktop_id: desktop.id,
+                    old_name: old.name.clone(),
+                    new_name: desktop.name.clone(),
+                }));
+            }
+
+            if old_monitor.id != monitor.id {
+                self.desktop(DesktopEvent::DesktopTransfer(
+                    DesktopTransferInfo {
+                        src_monitor_id: old_monitor.id,
+                        src_desktop_id: desktop.id,
+                        dst_monitor_id: monitor.id,
+                    },
+                ));
+            }
+
+            if old.layout != desktop.layout {
+                self.desktop(DesktopEvent::DesktopLayout(DesktopLayoutInfo {
+                    monitor_id: monitor.id,
+                    desktop_id: desktop.id,
+                    layout: desktop.layout.clone(),
+                }));
+            }
+        }
+
+        for monitor in &new.monitors {
+            if let Some(old) = old.find_monitor(monitor.id) {
+                self.reorder(old, monitor);
+            }
+        }
+    }
+
+    /// Compares order of desktops, that stayed on the monitor, ignoring
+    /// added and removed ones.
+    fn reorder(&mut self, old: &Monitor, new: &Monitor) {
+        let common = |monitor: &Monitor, other: &Monitor| -> Vec<Id> {
+            monitor
+                .desktops
+                .iter()
+                .filter(|desktop| other.find_desktop(desktop.id).is_some())
+                .map(|desktop| desktop.id)
+                .collect()
+        };
+
+        let old_order = common(old, new);
+        let new_order = common(new, old);
+        let index = |monitor: &Monitor, id| {
+            monitor.desktops.iter().position(|desktop| desktop.id == id)
+        };
+
+        for (old_id, &id) in old_order.iter().zip(&new_order) {
+            if *old_id == id {
+                continue;
+            }
+
+            self.changes
+                .push(Change::DesktopReorder(DesktopReorderInfo {
+                    monitor_id: new.id,
+                    desktop_id: id,
+                    old_index: index(old, id).unwrap_or_default(),
+                    new_index: index(new, id).unwrap_or_default(),
+                }));
+        }
+    }
+
+    /// Leaves are added, removed and moved, internal nodes just follow them.
+    fn leaves(&mut self) {
+        for loc in self.new_nodes.clone() {
+            if !loc.node.is_leaf() {
+                continue;
+            }
+
+            let ip_id = loc
+                .desktop
+                .sibling(loc.node.id)
+                .map_or(0, |sibling| sibling.id);
+
+            match self.old_nodes.get(&loc.node.id).copied() {
+                None => self.node(NodeEvent::NodeAdd(NodeAddInfo {
+                    monitor_id: loc.monitor.id,
+                    desktop_id: loc.desktop.id,
+                    ip_id,
+                    node_id: loc.node.id,
+                })),
+
+                Some(old) if old.desktop.id != loc.desktop.id => {
+                    self.node(NodeEvent::NodeTransfer(NodeTransferInfo {
+                        src_monitor_id: old.monitor.id,
+                        src_desktop_id: old.desktop.id,
+                        src_node_id: loc.node.id,
+                        dst_monitor_id: loc.monitor.id,
+                        dst_desktop_id: loc.desktop.id,
+                        dst_node_id: ip_id,
+                    }))
+                }
+
+                Some(_) => {}
+            }
+        }
+
+        for old in Loc::all(self.old) {
+            if old.node.is_leaf() && self.new.find_node(old.node.id).is_none() {
+                self.node(NodeEvent::NodeRemove(NodeRemoveInfo {
+                    monitor_id: old.monitor.id,
+                    desktop_id: old.desktop.id,
+                    node_id: old.node.id,
+                }));
+            }
+        }
+    }
+
+    fn properties(&mut self) {
+        for loc in self.new_nodes.clone() {
+            let Some(old) = self.old_nodes.get(&loc.node.id).copied() else {
+                continue;
+            };
+
+            let (monitor_id, desktop_id, node_id) =
+                (loc.monitor.id, loc.desktop.id, loc.node.id);
+
+            if let (Some(old), Some(new)) = (&old.node.client, &loc.node.client)
+            {
+                if old.state != new.state {
+                    for (state, switch) in [
+                        (old.state.clone(), Switch::Off),
+                        (new.state.clone(), Switch::On),
+                    ] {
+                        self.node(NodeEvent::NodeState(NodeStateInfo {
+                            monitor_id,
+                            desktop_id,
+                            node_id,
+                            state,
+                            switch,
+                        }));
+                    }
+                }
+
+                if old.layer != new.layer {
+                    self.node(NodeEvent::NodeLayer(NodeLayerInfo {
+                        monitor_id,
+                        desktop_id,
+                        node_id,
+                        layer: new.layer.clone(),
+                    }));
+                }
+            }
+
+            for ((flag, old), (_, new)) in
+                flags(old.node).into_iter().zip(flags(loc.node))
+            {
+                if old != new {
+                    self.node(NodeEvent::NodeFlag(NodeFlagInfo {
+                        monitor_id,
+                        desktop_id,
+                        node_id,
+                        flag,
+                        switch: switch(new),
+                    }));
+                }
+            }
+
+            if !old.node.is_leaf()
+                && !loc.node.is_leaf()
+                && old.node.split_ratio != loc.node.split_ratio
+            {
+                self.changes.push(Change::SplitRatio(SplitRatioInfo {
+                    monitor_id,
+                    desktop_id,
+                    node_id,
+                    old_ratio: old.node.split_ratio,
+                    new_ratio: loc.node.split_ratio,
+                }));
+            }
+        }
+    }
+
+    fn removals(&mut self) {
+        let (old, new) = (self.old, self.new);
+
+        for (monitor, desktop) in old.desktops() {
+            if new.find_desktop(desktop.id).is_none() {
+                self.desktop(DesktopEvent::DesktopRemove(DesktopRemoveInfo {
+                    monitor_id: monitor.id,
+                    desktop_id: desktop.id,
+                }));
+            }
+        }
+
+        for monitor in &old.monitors {
+            if new.find_monitor(monitor.id).is_none() {
+                self.monitor(MonitorEvent::MonitorRemove(MonitorRemoveInfo {
+                    monitor_id: monitor.id,
+                }));
+            }
+        }
+    }
+
+    fn focus(&mut self) {
+        let (old, new) = (self.old, self.new);
+        let old_focus =
+            focus(old).map(|(m, d)| (m.id, d.id, d.focused_node_id));
+        let Some((monitor, desktop)) = focus(new) else {
+            return;
+        };
+
+        if old.focused_monitor_id != new.focused_monitor_id {
+            self.monitor(MonitorEvent::MonitorFocus(MonitorFocusInfo {
+                monitor_id: monitor.id,
+            }));
+        }
+
+        if old_focus.map(|(_, d, _)| d) != Some(desktop.id) {
+            self.desktop(DesktopEvent::DesktopFocus(DesktopFocusInfo {
+                monitor_id: monitor.id,
+                desktop_id: desktop.id,
+            }));
+        }
+
+        for other in &new.monitors {
+            let activated = old.find_monitor(other.id).is_some_and(|old| {
+                old.focused_desktop_id != other.focused_desktop_id
+            });
+
+            if other.id != monitor.id && activated {
+                self.desktop(DesktopEvent::DesktopActivate(
+                    DesktopActivateInfo {
+                        monitor_id: other.id,
+                        desktop_id: other.focused_desktop_id,
+                    },
+                ));
+            }
+        }
+
+        let node_id = desktop.focused_node_id;
+
+        if node_id != 0 && old_focus.map(|(_, _, n)| n) != Some(node_id) {
+            self.node(NodeEvent::NodeFocus(NodeFocusInfo {
+                monitor_id: monitor.id,
+                desktop_id: desktop.id,
+                node_id,
+            }));
+        }
+
+        for (other_monitor, other) in new.desktops() {
+            let activated = old.find_desktop(other.id).is_some_and(|old| {
+                old.focused_node_id != other.focused_node_id
+            });
+
+            if other.id != desktop.id && other.focused_node_id != 0 && activated
+            {
+                self.node(NodeEvent::NodeActivate(NodeActivateInfo {
+                    monitor_id: other_monitor.id,
+                    desktop_id: other.id,
+                    node_id: other.focused_node_id,
+                }));
+            }
+        }
+    }
+}
+
+impl State {
+    /// Returns changes, that turn `old` state into `new` one, e.g. to find
+    /// out, what was missed while subscriber was disconnected.
+    ///
+    /// Changes go in the order, they could be replayed in: additions,
+    /// renames and moves of monitors, desktops and leaves, then changes of
+    /// nodes, then removals and focus last. Internal nodes are not reported
+    /// as added, removed or moved, they follow their leaves.
+    ///
+    /// ```no_run
+    /// use bspc_rs::state::{self, State};
+    ///
+    /// let old = state::get_current_state().unwrap();
+    /// // ...
+    /// let new = state::get_current_state().unwrap();
+    ///
+    /// for event in State::diff(&old, &new)
+    ///     .into_iter()
+    ///     .filter_map(|change| change.into_event())
+    /// {
+    ///     println!("{}", event);
+    /// }
+    /// ```
+    pub fn diff(old: &State, new: &State) -> Vec<Change> {
+        let mut diff = Diff::new(old, new);
+
+        diff.monitors();
+        diff.desktops();
+        diff.leaves();
+        diff.properties();
+        diff.removals();
+        diff.focus();
+
+        diff.changes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::properties::{Layer, State as NodeState};
+    use crate::testing::STATE_DUMP;
+
+    fn state() -> State {
+        serde_json::from_str(STATE_DUMP).unwrap()
+    }
+
+    fn change(event: impl Into<Event>) -> Change {
+        match event.into() {
+            Event::MonitorEvent(event) => Change::MonitorEvent(event),
+            Event::DesktopEvent(event) => Change::DesktopEvent(event),
+            Event::NodeEvent(event) => Change::NodeEvent(event),
+            event => panic!("Unexpected event: {event}"),
+        }
+    }
+
+    #[test]
+    fn diff_same_state() {
+        let state = state();
+        assert_eq!(State::diff(&state, &state), []);
+    }
+
+    #[test]
+    fn diff_states() {
+        let old = state();
+        let mut new = old.clone();
+
+        new.monitors[1].rectangle.width = 1280;
+
+        // Move window from "2 web" to "3" and open new one in "2 web"
+        let edp = &mut new.monitors[0];
+        let mut window = edp.desktops[0].root.as_ref().unwrap().clone();
+        window.id = 0x01000002;
+        window.first_child = None;
+        window.second_child = None;
+
+        edp.desktops[2].root = edp.desktops[1].root.take();
+        edp.desktops[2].focused_node_id = 0x00C00002;
+        edp.desktops[2].name = "three".to_string();
+        edp.desktops[1].root = Some(window);
+        edp.desktops[1].focused_node_id = 0x01000002;
+        edp.desktops.swap(0, 1);
+
+        let desktop = &mut edp.desktops[1];
+        desktop.focused_node_id = 0x00800002;
+
+        let root = desktop.root.as_mut().unwrap();
+        root.split_ratio = 0.25;
+
+        let firefox = root.second_child.as_mut().unwrap();
+        let firefox = firefox.first_child.as_mut().unwrap();
+        let client = firefox.client.as_mut().unwrap();
+        firefox.marked = true;
+        client.state = NodeState::Floating;
+        client.layer = Layer::Above;
+
+        // Close window on HDMI1 and add empty desktop there
+        let hdmi = &mut new.monitors[1];
+        hdmi.desktops[0].root = None;
+        hdmi.desktops[0].focused_node_id = 0;
+
+        let mut desktop = hdmi.desktops[0].clone();
+        desktop.id = 0x0020000C;
+        desktop.name = "5".to_string();
+        hdmi.desktops.push(desktop);
+
+        let node = |node_id| (0x00200002, 0x00200007, node_id);
+        let (monitor_id, desktop_id, node_id) = node(0x00800002);
+
+        assert_eq!(
+            State::diff(&old, &new),
+            [
+                change(MonitorEvent::MonitorGeometry(MonitorGeometryInfo {
+                    monitor_id: 0x0020000A,
+                    monitor_geometry: new.monitors[1].rectangle.clone(),
+                })),
+                change(DesktopEvent::DesktopRename(DesktopRenameInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200009,
+                    old_name: "3".to_string(),
+                    new_name: "three".to_string(),
+                })),
+                change(DesktopEvent::DesktopAdd(DesktopAddInfo {
+                    monitor_id: 0x0020000A,
+                    desktop_id: 0x0020000C,
+                    desktop_name: "5".to_string(),
+                })),
+                Change::DesktopReorder(DesktopReorderInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200008,
+                    old_index: 1,
+                    new_index: 0,
+                }),
+                Change::DesktopReorder(DesktopReorderInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200007,
+                    old_index: 0,
+                    new_index: 1,
+                }),
+                change(NodeEvent::NodeAdd(NodeAddInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200008,
+                    ip_id: 0,
+                    node_id: 0x01000002,
+                })),
+                change(NodeEvent::NodeTransfer(NodeTransferInfo {
+                    src_monitor_id: 0x00200002,
+                    src_desktop_id: 0x00200008,
+                    src_node_id: 0x00C00002,
+                    dst_monitor_id: 0x00200002,
+                    dst_desktop_id: 0x00200009,
+                    dst_node_id: 0,
+                })),
+                change(NodeEvent::NodeRemove(NodeRemoveInfo {
+                    monitor_id: 0x0020000A,
+                    desktop_id: 0x0020000B,
+                    node_id: 0x00E00002,
+                })),
+                Change::SplitRatio(SplitRatioInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200007,
+                    node_id: 0x00600001,
+                    old_ratio: 0.5,
+                    new_ratio: 0.25,
+                }),
+                change(NodeEvent::NodeState(NodeStateInfo {
+                    monitor_id,
+                    desktop_id,
+                    node_id,
+                    state: NodeState::Tiled,
+                    switch: Switch::Off,
+                })),
+                change(NodeEvent::NodeState(NodeStateInfo {
+                    monitor_id,
+                    desktop_id,
+                    node_id,
+                    state: NodeState::Floating,
+                    switch: Switch::On,
+                })),
+                change(NodeEvent::NodeLayer(NodeLayerInfo {
+                    monitor_id,
+                    desktop_id,
+                    node_id,
+                    layer: Layer::Above,
+                })),
+                change(NodeEvent::NodeFlag(NodeFlagInfo {
+                    monitor_id,
+                    desktop_id,
+                    node_id,
+                    flag: Flag::Marked,
+                    switch: Switch::On,
+                })),
+                change(NodeEvent::NodeFocus(NodeFocusInfo {
+                    monitor_id,
+                    desktop_id,
+                    node_id,
+                })),
+                change(NodeEvent::NodeActivate(NodeActivateInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200008,
+                    node_id: 0x01000002,
+                })),
+                change(NodeEvent::NodeActivate(NodeActivateInfo {
+                    monitor_id: 0x00200002,
+                    desktop_id: 0x00200009,
+                    node_id: 0x00C00002,
+                })),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_focus() {
+        let old = state();
+        let mut new = old.clone();
+        new.focused_monitor_id = 0x0020000A;
+
+        let events: Vec<_> = State::diff(&old, &new)
+            .into_iter()
+            .filter_map(Change::into_event)
+            .map(|event| event.to_string())
+            .collect();
+
+        assert_eq!(
+            events,
+            [
+                "monitor_focus 0x0020000A",
+                "desktop_focus 0x0020000A 0x0020000B",
+                "node_focus 0x0020000A 0x0020000B 0x00E00002",
+            ]
+        );
+    }
+}
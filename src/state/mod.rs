@@ -0,0 +1,146 @@
+//! This module is dedicated for the state dump of bspwm. It can be received by
+//! running `bspc wm -d` your shell.
+
+use crate::client::BspcClient;
+use crate::errors::ReplyError;
+use crate::events::ReportInfo;
+use crate::socket::{BspcCommunication, Transport};
+use crate::tree::{Desktop, Monitor, Node};
+use crate::Id;
+use serde::{Deserialize, Serialize};
+
+mod diff;
+
+pub use diff::{Change, DesktopReorderInfo, SplitRatioInfo};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusHistoryEntry {
+    pub monitor_id: Id,
+    pub desktop_id: Id,
+    pub node_id: Id,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct State {
+    pub focused_monitor_id: Id,
+    pub primary_monitor_id: Option<Id>,
+    pub clients_count: u32,
+    pub monitors: Vec<Monitor>,
+    pub focus_history: Vec<FocusHistoryEntry>,
+    pub stacking_list: Vec<Id>,
+}
+
+impl State {
+    /// Returns monitor with the given id.
+    pub fn find_monitor(&self, id: Id) -> Option<&Monitor> {
+        self.monitors.iter().find(|monitor| monitor.id == id)
+    }
+
+    /// Returns desktop with the given id.
+    pub fn find_desktop(&self, id: Id) -> Option<&Desktop> {
+        self.monitors
+            .iter()
+            .find_map(|monitor| monitor.find_desktop(id))
+    }
+
+    /// Returns node with the given id.
+    pub fn find_node(&self, id: Id) -> Option<&Node> {
+        self.monitors
+            .iter()
+            .find_map(|monitor| monitor.find_node(id))
+    }
+
+    /// Returns all desktops together with monitors, that contain them.
+    pub fn desktops(&self) -> impl Iterator<Item = (&Monitor, &Desktop)> {
+        self.monitors.iter().flat_map(|monitor| {
+            monitor
+                .desktops
+                .iter()
+                .map(move |desktop| (monitor, desktop))
+        })
+    }
+
+    /// Returns all nodes, that hold windows, together with monitors and
+    /// desktops, that contain them.
+    pub fn windows(&self) -> impl Iterator<Item = (&Monitor, &Desktop, &Node)> {
+        self.desktops().flat_map(|(monitor, desktop)| {
+            desktop.windows().map(move |node| (monitor, desktop, node))
+        })
+    }
+}
+
+impl<T: Transport> BspcClient<T> {
+    /// Returns a dump of the current bspwm state <br>
+    /// Contains all monitors with their desktops (and window respectively)
+    pub fn get_current_state(&self) -> Result<State, ReplyError> {
+        let mut conn = self.connect()?;
+        conn.send_message("wm\x00-d\x00")?;
+
+        let reply = conn.receive_message()?;
+        let state: State = serde_json::from_str(&reply[0])?;
+        Ok(state)
+    }
+
+    /// Returns current status report, the same one, that is sent to
+    /// subscribers of `report` events. Useful for drawing status bar before
+    /// any event has arrived.
+    pub fn get_status(&self) -> Result<ReportInfo, ReplyError> {
+        let status_prefix = self.get_status_prefix()?;
+
+        let mut conn = self.connect()?;
+        conn.send_message("wm\x00--get-status\x00")?;
+
+        let reply = conn.receive_message()?;
+        let report = ReportInfo::parse_with_prefix(&reply[0], &status_prefix)?;
+        Ok(report)
+    }
+}
+
+/// Returns a dump of the current bspwm state <br>
+/// Contains all monitors with their desktops (and window respectively)
+pub fn get_current_state() -> Result<State, ReplyError> {
+    BspcClient::new().get_current_state()
+}
+
+/// Returns current status report, the same one, that is sent to subscribers
+/// of `report` events.
+pub fn get_status() -> Result<ReportInfo, ReplyError> {
+    BspcClient::new().get_status()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::{FakeBspwm, Reply, STATE_DUMP};
+
+    #[test]
+    fn parse_state_dump() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.set_state(STATE_DUMP);
+
+        let state = bspwm.client().get_current_state().unwrap();
+
+        assert_eq!(state.focused_monitor_id, 0x00200002);
+        assert_eq!(state.monitors.len(), 2);
+        assert_eq!(state.focus_history.len(), 5);
+        assert_eq!(bspwm.requests(), vec![vec!["wm", "-d"]]);
+    }
+
+    #[test]
+    fn get_status() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.set_config("status_prefix", "MyBar");
+        bspwm.on_request(
+            &["wm", "--get-status"],
+            Reply::lines(["MyBarMeDP1:O1:o2 web:f3:LT:TT:G"]),
+        );
+
+        let report = bspwm.client().get_status().unwrap();
+        let monitor = &report.monitors[0];
+
+        assert_eq!(monitor.name, "eDP1");
+        assert_eq!(monitor.desktops.len(), 3);
+        assert_eq!(monitor.desktops[1].name, "2 web");
+    }
+}
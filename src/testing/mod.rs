@@ -0,0 +1,610 @@
+//! This module contains a fake bspwm, that can be used for testing code,
+//! built on top of this crate, without running real window manager.
+//!
+//! [`FakeBspwm`] binds a unix socket and speaks the same protocol as bspwm
+//! does: requests are NUL-separated arguments and replies are newline
+//! separated lines, where failures are prefixed with `\x07`. Replies are
+//! scripted in advance and every request is recorded, so that tests can
+//! assert on them.
+//!
+//! This module is available with `testing` feature.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use bspc_rs::testing::{FakeBspwm, Reply};
+//!
+//! let bspwm = FakeBspwm::start().unwrap();
+//! bspwm.on_request(&["query", "--nodes"], Reply::lines(["0x00400002"]));
+//!
+//! let client = bspwm.client();
+//! let nodes = client.query_nodes(None, None, None, None).unwrap();
+//!
+//! assert_eq!(nodes, vec![0x00400002]);
+//! assert_eq!(bspwm.requests(), vec![vec!["query", "--nodes"]]);
+//! ```
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::client::BspcClient;
+use crate::socket::UnixSocketTransport;
+
+/// State dump of bspwm with two monitors, four desktops and five windows,
+/// as returned by `bspc wm -d`.
+pub const STATE_DUMP: &str = include_str!("state.json");
+
+/// Event lines, as they are sent by bspwm to subscribers, one per line. It
+/// covers every kind of event with every possible value of enumerated
+/// fields, like states, flags and layers.
+pub const EVENTS: &str = include_str!("events.txt");
+
+/// Replies of `bspc query` for node, desktop and monitor selectors, when bspwm
+/// has [`STATE_DUMP`] loaded. Every line has domain flag, selector and ids,
+/// that are separated with tabs.
+pub const QUERIES: &str = include_str!("queries.txt");
+
+/// Size of the buffer, that bspwm uses for reading requests.
+const BUFFER_SIZE: usize = 8192;
+
+/// Scripted reply to the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    /// Sends given lines and closes connection.
+    Lines(Vec<String>),
+
+    /// Sends given message prefixed with `\x07`, which is how bspwm reports
+    /// failures, and closes connection.
+    Failure(String),
+
+    /// Closes connection without sending anything.
+    Empty,
+}
+
+impl Reply {
+    /// Creates [`Reply::Lines`] from anything, that can be turned into
+    /// strings.
+    pub fn lines<I, S>(lines: I) -> Reply
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Reply::Lines(lines.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates [`Reply::Failure`] with given message.
+    pub fn failure(message: impl Into<String>) -> Reply {
+        Reply::Failure(message.into())
+    }
+
+    fn write_to(&self, stream: &mut UnixStream) -> io::Result<()> {
+        match self {
+            Reply::Lines(lines) => {
+                for line in lines {
+                    writeln!(stream, "{}", line)?;
+                }
+            }
+            Reply::Failure(message) => write!(stream, "\x07{}", message)?,
+            Reply::Empty => {}
+        }
+
+        stream.flush()
+    }
+}
+
+/// Stream, that events are written to: either connection of the subscriber
+/// or FIFO, if it subscribed with `--fifo`.
+#[derive(Debug)]
+enum SubscriberStream {
+    Socket(UnixStream),
+    Fifo(File),
+}
+
+impl Write for SubscriberStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            SubscriberStream::Socket(stream) => stream.write(buf),
+            SubscriberStream::Fifo(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            SubscriberStream::Socket(stream) => stream.flush(),
+            SubscriberStream::Fifo(file) => file.flush(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FakeSubscriber {
+    stream: SubscriberStream,
+    subscriptions: Vec<String>,
+    remaining: Option<u32>,
+}
+
+impl FakeSubscriber {
+    fn is_subscribed_to(&self, event: &str) -> bool {
+        let kind = event.split('_').next().unwrap_or_default();
+
+        self.subscriptions
+            .iter()
+            .any(|sub| sub == "all" || sub == event || sub == kind)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    rules: Vec<(Vec<String>, Reply)>,
+    config: HashMap<String, String>,
+    state: Option<String>,
+    requests: Vec<Vec<String>>,
+    subscribers: Vec<FakeSubscriber>,
+    fifos: Vec<PathBuf>,
+}
+
+impl Inner {
+    fn reply_to(&mut self, args: &[String]) -> Reply {
+        if let Some((_, reply)) =
+            self.rules.iter().rev().find(|(rule, _)| rule == args)
+        {
+            return reply.clone();
+        }
+
+        let args: Vec<_> = args.iter().map(String::as_str).collect();
+
+        match args[..] {
+            ["wm", "-d"] | ["wm", "--dump-state"] => match &self.state {
+                Some(state) => Reply::lines([state.as_str()]),
+                None => Reply::failure("wm: No state was set.\n"),
+            },
+
+            ["config", name] => match self.config.get(name) {
+                Some(value) => Reply::lines([value.as_str()]),
+                None => Reply::failure(format!(
+                    "config: Unknown setting: '{}'.\n",
+                    name
+                )),
+            },
+
+            ["config", name, value] => {
+                self.config.insert(name.to_string(), value.to_string());
+                Reply::Empty
+            }
+
+            _ => Reply::failure(format!(
+                "Unknown request: '{}'.\n",
+                args.join(" ")
+            )),
+        }
+    }
+}
+
+/// Fake bspwm, listening on a unix socket in temporary directory.
+///
+/// Server is stopped and socket is removed, when it is dropped.
+#[derive(Debug)]
+pub struct FakeBspwm {
+    socket_path: PathBuf,
+    inner: Arc<Mutex<Inner>>,
+    stopped: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl FakeBspwm {
+    /// Binds a new socket and starts serving requests in the background
+    /// thread.
+    pub fn start() -> io::Result<FakeBspwm> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let socket_path = env::temp_dir().join(format!(
+            "bspc-rs-fake-{}-{}-socket",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+
+        Self::start_at(socket_path)
+    }
+
+    /// Same as [`FakeBspwm::start`], but binds socket at the given path.
+    pub fn start_at(socket_path: impl Into<PathBuf>) -> io::Result<FakeBspwm> {
+        let socket_path = socket_path.into();
+        let _ = fs::remove_file(&socket_path);
+
+        let listener = UnixListener::bind(&socket_path)?;
+        let mut inner = Inner::default();
+
+        // Status prefix and state always exist in bspwm, they are needed to
+        // parse reports and renames
+        inner
+            .config
+            .insert("status_prefix".to_string(), "W".to_string());
+        inner.state = Some(STATE_DUMP.to_string());
+
+        let inner = Arc::new(Mutex::new(inner));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let inner = Arc::clone(&inner);
+            let stopped = Arc::clone(&stopped);
+
+            thread::spawn(move || serve(listener, inner, stopped))
+        };
+
+        Ok(FakeBspwm {
+            socket_path,
+            inner,
+            stopped,
+            handle: Some(handle),
+        })
+    }
+
+    /// Returns path to the socket, this server listens to.
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Returns client, that is connected to this server.
+    pub fn client(&self) -> BspcClient {
+        BspcClient::with_transport(UnixSocketTransport::with_path(
+            &self.socket_path,
+        ))
+    }
+
+    /// Returns async client, that is connected to this server.
+    #[cfg(feature = "tokio")]
+    pub fn async_client(&self) -> crate::asynchronous::BspcClient {
+        crate::asynchronous::BspcClient::with_transport(
+            UnixSocketTransport::with_path(&self.socket_path),
+        )
+    }
+
+    /// Replies with `reply` to every request with exactly these arguments.
+    /// Rules, added later, take precedence.
+    pub fn on_request(&self, args: &[&str], reply: Reply) {
+        let args = args.iter().map(ToString::to_string).collect();
+        self.lock().rules.push((args, reply));
+    }
+
+    /// Sets the state, that is returned for `wm -d` request. It is
+    /// [`STATE_DUMP`] by default.
+    pub fn set_state(&self, state: impl Into<String>) {
+        self.lock().state = Some(state.into());
+    }
+
+    /// Sets value of the setting, that is returned for `config <name>`
+    /// request. Values can also be changed by clients with
+    /// `config <name> <value>` requests.
+    pub fn set_config(&self, name: &str, value: &str) {
+        self.lock()
+            .config
+            .insert(name.to_string(), value.to_string());
+    }
+
+    /// Returns current value of the setting.
+    pub fn config(&self, name: &str) -> Option<String> {
+        self.lock().config.get(name).cloned()
+    }
+
+    /// Returns arguments of all requests, received so far, in the order of
+    /// arrival.
+    pub fn requests(&self) -> Vec<Vec<String>> {
+        self.lock().requests.clone()
+    }
+
+    /// Returns number of currently connected subscribers.
+    pub fn subscribers_count(&self) -> usize {
+        self.lock().subscribers.len()
+    }
+
+    /// Blocks until at least `count` subscribers are connected. Returns
+    /// `false` if it didn't happen within `timeout`.
+    pub fn wait_for_subscribers(
+        &self,
+        count: usize,
+        timeout: Duration,
+    ) -> bool {
+        let start = Instant::now();
+
+        while start.elapsed() < timeout {
+            if self.subscribers_count() >= count {
+                return true;
+            }
+
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        false
+    }
+
+    /// Sends event line to every subscriber, that is subscribed to it.
+    /// Lines, that don't look like named events, are treated as reports.
+    pub fn emit(&self, line: &str) {
+        let event = event_name(line);
+        let mut inner = self.lock();
+
+        inner.subscribers.retain_mut(|sub| {
+            if !sub.is_subscribed_to(event) {
+                return true;
+            }
+
+            if writeln!(sub.stream, "{}", line).is_err() {
+                return false;
+            }
+
+            match &mut sub.remaining {
+                Some(remaining) => {
+                    *remaining = remaining.saturating_sub(1);
+                    *remaining > 0
+                }
+                None => true,
+            }
+        });
+    }
+
+    /// Closes connections of all subscribers, like bspwm does when it
+    /// exits.
+    pub fn close_subscribers(&self) {
+        self.lock().subscribers.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap()
+    }
+}
+
+impl Drop for FakeBspwm {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+
+        // Wake up the thread, blocked in `accept`
+        let _ = UnixStream::connect(&self.socket_path);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+
+        self.close_subscribers();
+        let _ = fs::remove_file(&self.socket_path);
+
+        for fifo in &self.lock().fifos {
+            let _ = fs::remove_file(fifo);
+        }
+    }
+}
+
+fn event_name(line: &str) -> &str {
+    let name = line.split(' ').next().unwrap_or_default();
+
+    let is_event = ["monitor_", "desktop_", "node_", "pointer_"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix));
+
+    if is_event {
+        name
+    } else {
+        "report"
+    }
+}
+
+/// Creates FIFO in temporary directory with `mkfifo` command.
+fn make_fifo() -> io::Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let fifo_path = env::temp_dir().join(format!(
+        "bspc-rs-fake-fifo-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+
+    let status = Command::new("mkfifo").arg(&fifo_path).status()?;
+
+    if !status.success() {
+        return Err(io::Error::other(format!("mkfifo failed: {}", status)));
+    }
+
+    Ok(fifo_path)
+}
+
+fn serve(
+    listener: UnixListener,
+    inner: Arc<Mutex<Inner>>,
+    stopped: Arc<AtomicBool>,
+) {
+    for stream in listener.incoming() {
+        if stopped.load(Ordering::SeqCst) {
+            break;
+        }
+
+        if let Ok(stream) = stream {
+            let _ = handle_connection(stream, &inner);
+        }
+    }
+}
+
+fn handle_connection(
+    mut stream: UnixStream,
+    inner: &Mutex<Inner>,
+) -> io::Result<()> {
+    // Just like bspwm, read the whole request at once
+    let mut buf = vec![0; BUFFER_SIZE];
+    let len = stream.read(&mut buf)?;
+
+    let args: Vec<String> = String::from_utf8_lossy(&buf[..len])
+        .split('\x00')
+        .filter(|arg| !arg.is_empty())
+        .map(ToString::to_string)
+        .collect();
+
+    if args.is_empty() {
+        return Ok(());
+    }
+
+    let mut inner = inner.lock().unwrap();
+    inner.requests.push(args.clone());
+
+    if args[0] == "subscribe" {
+        let mut subscriptions = Vec::new();
+        let mut remaining = None;
+        let mut fifo = false;
+        let mut iter = args[1..].iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-c" | "--count" => {
+                    remaining = iter.next().and_then(|x| x.parse().ok());
+                }
+                "-f" | "--fifo" => fifo = true,
+                _ => subscriptions.push(arg.clone()),
+            }
+        }
+
+        if subscriptions.is_empty() {
+            subscriptions.push("report".to_string());
+        }
+
+        let stream = if fifo {
+            let fifo_path = make_fifo()?;
+            inner.fifos.push(fifo_path.clone());
+
+            // Just like bspwm, open FIFO for both reading and writing, so it
+            // doesn't block until subscriber opens it
+            let file =
+                OpenOptions::new().read(true).write(true).open(&fifo_path)?;
+
+            writeln!(stream, "{}", fifo_path.display())?;
+            SubscriberStream::Fifo(file)
+        } else {
+            SubscriberStream::Socket(stream)
+        };
+
+        inner.subscribers.push(FakeSubscriber {
+            stream,
+            subscriptions,
+            remaining,
+        });
+
+        return Ok(());
+    }
+
+    let reply = inner.reply_to(&args);
+    drop(inner);
+
+    reply.write_to(&mut stream)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::errors::ReplyError;
+    use crate::events::{Event, NodeEvent, Subscription};
+
+    #[test]
+    fn scripted_reply() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let client = bspwm.client();
+        bspwm.on_request(
+            &["query", "--nodes"],
+            Reply::lines(["0x00400002", "0x00800002"]),
+        );
+
+        let nodes = client.query_nodes(None, None, None, None).unwrap();
+        assert_eq!(nodes, vec![0x00400002, 0x00800002]);
+
+        let reply = client.query_monitors(false, None, None, None, None);
+        assert!(matches!(reply, Err(ReplyError::RequestFailed(_))));
+
+        assert_eq!(
+            bspwm.requests(),
+            vec![vec!["query", "--nodes"], vec!["query", "--monitors"]]
+        );
+    }
+
+    #[test]
+    fn failure_reply() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.on_request(
+            &["query", "--nodes"],
+            Reply::failure("query -N: No matches.\n"),
+        );
+
+        match bspwm.client().query_nodes(None, None, None, None) {
+            Err(ReplyError::RequestFailed(message)) => {
+                assert_eq!(message, "query -N: No matches.");
+            }
+            reply => panic!("Unexpected reply: {reply:?}"),
+        }
+    }
+
+    #[test]
+    fn config() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let client = bspwm.client();
+        bspwm.set_config("border_width", "2");
+
+        assert_eq!(bspwm.config("border_width"), Some("2".to_string()));
+
+        client.set_split_ratio(0.6).unwrap();
+        assert_eq!(client.get_split_ratio().unwrap(), 0.6);
+
+        assert!(matches!(
+            client.get_gapless_monocle(),
+            Err(ReplyError::RequestFailed(_))
+        ));
+    }
+
+    #[test]
+    fn subscribe() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::NodeFocus])
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        bspwm.emit("node_add 0x00200002 0x00200007 0x00000000 0x00400002");
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+
+        let event = subscriber.events().next().unwrap().unwrap();
+
+        match event {
+            Event::NodeEvent(NodeEvent::NodeFocus(info)) => {
+                assert_eq!(info.node_id, 0x00400002);
+            }
+            event => panic!("Unexpected event: {event:?}"),
+        }
+
+        assert_eq!(bspwm.requests(), vec![vec!["subscribe", "node_focus"]]);
+    }
+
+    #[test]
+    fn subscribe_report() {
+        let bspwm = FakeBspwm::start().unwrap();
+        let mut subscriber = bspwm
+            .client()
+            .subscribe(false, None, &[Subscription::Report])
+            .unwrap();
+
+        assert!(bspwm.wait_for_subscribers(1, Duration::from_secs(5)));
+
+        bspwm.emit("node_focus 0x00200002 0x00200007 0x00400002");
+        bspwm.emit("WMeDP1:O1:f2:LT:TT:G");
+
+        match subscriber.events().next().unwrap().unwrap() {
+            Event::Report(report) => {
+                assert_eq!(report.monitors[0].desktops.len(), 2)
+            }
+            event => panic!("Unexpected event: {event:?}"),
+        }
+    }
+}
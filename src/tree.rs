@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::client::BspcClient;
 use crate::errors::ReplyError;
 use crate::properties::{Dir, Layer, Layout, Rectangle, SplitType, State};
+use crate::socket::Transport;
 use crate::Id;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -98,83 +100,403 @@ pub enum Tree {
     Monitor(Monitor),
 }
 
+/// Iterator over node and its descendants in pre-order, i.e. parents before
+/// children and first children before second ones.
+#[derive(Debug, Clone)]
+pub struct PreOrder<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> PreOrder<'a> {
+    fn new(root: Option<&'a Node>) -> Self {
+        Self {
+            stack: root.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> Iterator for PreOrder<'a> {
+    type Item = &'a Node;
+
+    fn next(&mut self) -> Option<&'a Node> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.second_child.as_deref());
+        self.stack.extend(node.first_child.as_deref());
+
+        Some(node)
+    }
+}
+
+/// Iterator over node and its descendants in post-order, i.e. children
+/// before parents and first children before second ones.
+#[derive(Debug, Clone)]
+pub struct PostOrder<'a> {
+    // Nodes are marked, when their children were already pushed
+    stack: Vec<(&'a Node, bool)>,
+}
+
+impl<'a> PostOrder<'a> {
+    fn new(root: Option<&'a Node>) -> Self {
+        Self {
+            stack: root.into_iter().map(|node| (node, false)).collect(),
+        }
+    }
+}
+
+impl<'a> Iterator for PostOrder<'a> {
+    type Item = &'a Node;
+
+    fn next(&mut self) -> Option<&'a Node> {
+        loop {
+            let (node, is_expanded) = self.stack.pop()?;
+
+            if is_expanded || node.is_leaf() {
+                return Some(node);
+            }
+
+            self.stack.push((node, true));
+
+            let children = [&node.second_child, &node.first_child];
+
+            for child in children.into_iter().flatten() {
+                self.stack.push((child, false));
+            }
+        }
+    }
+}
+
+impl Node {
+    /// Returns `true`, if node doesn't have children.
+    pub fn is_leaf(&self) -> bool {
+        self.first_child.is_none() && self.second_child.is_none()
+    }
+
+    /// Returns `true`, if node holds a window.
+    pub fn is_window(&self) -> bool {
+        self.client.is_some()
+    }
+
+    /// Returns children of the node: first, then second one.
+    pub fn children(&self) -> impl Iterator<Item = &Node> {
+        [&self.first_child, &self.second_child]
+            .into_iter()
+            .flat_map(|child| child.as_deref())
+    }
+
+    /// Returns iterator over this node and its descendants in pre-order.
+    pub fn pre_order(&self) -> PreOrder<'_> {
+        PreOrder::new(Some(self))
+    }
+
+    /// Returns iterator over this node and its descendants in post-order.
+    pub fn post_order(&self) -> PostOrder<'_> {
+        PostOrder::new(Some(self))
+    }
+
+    /// Returns leaves of the tree with this node as root, from left to
+    /// right.
+    pub fn leaves(&self) -> impl Iterator<Item = &Node> {
+        self.pre_order().filter(|node| node.is_leaf())
+    }
+
+    /// Returns nodes, that hold windows, in pre-order.
+    pub fn windows(&self) -> impl Iterator<Item = &Node> {
+        self.pre_order().filter(|node| node.is_window())
+    }
+
+    /// Returns this node or its descendant with the given id.
+    pub fn find(&self, id: Id) -> Option<&Node> {
+        self.pre_order().find(|node| node.id == id)
+    }
+
+    /// Returns nodes from this one to the node with the given id, including
+    /// both of them, or `None`, if there is no such node.
+    pub fn path_to(&self, id: Id) -> Option<Vec<&Node>> {
+        let mut path = Vec::new();
+
+        if self.collect_path(id, &mut path) {
+            path.reverse();
+            return Some(path);
+        }
+
+        None
+    }
+
+    /// Pushes nodes from the node with the given id up to this one.
+    fn collect_path<'a>(&'a self, id: Id, path: &mut Vec<&'a Node>) -> bool {
+        let is_found = self.id == id
+            || self.children().any(|child| child.collect_path(id, path));
+
+        if is_found {
+            path.push(self);
+        }
+
+        is_found
+    }
+
+    /// Returns parent of the descendant with the given id.
+    pub fn parent(&self, id: Id) -> Option<&Node> {
+        let path = self.path_to(id)?;
+        path.get(path.len().checked_sub(2)?).copied()
+    }
+
+    /// Returns sibling of the descendant with the given id, i.e. other child
+    /// of its parent.
+    pub fn sibling(&self, id: Id) -> Option<&Node> {
+        self.parent(id)?.children().find(|child| child.id != id)
+    }
+
+    /// Returns depth of the node with the given id, where this node has
+    /// depth 0.
+    pub fn depth(&self, id: Id) -> Option<usize> {
+        Some(self.path_to(id)?.len() - 1)
+    }
+}
+
+impl Desktop {
+    /// Returns iterator over nodes of this desktop in pre-order.
+    pub fn nodes(&self) -> PreOrder<'_> {
+        PreOrder::new(self.root.as_ref())
+    }
+
+    /// Returns iterator over nodes of this desktop in post-order.
+    pub fn nodes_post_order(&self) -> PostOrder<'_> {
+        PostOrder::new(self.root.as_ref())
+    }
+
+    /// Returns nodes of this desktop, that hold windows, in pre-order.
+    pub fn windows(&self) -> impl Iterator<Item = &Node> {
+        self.nodes().filter(|node| node.is_window())
+    }
+
+    /// Returns node of this desktop with the given id.
+    pub fn find_node(&self, id: Id) -> Option<&Node> {
+        self.root.as_ref()?.find(id)
+    }
+
+    /// Returns nodes from the root of this desktop to the node with the given
+    /// id, including both of them.
+    pub fn path_to(&self, id: Id) -> Option<Vec<&Node>> {
+        self.root.as_ref()?.path_to(id)
+    }
+
+    /// Returns parent of the node with the given id.
+    pub fn parent(&self, id: Id) -> Option<&Node> {
+        self.root.as_ref()?.parent(id)
+    }
+
+    /// Returns sibling of the node with the given id.
+    pub fn sibling(&self, id: Id) -> Option<&Node> {
+        self.root.as_ref()?.sibling(id)
+    }
+
+    /// Returns depth of the node with the given id, where root of this
+    /// desktop has depth 0.
+    pub fn depth(&self, id: Id) -> Option<usize> {
+        self.root.as_ref()?.depth(id)
+    }
+}
+
+impl Monitor {
+    /// Returns desktop of this monitor with the given id.
+    pub fn find_desktop(&self, id: Id) -> Option<&Desktop> {
+        self.desktops.iter().find(|desktop| desktop.id == id)
+    }
+
+    /// Returns node on this monitor with the given id.
+    pub fn find_node(&self, id: Id) -> Option<&Node> {
+        self.desktops
+            .iter()
+            .find_map(|desktop| desktop.find_node(id))
+    }
+
+    /// Returns nodes of this monitor, that hold windows, together with
+    /// desktops, that contain them.
+    pub fn windows(&self) -> impl Iterator<Item = (&Desktop, &Node)> {
+        self.desktops.iter().flat_map(|desktop| {
+            desktop.windows().map(move |node| (desktop, node))
+        })
+    }
+}
+
+impl<T: Transport> BspcClient<T> {
+    /// Converts id of the monitor to `Monitor` structure. Returns `None` if
+    /// there is no monitor with the given id.
+    pub fn from_id_to_monitor(
+        &self,
+        id: Id,
+    ) -> Result<Option<Monitor>, ReplyError> {
+        Ok(self.from_ids_to_monitors(&[id])?.remove(0))
+    }
+
+    /// Converts id of the desktop to `Desktop` structure. Returns `None` if
+    /// there is no desktop with the given id.
+    pub fn from_id_to_desktop(
+        &self,
+        id: Id,
+    ) -> Result<Option<Desktop>, ReplyError> {
+        Ok(self.from_ids_to_desktops(&[id])?.remove(0))
+    }
+
+    /// Converts id of the node to `Node` structure. Returns `None` if there
+    /// is no node with the given id.
+    pub fn from_id_to_node(&self, id: Id) -> Result<Option<Node>, ReplyError> {
+        Ok(self.from_ids_to_nodes(&[id])?.remove(0))
+    }
+
+    /// Converts ids of the monitors to `Monitor` structures, using one state
+    /// dump. Monitors are returned in the order of ids, with `None` for ids,
+    /// that don't exist.
+    pub fn from_ids_to_monitors(
+        &self,
+        ids: &[Id],
+    ) -> Result<Vec<Option<Monitor>>, ReplyError> {
+        let state = self.get_current_state()?;
+        Ok(ids
+            .iter()
+            .map(|&id| state.find_monitor(id).cloned())
+            .collect())
+    }
+
+    /// Converts ids of the desktops to `Desktop` structures, using one state
+    /// dump. Desktops are returned in the order of ids, with `None` for ids,
+    /// that don't exist.
+    pub fn from_ids_to_desktops(
+        &self,
+        ids: &[Id],
+    ) -> Result<Vec<Option<Desktop>>, ReplyError> {
+        let state = self.get_current_state()?;
+        Ok(ids
+            .iter()
+            .map(|&id| state.find_desktop(id).cloned())
+            .collect())
+    }
+
+    /// Converts ids of the nodes to `Node` structures, using one state dump.
+    /// Nodes are returned in the order of ids, with `None` for ids, that
+    /// don't exist.
+    pub fn from_ids_to_nodes(
+        &self,
+        ids: &[Id],
+    ) -> Result<Vec<Option<Node>>, ReplyError> {
+        let state = self.get_current_state()?;
+        Ok(ids.iter().map(|&id| state.find_node(id).cloned()).collect())
+    }
+}
+
 /// Converts id of the monitor to `Monitor` structure. Returns `None` if there
 /// is no monitor with the given id.
 pub fn from_id_to_monitor(id: Id) -> Result<Option<Monitor>, ReplyError> {
-    todo!();
+    BspcClient::new().from_id_to_monitor(id)
 }
 
 /// Converts id of the desktop to `Desktop` structure. Returns `None` if there
 /// is no desktop with the given id.
 pub fn from_id_to_desktop(id: Id) -> Result<Option<Desktop>, ReplyError> {
-    todo!();
+    BspcClient::new().from_id_to_desktop(id)
 }
 
 /// Converts id of the node to `Node` structure. Returns `None` if there
 /// is no node with the given id.
 pub fn from_id_to_node(id: Id) -> Result<Option<Node>, ReplyError> {
-    todo!();
-
-    // let tree_raw = self.query_tree(QueryOptions::Monitor)?;
-    // let tree = if let Tree::Monitor(mon) = tree_raw {
-    //     mon
-    // } else {
-    //     unreachable!();
-    // };
-
-    // for desktop in tree.desktops {
-    //     let root = desktop.root;
-
-    //     if let Some(root) = root {
-    //         while let None = root.client {
-    //             if let Some(first_child) = root.first_child {
-    //                 if first_child.id == id {
-    //                     return Ok(Some(*first_child));
-    //                 }
-    //             }
-
-    //             if let Some(second_child) = root.second_child {
-    //                 if second_child.id == id {
-    //                     return Ok(Some(*second_child));
-    //                 }
-    //             }
-    //         }
-    //     }
-    // }
-
-    // Ok(None)
+    BspcClient::new().from_id_to_node(id)
+}
+
+/// Converts ids of the monitors to `Monitor` structures, using one state
+/// dump. Monitors are returned in the order of ids, with `None` for ids, that
+/// don't exist.
+pub fn from_ids_to_monitors(
+    ids: &[Id],
+) -> Result<Vec<Option<Monitor>>, ReplyError> {
+    BspcClient::new().from_ids_to_monitors(ids)
+}
+
+/// Converts ids of the desktops to `Desktop` structures, using one state
+/// dump. Desktops are returned in the order of ids, with `None` for ids, that
+/// don't exist.
+pub fn from_ids_to_desktops(
+    ids: &[Id],
+) -> Result<Vec<Option<Desktop>>, ReplyError> {
+    BspcClient::new().from_ids_to_desktops(ids)
+}
+
+/// Converts ids of the nodes to `Node` structures, using one state dump.
+/// Nodes are returned in the order of ids, with `None` for ids, that don't
+/// exist.
+pub fn from_ids_to_nodes(ids: &[Id]) -> Result<Vec<Option<Node>>, ReplyError> {
+    BspcClient::new().from_ids_to_nodes(ids)
 }
 
 #[cfg(test)]
 mod test {
-    use std::process::Command;
-    use std::thread;
-    use std::time::Duration;
-
     use super::*;
-    use crate::events::*;
-    use crate::query;
     use crate::selectors::NodeSelector;
+    use crate::state::State;
+    use crate::testing::{FakeBspwm, Reply, STATE_DUMP};
+
+    #[test]
+    fn parse_client_state() {
+        use crate::properties::State as ClientState;
+
+        let state: State = serde_json::from_str(STATE_DUMP).unwrap();
+        let client = state.find_node(0x00A00002).unwrap().client.as_ref();
+
+        // bspwm writes states in snake case, not camel case
+        assert_eq!(client.unwrap().state, ClientState::PseudoTiled);
+        assert_eq!(
+            serde_json::to_string(&ClientState::PseudoTiled).unwrap(),
+            "\"pseudo_tiled\""
+        );
+        assert!(serde_json::from_str::<ClientState>("\"pseudoTiled\"").is_err());
+    }
 
     #[test]
     fn parse_tree() {
-        let monitor = Command::new("bspc")
-            .arg("query")
-            .arg("-T")
-            .arg("-m")
-            .output()
-            .unwrap();
-        let monitor = std::str::from_utf8(&monitor.stdout).unwrap();
+        let state: serde_json::Value =
+            serde_json::from_str(STATE_DUMP).unwrap();
+        let monitor = state["monitors"][0].to_string();
 
-        if monitor.len() > 1 {
-            let tree: Monitor = serde_json::from_str(monitor).unwrap();
-            println!("{:#?}", tree);
-        }
+        let tree: Monitor = serde_json::from_str(&monitor).unwrap();
+        let desktop = &tree.desktops[0];
+        let root = desktop.root.as_ref().unwrap();
+
+        assert_eq!(tree.name, "eDP1");
+        assert_eq!(desktop.name, "1");
+        assert!(root.client.is_none());
+        assert_eq!(
+            root.first_child
+                .as_ref()
+                .unwrap()
+                .client
+                .as_ref()
+                .unwrap()
+                .class_name,
+            "Alacritty"
+        );
+    }
+
+    #[test]
+    fn parse_tree_from_state() {
+        let state: State = serde_json::from_str(STATE_DUMP).unwrap();
+        let names: Vec<_> = state.monitors[0]
+            .desktops
+            .iter()
+            .map(|desktop| desktop.name.as_str())
+            .collect();
+
+        assert_eq!(names, ["1", "2 web", "3"]);
+        assert!(state.monitors[0].desktops[2].root.is_none());
     }
 
     #[test]
     fn test_from_id_to_node() {
-        let window_id = query::query_nodes(
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.on_request(
+            &["query", "--nodes", "--node", ".fullscreen"],
+            Reply::lines(["0x00C00002"]),
+        );
+
+        let window_id = bspwm.client().query_nodes(
             None,
             None,
             None,
@@ -184,7 +506,7 @@ mod test {
         match window_id {
             Ok(_) => {}
             Err(ReplyError::RequestFailed(reply)) => {
-                if reply.len() > 0 {
+                if !reply.is_empty() {
                     panic!("{}", reply);
                 }
             }
@@ -193,4 +515,114 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn find_by_id() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.set_state(STATE_DUMP);
+        let client = bspwm.client();
+
+        // Node is nested two levels deep under the root
+        let node = client.from_id_to_node(0x00A00002).unwrap().unwrap();
+        assert_eq!(node.client.unwrap().class_name, "Zathura");
+
+        let desktop = client.from_id_to_desktop(0x0020000B).unwrap().unwrap();
+        assert_eq!(desktop.name, "4");
+
+        let monitor = client.from_id_to_monitor(0x0020000A).unwrap().unwrap();
+        assert_eq!(monitor.name, "HDMI1");
+
+        assert!(client.from_id_to_node(0x12345678).unwrap().is_none());
+        assert!(client.from_id_to_desktop(0x00200002).unwrap().is_none());
+    }
+
+    #[test]
+    fn find_many_by_id() {
+        let bspwm = FakeBspwm::start().unwrap();
+        bspwm.set_state(STATE_DUMP);
+
+        let nodes = bspwm
+            .client()
+            .from_ids_to_nodes(&[0x00E00002, 0x12345678, 0x00600002])
+            .unwrap();
+        let ids: Vec<_> =
+            nodes.iter().map(|x| x.as_ref().map(|x| x.id)).collect();
+
+        assert_eq!(ids, [Some(0x00E00002), None, Some(0x00600002)]);
+        assert_eq!(bspwm.requests(), vec![vec!["wm", "-d"]]);
+    }
+
+    fn desktop() -> Desktop {
+        let state: State = serde_json::from_str(STATE_DUMP).unwrap();
+        state.monitors[0].desktops[0].clone()
+    }
+
+    fn ids<'a>(nodes: impl Iterator<Item = &'a Node>) -> Vec<Id> {
+        nodes.map(|node| node.id).collect()
+    }
+
+    #[test]
+    fn traverse_tree() {
+        let desktop = desktop();
+        let root = desktop.root.as_ref().unwrap();
+
+        assert_eq!(
+            ids(root.pre_order()),
+            [0x00600001, 0x00400002, 0x00600002, 0x00800002, 0x00A00002]
+        );
+        assert_eq!(
+            ids(desktop.nodes_post_order()),
+            [0x00400002, 0x00800002, 0x00A00002, 0x00600002, 0x00600001]
+        );
+        assert_eq!(ids(root.leaves()), [0x00400002, 0x00800002, 0x00A00002]);
+        assert_eq!(ids(desktop.windows()), ids(root.leaves()));
+        assert_eq!(ids(root.children()), [0x00400002, 0x00600002]);
+    }
+
+    #[test]
+    fn navigate_tree() {
+        let desktop = desktop();
+
+        assert_eq!(
+            ids(desktop.path_to(0x00A00002).unwrap().into_iter()),
+            [0x00600001, 0x00600002, 0x00A00002]
+        );
+        assert_eq!(desktop.depth(0x00A00002), Some(2));
+        assert_eq!(desktop.depth(0x00600001), Some(0));
+        assert_eq!(desktop.parent(0x00A00002).unwrap().id, 0x00600002);
+        assert_eq!(desktop.sibling(0x00A00002).unwrap().id, 0x00800002);
+        assert_eq!(desktop.sibling(0x00600002).unwrap().id, 0x00400002);
+
+        assert!(desktop.parent(0x00600001).is_none());
+        assert!(desktop.sibling(0x00600001).is_none());
+        assert!(desktop.path_to(0x12345678).is_none());
+    }
+
+    #[test]
+    fn windows_of_state() {
+        let state: State = serde_json::from_str(STATE_DUMP).unwrap();
+        let windows: Vec<_> = state
+            .windows()
+            .map(|(monitor, desktop, node)| {
+                (
+                    monitor.name.as_str(),
+                    desktop.name.as_str(),
+                    node.client.as_ref().unwrap().class_name.as_str(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            windows,
+            [
+                ("eDP1", "1", "Alacritty"),
+                ("eDP1", "1", "firefox"),
+                ("eDP1", "1", "Zathura"),
+                ("eDP1", "2 web", "TelegramDesktop"),
+                ("HDMI1", "4", "Spotify"),
+            ]
+        );
+        assert_eq!(state.desktops().count(), 4);
+        assert_eq!(state.windows().count(), state.clients_count as usize);
+    }
 }